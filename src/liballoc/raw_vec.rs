@@ -716,6 +716,7 @@ impl<T, A: Alloc> RawVec<T, A> {
 
 unsafe impl<#[may_dangle] T, A: Alloc> Drop for RawVec<T, A> {
     /// Frees the memory owned by the RawVec *without* trying to Drop its contents.
+    #[cfg_attr(not(bootstrap), rustc_const_heap = "raw_vec_drop")]
     fn drop(&mut self) {
         unsafe { self.dealloc_buffer(); }
     }
@@ -700,3 +700,165 @@ fn test_split_off_large_random_sorted() {
     assert!(map.into_iter().eq(data.clone().into_iter().filter(|x| x.0 < key)));
     assert!(right.into_iter().eq(data.into_iter().filter(|x| x.0 >= key)));
 }
+
+#[test]
+fn test_first_last_entry() {
+    let mut a = BTreeMap::new();
+    assert!(a.first_key_value().is_none());
+    assert!(a.last_key_value().is_none());
+    assert!(a.first_entry().is_none());
+    assert!(a.last_entry().is_none());
+    a.insert(1, 42);
+    assert_eq!(a.first_key_value(), Some((&1, &42)));
+    assert_eq!(a.last_key_value(), Some((&1, &42)));
+    assert_eq!(a.first_entry().unwrap().key(), &1);
+    assert_eq!(a.last_entry().unwrap().key(), &1);
+    a.insert(2, 24);
+    assert_eq!(a.first_key_value(), Some((&1, &42)));
+    assert_eq!(a.last_key_value(), Some((&2, &24)));
+    assert_eq!(a.first_entry().unwrap().key(), &1);
+    assert_eq!(a.last_entry().unwrap().key(), &2);
+    *a.last_entry().unwrap().get_mut() += 1;
+    assert_eq!(a[&2], 25);
+    a.insert(0, 6);
+    assert_eq!(a.first_key_value(), Some((&0, &6)));
+    assert_eq!(a.last_key_value(), Some((&2, &25)));
+    let (k1, v1) = a.first_entry().unwrap().remove_entry();
+    assert_eq!(k1, 0);
+    assert_eq!(v1, 6);
+    let (k2, v2) = a.last_entry().unwrap().remove_entry();
+    assert_eq!(k2, 2);
+    assert_eq!(v2, 25);
+    assert_eq!(a.first_key_value(), Some((&1, &42)));
+    assert_eq!(a.last_key_value(), Some((&1, &42)));
+    assert_eq!(a.len(), 1);
+}
+
+#[test]
+fn test_pop_first_last() {
+    #[cfg(not(miri))] // Miri is too slow
+    let size = 1000;
+    #[cfg(miri)]
+    let size = 100;
+
+    let mut map: BTreeMap<_, _> = (0..size).map(|i| (i, i * 10)).collect();
+    for i in 0..size / 2 {
+        assert_eq!(map.pop_first(), Some((i, i * 10)));
+        assert_eq!(map.pop_last(), Some((size - i - 1, (size - i - 1) * 10)));
+        assert_eq!(map.len(), size - 2 * i - 2);
+        assert!(map.iter().map(|(&k, _)| k).eq(i + 1..size - i - 1));
+    }
+    assert_eq!(map.pop_first(), None);
+    assert_eq!(map.pop_last(), None);
+    assert!(map.is_empty());
+}
+
+#[test]
+fn test_drain_filter_empty() {
+    let mut map: BTreeMap<i32, i32> = BTreeMap::new();
+    {
+        let mut iter = map.drain_filter(|_, _| true);
+        assert_eq!(iter.size_hint(), (0, Some(0)));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.size_hint(), (0, Some(0)));
+        assert_eq!(iter.next(), None);
+    }
+    assert!(map.is_empty());
+}
+
+#[test]
+fn test_drain_filter_consuming_nothing() {
+    let pairs = (0..3).map(|i| (i, i));
+    let mut map: BTreeMap<_, _> = pairs.collect();
+    assert!(map.drain_filter(|_, _| false).eq(std::iter::empty()));
+    assert_eq!(map.len(), 3);
+}
+
+#[test]
+fn test_drain_filter_consuming_all() {
+    let pairs = (0..3).map(|i| (i, i));
+    let mut map: BTreeMap<_, _> = pairs.clone().collect();
+    assert!(map.drain_filter(|_, _| true).eq(pairs));
+    assert!(map.is_empty());
+}
+
+#[test]
+fn test_drain_filter_mutating_and_keeping() {
+    let pairs = (0..3).map(|i| (i, i));
+    let mut map: BTreeMap<_, _> = pairs.collect();
+    assert!(map.drain_filter(|_, v| {
+        *v += 6;
+        false
+    }).eq(std::iter::empty()));
+    assert!(map.keys().copied().eq(0..3));
+    assert!(map.values().copied().eq(6..9));
+}
+
+#[test]
+fn test_drain_filter_mutating_and_removing() {
+    let pairs = (0..3).map(|i| (i, i));
+    let mut map: BTreeMap<_, _> = pairs.collect();
+    assert!(map.drain_filter(|_, v| {
+        *v += 6;
+        true
+    }).eq((0..3).map(|i| (i, i + 6))));
+    assert!(map.is_empty());
+}
+
+#[test]
+fn test_drain_filter_underfull_nodes() {
+    // Removing every other element, and then runs of elements, exercises
+    // merging and stealing at the leaf level as well as higher up the tree.
+    #[cfg(not(miri))] // Miri is too slow
+    let size = 1000;
+    #[cfg(miri)]
+    let size = 100;
+
+    for &(modulus, keep) in &[(2, 0), (2, 1), (3, 0), (7, 3), (10, 9)] {
+        let mut map: BTreeMap<_, _> = (0..size).map(|i| (i, i)).collect();
+        let drained: Vec<_> = map.drain_filter(|k, _| k % modulus != keep).collect();
+        assert!(drained.into_iter().eq((0..size).filter(|i| i % modulus != keep).map(|i| (i, i))));
+        assert!(map.into_iter().eq((0..size).filter(|i| i % modulus == keep).map(|i| (i, i))));
+    }
+
+    for &run in &[5, 11, 50] {
+        let mut map: BTreeMap<_, _> = (0..size).map(|i| (i, i)).collect();
+        map.drain_filter(|k, _| (k / run) % 2 == 0);
+        assert!(map.keys().copied().eq((0..size).filter(|i| (i / run) % 2 != 0)));
+        assert_eq!(map.len(), (0..size).filter(|i| (i / run) % 2 != 0).count());
+    }
+}
+
+#[test]
+fn test_drain_filter_drop_unconsumed() {
+    let mut map: BTreeMap<_, _> = (0..100).map(|i| (i, i)).collect();
+    {
+        let mut iter = map.drain_filter(|k, _| k % 3 == 0);
+        assert_eq!(iter.next(), Some((0, 0)));
+        assert_eq!(iter.next(), Some((3, 3)));
+    }
+    assert!(map.keys().copied().eq((0..100).filter(|i| i % 3 != 0)));
+}
+
+#[test]
+fn test_drain_filter_debug() {
+    let mut map: BTreeMap<_, _> = (0..4).map(|i| (i, i * 2)).collect();
+    let mut iter = map.drain_filter(|k, _| k % 2 == 1);
+    assert_eq!(format!("{:?}", iter), "DrainFilter(Some((0, 0)))");
+    assert_eq!(iter.next(), Some((1, 2)));
+    assert_eq!(format!("{:?}", iter), "DrainFilter(Some((2, 4)))");
+    assert_eq!(iter.next(), Some((3, 6)));
+    assert_eq!(iter.next(), None);
+    assert_eq!(format!("{:?}", iter), "DrainFilter(None)");
+}
+
+#[test]
+fn test_retain() {
+    let mut map: BTreeMap<i32, i32> = (0..100).map(|x| (x, x * 10)).collect();
+
+    map.retain(|&k, _| k % 2 == 0);
+    assert_eq!(map.len(), 50);
+    assert_eq!(map[&2], 20);
+    assert_eq!(map[&4], 40);
+    assert_eq!(map[&6], 60);
+}
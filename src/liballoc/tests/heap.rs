@@ -1,4 +1,6 @@
-use std::alloc::{Global, Alloc, Layout, System};
+use std::alloc::{Global, Alloc, AllocErr, Layout, System};
+use std::cell::Cell;
+use std::ptr::NonNull;
 
 /// Issue #45955 and #62251.
 #[test]
@@ -32,3 +34,109 @@ fn check_overalign_requests<T: Alloc>(mut allocator: T) {
         }
     }
 }
+
+thread_local! {
+    static ALLOCS: Cell<usize> = Cell::new(0);
+    static REALLOCS: Cell<usize> = Cell::new(0);
+    static DEALLOCS: Cell<usize> = Cell::new(0);
+}
+
+fn bump(counter: &'static std::thread::LocalKey<Cell<usize>>) {
+    counter.with(|c| c.set(c.get() + 1));
+}
+
+/// Forwards to `Global` and counts the calls made through it on the
+/// current thread, as `(allocs, reallocs, deallocs)`.
+#[derive(Copy, Clone, Default)]
+pub struct Counting;
+
+impl Counting {
+    pub fn counts() -> (usize, usize, usize) {
+        (ALLOCS.with(Cell::get), REALLOCS.with(Cell::get), DEALLOCS.with(Cell::get))
+    }
+}
+
+unsafe impl Alloc for Counting {
+    unsafe fn alloc(&mut self, layout: Layout) -> Result<NonNull<u8>, AllocErr> {
+        bump(&ALLOCS);
+        Global.alloc(layout)
+    }
+
+    unsafe fn dealloc(&mut self, ptr: NonNull<u8>, layout: Layout) {
+        bump(&DEALLOCS);
+        Global.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&mut self,
+                      ptr: NonNull<u8>,
+                      layout: Layout,
+                      new_size: usize) -> Result<NonNull<u8>, AllocErr> {
+        bump(&REALLOCS);
+        Global.realloc(ptr, layout, new_size)
+    }
+}
+
+#[test]
+fn box_new_in_uses_allocator() {
+    let b = Box::new_in(5u32, Counting);
+    assert_eq!(*b, 5);
+    assert_eq!(Counting::counts(), (1, 0, 0));
+    drop(b);
+    assert_eq!(Counting::counts(), (1, 0, 1));
+}
+
+#[test]
+fn box_new_in_zst() {
+    let b = Box::new_in((), Counting);
+    drop(b);
+    assert_eq!(Counting::counts(), (0, 0, 0));
+}
+
+#[test]
+fn box_new_in_unsize() {
+    let b: Box<[u8], Counting> = Box::new_in([1, 2, 3], Counting);
+    assert_eq!(&*b, &[1, 2, 3][..]);
+    let d: Box<dyn std::fmt::Debug, Counting> = Box::new_in(String::from("hi"), Counting);
+    assert_eq!(format!("{:?}", d), "\"hi\"");
+    drop(b);
+    drop(d);
+    // `String`'s own buffer goes through `Global`, only the boxes count.
+    assert_eq!(Counting::counts(), (2, 0, 2));
+}
+
+/// An allocator with state, which `Box` cannot store.
+struct Stateful(usize);
+
+unsafe impl Alloc for Stateful {
+    unsafe fn alloc(&mut self, layout: Layout) -> Result<NonNull<u8>, AllocErr> {
+        Global.alloc(layout)
+    }
+
+    unsafe fn dealloc(&mut self, ptr: NonNull<u8>, layout: Layout) {
+        Global.dealloc(ptr, layout)
+    }
+}
+
+#[test]
+#[should_panic(expected = "`Box` only supports zero-sized allocators")]
+fn box_new_in_stateful() {
+    let _b = Box::new_in(5u32, Stateful(0));
+}
+
+#[test]
+#[should_panic(expected = "`Box` only supports zero-sized allocators")]
+fn box_from_raw_in_stateful() {
+    let ptr = Box::into_raw(Box::new(5u32));
+    let _b = unsafe { Box::from_raw_in(ptr, Stateful(0)) };
+}
+
+#[test]
+fn box_into_raw_with_alloc() {
+    let b = Box::new_in(7u64, Counting);
+    let (ptr, a) = Box::into_raw_with_alloc(b);
+    assert_eq!(Counting::counts(), (1, 0, 0));
+    let b = unsafe { Box::from_raw_in(ptr, a) };
+    assert_eq!(*b, 7);
+    drop(b);
+    assert_eq!(Counting::counts(), (1, 0, 1));
+}
@@ -1,7 +1,10 @@
 #![feature(allocator_api)]
 #![feature(box_syntax)]
+#![feature(btree_drain_filter)]
+#![feature(btree_retain)]
 #![feature(drain_filter)]
 #![feature(exact_size_is_empty)]
+#![feature(map_first_last)]
 #![feature(option_flattening)]
 #![feature(pattern)]
 #![feature(repeat_generic_slice)]
@@ -1281,3 +1281,81 @@ fn test_stable_push_pop() {
     v.pop().unwrap();
     assert_eq!(*v0, 13);
 }
+
+#[test]
+fn test_new_in() {
+    use std::alloc::System;
+
+    let mut v: Vec<u32, _> = Vec::new_in(System);
+    assert_eq!(v.capacity(), 0);
+    v.push(1);
+    v.extend(&[2, 3]);
+    v.extend(vec![4, 5]);
+    assert_eq!(v, [1, 2, 3, 4, 5]);
+    assert_eq!(v, vec![1, 2, 3, 4, 5]);
+
+    let w = v.clone();
+    assert_eq!(w, v);
+    assert_eq!(v.into_iter().rev().collect::<Vec<_>>(), [5, 4, 3, 2, 1]);
+}
+
+#[test]
+fn test_with_capacity_in() {
+    use std::alloc::System;
+
+    let mut v = Vec::with_capacity_in(4, System);
+    assert_eq!(v.capacity(), 4);
+    v.extend(0..8);
+    assert_eq!(v.len(), 8);
+
+    let tail = v.split_off(5);
+    assert_eq!(tail, [5, 6, 7]);
+    assert_eq!(v.drain(1..3).collect::<Vec<_>>(), [1, 2]);
+    assert_eq!(v, [0, 3, 4]);
+    v.retain(|&x| x != 3);
+    assert_eq!(v, [0, 4]);
+}
+
+#[test]
+fn test_into_iter_in_drop() {
+    use std::alloc::System;
+
+    let mut count = 0;
+    {
+        let mut v = Vec::new_in(System);
+        v.push(DropCounter { count: &mut count });
+        let mut it = v.into_iter();
+        assert!(it.next().is_some());
+    }
+    assert_eq!(count, 1);
+}
+
+#[test]
+fn test_with_capacity_in_counting() {
+    use crate::heap::Counting;
+
+    let mut v = Vec::with_capacity_in(2, Counting);
+    assert_eq!(Counting::counts(), (1, 0, 0));
+    v.push(1u32);
+    v.push(2);
+    assert_eq!(Counting::counts(), (1, 0, 0));
+    v.push(3);
+    assert!(v.capacity() >= 3);
+    assert_eq!(Counting::counts(), (1, 1, 0));
+    drop(v);
+    assert_eq!(Counting::counts(), (1, 1, 1));
+}
+
+#[test]
+fn test_new_in_counting() {
+    use crate::heap::Counting;
+
+    let mut v = Vec::new_in(Counting);
+    assert_eq!(Counting::counts(), (0, 0, 0));
+    v.extend(0..100u8);
+    let (allocs, _, deallocs) = Counting::counts();
+    assert_eq!((allocs, deallocs), (1, 0));
+    let it = v.into_iter();
+    drop(it);
+    assert_eq!(Counting::counts().2, 1);
+}
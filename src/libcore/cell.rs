@@ -191,6 +191,11 @@ use crate::mem;
 use crate::ops::{Deref, DerefMut, CoerceUnsized};
 use crate::ptr;
 
+mod once;
+
+#[unstable(feature = "once_cell", issue = "0")]
+pub use self::once::{Lazy, OnceCell};
+
 /// A mutable memory location.
 ///
 /// # Examples
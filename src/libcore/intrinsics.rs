@@ -713,6 +713,22 @@ extern "rust-intrinsic" {
     /// Moves a value out of scope without running drop glue.
     pub fn forget<T: ?Sized>(_: T);
 
+    /// Allocates a block of memory during compile-time evaluation.
+    ///
+    /// At runtime this returns a null pointer; it is only meant to be used
+    /// from code that runs in a const context. Memory allocated this way must
+    /// either be freed with `const_deallocate` before evaluation finishes, or
+    /// only be reachable through shared references in the final value, in
+    /// which case it is interned as immutable memory.
+    #[cfg(not(bootstrap))]
+    pub fn const_allocate(size: usize, align: usize) -> *mut u8;
+
+    /// Deallocates memory that was allocated with `const_allocate`.
+    ///
+    /// At runtime this does nothing.
+    #[cfg(not(bootstrap))]
+    pub fn const_deallocate(ptr: *mut u8, size: usize, align: usize);
+
     /// Reinterprets the bits of a value of one type as another type.
     ///
     /// Both types must have the same size. Neither the original, nor the result,
@@ -363,3 +363,73 @@ fn refcell_replace_borrows() {
     let _b = x.borrow();
     x.replace(1);
 }
+
+#[test]
+fn once_cell() {
+    let c = OnceCell::new();
+    assert!(c.get().is_none());
+    c.get_or_init(|| 92);
+    assert_eq!(c.get(), Some(&92));
+
+    c.get_or_init(|| panic!("Kabom!"));
+    assert_eq!(c.get(), Some(&92));
+    assert_eq!(c.set(62), Err(62));
+}
+
+#[test]
+fn once_cell_get_mut_and_take() {
+    let mut c = OnceCell::new();
+    assert!(c.get_mut().is_none());
+    c.set(90).unwrap();
+    *c.get_mut().unwrap() += 2;
+    assert_eq!(c.get(), Some(&92));
+    assert_eq!(c.take(), Some(92));
+    assert!(c.get().is_none());
+    assert_eq!(c.into_inner(), None);
+}
+
+#[test]
+fn once_cell_get_or_try_init() {
+    let c = OnceCell::new();
+    assert_eq!(c.get_or_try_init(|| Err::<i32, ()>(())), Err(()));
+    assert!(c.get().is_none());
+    assert_eq!(c.get_or_try_init(|| Ok::<i32, ()>(92)), Ok(&92));
+    assert_eq!(c.get_or_try_init(|| Err::<i32, ()>(())), Ok(&92));
+}
+
+#[test]
+#[should_panic(expected = "reentrant init")]
+fn once_cell_reentrant_init() {
+    let c: OnceCell<Box<i32>> = OnceCell::new();
+    c.get_or_init(|| {
+        c.get_or_init(|| Box::new(1));
+        Box::new(2)
+    });
+}
+
+#[test]
+fn lazy_new() {
+    let called = Cell::new(0);
+    let x = Lazy::new(|| {
+        called.set(called.get() + 1);
+        92
+    });
+    assert_eq!(called.get(), 0);
+
+    let y = *x - 30;
+    assert_eq!(y, 62);
+    assert_eq!(called.get(), 1);
+
+    let y = *x - 30;
+    assert_eq!(y, 62);
+    assert_eq!(called.get(), 1);
+}
+
+#[test]
+fn lazy_poisoned() {
+    let x: Lazy<String> = Lazy::new(|| panic!("kaboom"));
+    for _ in 0..2 {
+        let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| x.len()));
+        assert!(res.is_err());
+    }
+}
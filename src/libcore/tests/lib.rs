@@ -33,6 +33,7 @@
 #![feature(const_fn)]
 #![feature(iter_partition_in_place)]
 #![feature(iter_is_partitioned)]
+#![feature(once_cell)]
 #![warn(rust_2018_idioms)]
 
 extern crate test;
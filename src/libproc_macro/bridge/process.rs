@@ -0,0 +1,511 @@
+//! Running proc macros in a separate *host* process.
+//!
+//! Instead of loading proc macro dylibs into the server and running them via
+//! `client::Client`, the server can spawn a host process which loads them.
+//! The two sides then exchange the same buffers the in-process bridge uses,
+//! framed and sent over the host's stdin and stdout. A proc macro crashing
+//! or aborting then only takes down the host, and the host may be built with
+//! a different compiler than the server, as long as both agree on
+//! `PROTOCOL_VERSION` and the bridge API (see `api_fingerprint`).
+//!
+//! Every frame is a one byte `Tag`, followed by the length of the payload
+//! as a little-endian `u32`, and the payload itself. Strings and lists in
+//! the messages below are also prefixed with their length as a `u32`, so
+//! that the encoding doesn't depend on the pointer width of either side.
+//! The conversation is:
+//! * both sides start by sending `Hello`, with the protocol version
+//!   and the API fingerprint, and check the other side's `Hello`
+//! * the server sends `Load`, naming a dylib and its declarations symbol,
+//!   to which the host answers with `Macros` or `Error`
+//! * the server sends `Expand`, naming a proc macro and containing its input,
+//!   to which the host answers with any number of `Request`s (each answered
+//!   by the server with a `Response`), followed by `Done` or `Error`
+//!
+//! While handling a `Request`, the server may itself send `Load` and `Expand`
+//! frames (e.g. for `TokenStream::expand_expr`), which are answered as usual
+//! before the host goes back to waiting for the `Response`.
+
+use super::*;
+
+use std::collections::HashMap;
+use std::io::{self, BufReader, Read};
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::str;
+use std::sync::{Mutex, MutexGuard};
+
+/// Version of the framing and of the messages described in the module
+/// documentation. Changes to the bridge API itself are caught by
+/// `api_fingerprint` instead, so this only needs bumping when this
+/// module changes.
+pub const PROTOCOL_VERSION: u32 = 2;
+
+/// Frames larger than this are refused, instead of trusting the other side
+/// with how much memory to allocate.
+const MAX_FRAME_LEN: u32 = 1 << 30;
+
+/// Hash of the `with_api!` method list, so that a host built from a `proc_macro`
+/// with a different bridge API is refused instead of misinterpreted.
+fn api_fingerprint() -> u64 {
+    // FNV-1a, as `DefaultHasher` doesn't promise the same result across versions.
+    let mut hash = 0xcbf2_9ce4_8422_2325u64;
+    for &byte in with_api!(self, self, stringify).as_bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+#[repr(u8)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub(super) enum Tag {
+    Hello,
+    Load,
+    Macros,
+    Expand,
+    Request,
+    Response,
+    Done,
+    Error,
+}
+
+impl Tag {
+    fn from_u8(tag: u8) -> Option<Tag> {
+        Some(match tag {
+            0 => Tag::Hello,
+            1 => Tag::Load,
+            2 => Tag::Macros,
+            3 => Tag::Expand,
+            4 => Tag::Request,
+            5 => Tag::Response,
+            6 => Tag::Done,
+            7 => Tag::Error,
+            _ => return None,
+        })
+    }
+}
+
+fn invalid_data(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn write_frame(w: &mut impl io::Write, tag: Tag, payload: &[u8]) -> io::Result<()> {
+    if payload.len() > MAX_FRAME_LEN as usize {
+        return Err(invalid_data(format!("{:?} frame too large", tag)));
+    }
+    w.write_all(&[tag as u8])?;
+    w.write_all(&(payload.len() as u32).to_le_bytes())?;
+    w.write_all(payload)?;
+    w.flush()
+}
+
+fn read_frame(r: &mut impl Read) -> io::Result<(Tag, Buffer<u8>)> {
+    let mut header = [0; 5];
+    r.read_exact(&mut header)?;
+    let tag = Tag::from_u8(header[0])
+        .ok_or_else(|| invalid_data(format!("unknown frame tag {}", header[0])))?;
+    let mut len = [0; 4];
+    len.copy_from_slice(&header[1..]);
+    let len = u32::from_le_bytes(len);
+    if len > MAX_FRAME_LEN {
+        return Err(invalid_data(format!("{:?} frame too large ({} bytes)", tag, len)));
+    }
+    // Let the buffer grow as the payload arrives, rather than allocating all
+    // of it upfront for a frame that may turn out to be truncated.
+    let mut payload = Vec::new();
+    r.take(u64::from(len)).read_to_end(&mut payload)?;
+    if payload.len() != len as usize {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok((tag, payload.into()))
+}
+
+// Bounds-checked decoding of the messages in this module. Unlike the rest of
+// the bridge, they come from another process, so they can't be assumed to be
+// well-formed.
+
+fn read_bytes<'a>(r: &mut Reader<'a>, len: usize) -> io::Result<&'a [u8]> {
+    if r.len() < len {
+        return Err(invalid_data("truncated message".to_string()));
+    }
+    let (bytes, rest) = r.split_at(len);
+    *r = rest;
+    Ok(bytes)
+}
+
+fn read_u8(r: &mut Reader<'_>) -> io::Result<u8> {
+    Ok(read_bytes(r, 1)?[0])
+}
+
+fn read_u32(r: &mut Reader<'_>) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    bytes.copy_from_slice(read_bytes(r, 4)?);
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64(r: &mut Reader<'_>) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(read_bytes(r, 8)?);
+    Ok(u64::from_le_bytes(bytes))
+}
+
+pub(super) fn read_str<'a>(r: &mut Reader<'a>) -> io::Result<&'a str> {
+    let len = read_u32(r)? as usize;
+    str::from_utf8(read_bytes(r, len)?).map_err(|e| invalid_data(e.to_string()))
+}
+
+/// Encodes the length of `s` as a `u32`. Longer strings don't fit in a frame
+/// anyway, which `write_frame` catches.
+fn write_str(b: &mut Buffer<u8>, s: &str) {
+    (s.len() as u32).encode(b, &mut ());
+    b.extend_from_slice(s.as_bytes());
+}
+
+fn encode_str(s: &str) -> Buffer<u8> {
+    let mut b = Buffer::new();
+    write_str(&mut b, s);
+    b
+}
+
+/// Runs `f`, which decodes a bridge buffer received from the other side.
+/// The bridge's own decoding panics on malformed input, as it normally only
+/// talks to the same `proc_macro`, so the panic is turned into an error.
+pub(super) fn decode_bridge<T>(f: impl FnOnce() -> T) -> io::Result<T> {
+    panic::catch_unwind(panic::AssertUnwindSafe(f))
+        .map_err(|_| invalid_data("malformed bridge message".to_string()))
+}
+
+/// Exchanges `Hello` frames, returning an error if the other side is incompatible.
+fn handshake(r: &mut impl Read, w: &mut impl io::Write) -> io::Result<()> {
+    let mut b = Buffer::new();
+    PROTOCOL_VERSION.encode(&mut b, &mut ());
+    api_fingerprint().encode(&mut b, &mut ());
+    write_frame(w, Tag::Hello, &b)?;
+    let (tag, b) = read_frame(r)?;
+    if tag != Tag::Hello {
+        return Err(invalid_data(format!("expected Hello frame, found {:?}", tag)));
+    }
+    let reader = &mut &b[..];
+    let version = read_u32(reader)?;
+    if version != PROTOCOL_VERSION {
+        return Err(invalid_data(format!(
+            "protocol version mismatch (expected {}, found {})",
+            PROTOCOL_VERSION, version
+        )));
+    }
+    if read_u64(reader)? != api_fingerprint() {
+        return Err(invalid_data("bridge API mismatch".to_string()));
+    }
+    Ok(())
+}
+
+/// A proc macro exported by a dylib in the host, i.e. a `client::ProcMacro`
+/// without the client, which stays in the host.
+#[derive(Clone, Debug)]
+pub enum ProcMacroDecl {
+    CustomDerive { trait_name: String, attributes: Vec<String> },
+    Attr { name: String },
+    Bang { name: String },
+}
+
+impl From<&client::ProcMacro> for ProcMacroDecl {
+    fn from(decl: &client::ProcMacro) -> Self {
+        match *decl {
+            client::ProcMacro::CustomDerive { trait_name, attributes, .. } => {
+                ProcMacroDecl::CustomDerive {
+                    trait_name: trait_name.to_string(),
+                    attributes: attributes.iter().map(|attr| attr.to_string()).collect(),
+                }
+            }
+            client::ProcMacro::Attr { name, .. } => ProcMacroDecl::Attr { name: name.to_string() },
+            client::ProcMacro::Bang { name, .. } => ProcMacroDecl::Bang { name: name.to_string() },
+        }
+    }
+}
+
+impl ProcMacroDecl {
+    /// Encodes the declarations sent in a `Macros` frame.
+    fn encode_all(decls: &[ProcMacroDecl]) -> Buffer<u8> {
+        let mut b = Buffer::new();
+        (decls.len() as u32).encode(&mut b, &mut ());
+        for decl in decls {
+            match decl {
+                ProcMacroDecl::CustomDerive { trait_name, attributes } => {
+                    0u8.encode(&mut b, &mut ());
+                    write_str(&mut b, trait_name);
+                    (attributes.len() as u32).encode(&mut b, &mut ());
+                    for attr in attributes {
+                        write_str(&mut b, attr);
+                    }
+                }
+                ProcMacroDecl::Attr { name } => {
+                    1u8.encode(&mut b, &mut ());
+                    write_str(&mut b, name);
+                }
+                ProcMacroDecl::Bang { name } => {
+                    2u8.encode(&mut b, &mut ());
+                    write_str(&mut b, name);
+                }
+            }
+        }
+        b
+    }
+
+    /// Decodes the declarations sent in a `Macros` frame, which has to cope
+    /// with an unknown kind of proc macro, as the host is a separate program.
+    fn decode_all(r: &mut Reader<'_>) -> io::Result<Vec<ProcMacroDecl>> {
+        // Don't preallocate based on the count, which comes from the host.
+        let len = read_u32(r)?;
+        let mut decls = Vec::new();
+        for _ in 0..len {
+            decls.push(match read_u8(r)? {
+                0 => {
+                    let trait_name = read_str(r)?.to_string();
+                    let len = read_u32(r)?;
+                    let mut attributes = Vec::new();
+                    for _ in 0..len {
+                        attributes.push(read_str(r)?.to_string());
+                    }
+                    ProcMacroDecl::CustomDerive { trait_name, attributes }
+                }
+                1 => ProcMacroDecl::Attr { name: read_str(r)?.to_string() },
+                2 => ProcMacroDecl::Bang { name: read_str(r)?.to_string() },
+                kind => return Err(invalid_data(format!("unknown proc macro kind {}", kind))),
+            });
+        }
+        Ok(decls)
+    }
+}
+
+/// Serves a proc macro server (i.e. a compiler) talking to us over `input`
+/// and `output`, until it closes `input`.
+///
+/// `load` is called with the path of a proc macro dylib and the name of
+/// its declarations symbol, and returns the proc macros the dylib exports.
+/// Each dylib is only loaded once.
+///
+/// If the connection breaks while a proc macro is running, the process
+/// exits, as there is no way to return early out of the proc macro.
+pub fn run_host<R, W, L>(input: R, output: W, load: L) -> io::Result<()>
+where
+    R: Read,
+    W: io::Write,
+    L: FnMut(&Path, &str) -> Result<&'static [client::ProcMacro], String>,
+{
+    let mut host = Host { input, output, load, loaded: HashMap::new() };
+    handshake(&mut host.input, &mut host.output)?;
+
+    loop {
+        let (tag, b) = match read_frame(&mut host.input) {
+            Ok(frame) => frame,
+            Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        host.serve(tag, b)?;
+    }
+}
+
+struct Host<R, W, L> {
+    input: R,
+    output: W,
+    load: L,
+    loaded: HashMap<(PathBuf, String), &'static [client::ProcMacro]>,
+}
+
+impl<R, W, L> Host<R, W, L>
+where
+    R: Read,
+    W: io::Write,
+    L: FnMut(&Path, &str) -> Result<&'static [client::ProcMacro], String>,
+{
+    /// Handles a `Load` or `Expand` frame from the server.
+    fn serve(&mut self, tag: Tag, b: Buffer<u8>) -> io::Result<()> {
+        if tag != Tag::Load && tag != Tag::Expand {
+            return Err(invalid_data(format!("unexpected {:?} frame", tag)));
+        }
+
+        let reader = &mut &b[..];
+        let dylib = PathBuf::from(read_str(reader)?);
+        let symbol = read_str(reader)?.to_string();
+        let decls = match self.loaded.get(&(dylib.clone(), symbol.clone())) {
+            Some(&decls) => decls,
+            None => match (self.load)(&dylib, &symbol) {
+                Ok(decls) => *self.loaded.entry((dylib, symbol)).or_insert(decls),
+                Err(e) => return write_frame(&mut self.output, Tag::Error, &encode_str(&e)),
+            },
+        };
+
+        if tag == Tag::Load {
+            let decls: Vec<_> = decls.iter().map(ProcMacroDecl::from).collect();
+            return write_frame(&mut self.output, Tag::Macros, &ProcMacroDecl::encode_all(&decls));
+        }
+
+        let index = read_u32(reader)? as usize;
+        let decl = match decls.get(index) {
+            Some(decl) => decl,
+            None => {
+                let e = format!("no proc macro with index {}", index);
+                return write_frame(&mut self.output, Tag::Error, &encode_str(&e));
+            }
+        };
+        let result = self.run_client(decl, reader.to_vec().into());
+        write_frame(&mut self.output, Tag::Done, &result)
+    }
+
+    /// Runs a proc macro in the host, forwarding its requests to the server.
+    fn run_client(&mut self, decl: &client::ProcMacro, input: Buffer<u8>) -> Buffer<u8> {
+        let mut dispatch = |b: Buffer<u8>| match self.request(b) {
+            Ok(b) => b,
+            Err(_) => std::process::exit(1),
+        };
+        let bridge = Bridge {
+            cached_buffer: input,
+            dispatch: (&mut dispatch).into(),
+        };
+        match *decl {
+            client::ProcMacro::CustomDerive { client, .. } => (client.run)(bridge, client.f),
+            client::ProcMacro::Attr { client, .. } => (client.run)(bridge, client.f),
+            client::ProcMacro::Bang { client, .. } => (client.run)(bridge, client.f),
+        }
+    }
+
+    /// Sends a request from a running proc macro to the server, and waits
+    /// for the response. In the meantime, the server may run other proc
+    /// macros (e.g. for `TokenStream::expand_expr`), which are served here.
+    fn request(&mut self, b: Buffer<u8>) -> io::Result<Buffer<u8>> {
+        write_frame(&mut self.output, Tag::Request, &b)?;
+        loop {
+            match read_frame(&mut self.input)? {
+                (Tag::Response, b) => return Ok(b),
+                (tag, b) => self.serve(tag, b)?,
+            }
+        }
+    }
+}
+
+/// An error talking to a proc macro host.
+#[derive(Debug)]
+pub enum HostError {
+    /// The host couldn't load a dylib, or doesn't have the requested proc macro.
+    Load(String),
+    /// The host crashed, exited early, or otherwise broke the protocol.
+    Crashed(String),
+}
+
+impl fmt::Display for HostError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HostError::Load(msg) => write!(f, "failed to load proc macro: {}", msg),
+            HostError::Crashed(msg) => write!(f, "proc macro host crashed: {}", msg),
+        }
+    }
+}
+
+/// Server-side connection to a proc macro host process.
+/// See `server.rs` for running proc macros through it.
+///
+/// Proc macros can be run through the same connection while another one is
+/// running, as long as they're nested (e.g. for `TokenStream::expand_expr`).
+/// There can't be two independent conversations with the host at once, so
+/// all uses of the connection should be on the same thread.
+pub struct ProcessServer {
+    conn: Mutex<Connection>,
+}
+
+struct Connection {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl ProcessServer {
+    /// Spawns a host process from `command`, which has to end up calling
+    /// `run_host` with its stdin and stdout.
+    pub fn spawn(mut command: Command) -> Result<ProcessServer, HostError> {
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| HostError::Crashed(format!("failed to spawn: {}", e)))?;
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        let mut conn = Connection { child, stdin, stdout };
+        match handshake(&mut conn.stdout, &mut conn.stdin) {
+            Ok(()) => Ok(ProcessServer { conn: Mutex::new(conn) }),
+            Err(e) => Err(conn.crashed(e)),
+        }
+    }
+
+    /// Loads `dylib` in the host, returning the proc macros found through its
+    /// declarations `symbol`. They're referred to by their index from then on.
+    pub fn load(&self, dylib: &Path, symbol: &str) -> Result<Vec<ProcMacroDecl>, HostError> {
+        let mut b = Buffer::new();
+        encode_dylib(&mut b, dylib, symbol)?;
+        self.send(Tag::Load, &b)?;
+        match self.recv()? {
+            (Tag::Macros, b) => {
+                ProcMacroDecl::decode_all(&mut &b[..]).map_err(|e| self.crashed(e))
+            }
+            (Tag::Error, b) => match read_str(&mut &b[..]) {
+                Ok(msg) => Err(HostError::Load(msg.to_string())),
+                Err(e) => Err(self.crashed(e)),
+            },
+            (tag, _) => Err(self.crashed(invalid_data(format!("unexpected {:?} frame", tag)))),
+        }
+    }
+
+    fn conn(&self) -> MutexGuard<'_, Connection> {
+        // Nothing panics while holding the lock, but don't make things worse if it does.
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(super) fn send(&self, tag: Tag, payload: &[u8]) -> Result<(), HostError> {
+        let mut conn = self.conn();
+        write_frame(&mut conn.stdin, tag, payload).map_err(|e| conn.crashed(e))
+    }
+
+    pub(super) fn recv(&self) -> Result<(Tag, Buffer<u8>), HostError> {
+        let mut conn = self.conn();
+        read_frame(&mut conn.stdout).map_err(|e| conn.crashed(e))
+    }
+
+    pub(super) fn crashed(&self, e: io::Error) -> HostError {
+        self.conn().crashed(e)
+    }
+}
+
+impl Connection {
+    /// Kills the host (if it's still running) after it broke the protocol,
+    /// and describes what happened. Usually the host died, closing its end.
+    fn crashed(&mut self, e: io::Error) -> HostError {
+        let _ = self.child.kill();
+        match self.child.wait() {
+            Ok(status) if e.kind() == io::ErrorKind::UnexpectedEof ||
+                          e.kind() == io::ErrorKind::BrokenPipe => {
+                HostError::Crashed(format!("host exited with {}", status))
+            }
+            _ => HostError::Crashed(e.to_string()),
+        }
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+pub(super) fn encode_dylib(
+    b: &mut Buffer<u8>,
+    dylib: &Path,
+    symbol: &str,
+) -> Result<(), HostError> {
+    let dylib = dylib.to_str().ok_or_else(|| {
+        HostError::Load(format!("non-UTF-8 path `{}`", dylib.display()))
+    })?;
+    write_str(b, dylib);
+    write_str(b, symbol);
+    Ok(())
+}
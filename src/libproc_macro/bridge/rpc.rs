@@ -127,6 +127,7 @@ impl<S> DecodeMut<'_, '_, S> for u8 {
 }
 
 rpc_encode_decode!(le u32);
+rpc_encode_decode!(le u64);
 rpc_encode_decode!(le usize);
 
 impl<S> Encode<S> for bool {
@@ -246,6 +247,22 @@ impl<S> DecodeMut<'_, '_, S> for String {
     }
 }
 
+impl<S, T: Encode<S>> Encode<S> for Vec<T> {
+    fn encode(self, w: &mut Writer, s: &mut S) {
+        self.len().encode(w, s);
+        for x in self {
+            x.encode(w, s);
+        }
+    }
+}
+
+impl<S, T: for<'s> DecodeMut<'a, 's, S>> DecodeMut<'a, '_, S> for Vec<T> {
+    fn decode(r: &mut Reader<'a>, s: &mut S) -> Self {
+        let len = usize::decode(r, s);
+        (0..len).map(|_| T::decode(r, s)).collect()
+    }
+}
+
 /// Simplied version of panic payloads, ignoring
 /// types other than `&'static str` and `String`.
 pub enum PanicMessage {
@@ -2,12 +2,17 @@
 
 use super::*;
 
+use std::io;
+use std::path::Path;
+
 // FIXME(eddyb) generate the definition of `HandleStore` in `server.rs`.
 use super::client::HandleStore;
 
 /// Declare an associated item of one of the traits below, optionally
 /// adjusting it (i.e., adding bounds to types and default bodies to methods).
 macro_rules! associated_item {
+    (type FreeFunctions) =>
+        (type FreeFunctions: 'static;);
     (type TokenStream) =>
         (type TokenStream: 'static + Clone;);
     (type TokenStreamBuilder) =>
@@ -340,3 +345,92 @@ impl client::Client<fn(crate::TokenStream, crate::TokenStream) -> crate::TokenSt
         .map(<MarkedTypes<S> as Types>::TokenStream::unmark)
     }
 }
+
+impl process::ProcessServer {
+    /// Like `run_server`, but the client is the proc macro at `index`
+    /// in `dylib`, running in the host process.
+    fn run<
+        S: Server,
+        I: Encode<HandleStore<MarkedTypes<S>>>,
+        O: for<'a, 's> DecodeMut<'a, 's, HandleStore<MarkedTypes<S>>>,
+    >(
+        &self,
+        dylib: &Path,
+        symbol: &str,
+        index: u32,
+        server: S,
+        input: I,
+    ) -> Result<Result<O, PanicMessage>, process::HostError> {
+        // The clients never see these handles directly, so the server's own
+        // counters can be used, instead of those of the `proc_macro` in the host.
+        let mut dispatcher = Dispatcher {
+            handle_store: HandleStore::new(client::HandleCounters::get()),
+            server: MarkedTypes(server),
+        };
+
+        let mut b = Buffer::new();
+        process::encode_dylib(&mut b, dylib, symbol)?;
+        index.encode(&mut b, &mut ());
+        input.encode(&mut b, &mut dispatcher.handle_store);
+        self.send(process::Tag::Expand, &b)?;
+
+        loop {
+            match self.recv()? {
+                (process::Tag::Request, b) => {
+                    let b = process::decode_bridge(|| dispatcher.dispatch(b))
+                        .map_err(|e| self.crashed(e))?;
+                    self.send(process::Tag::Response, &b)?;
+                }
+                (process::Tag::Done, b) => {
+                    let handle_store = &mut dispatcher.handle_store;
+                    return process::decode_bridge(|| Result::decode(&mut &b[..], handle_store))
+                        .map_err(|e| self.crashed(e));
+                }
+                (process::Tag::Error, b) => {
+                    let msg = process::read_str(&mut &b[..]).map_err(|e| self.crashed(e))?;
+                    return Err(process::HostError::Load(msg.to_string()));
+                }
+                (tag, _) => {
+                    let msg = format!("unexpected {:?} frame", tag);
+                    return Err(self.crashed(io::Error::new(io::ErrorKind::InvalidData, msg)));
+                }
+            }
+        }
+    }
+
+    /// Runs a bang or derive proc macro in the host, see `Client::run`.
+    pub fn expand1<S: Server>(
+        &self,
+        dylib: &Path,
+        symbol: &str,
+        index: u32,
+        server: S,
+        input: S::TokenStream,
+    ) -> Result<Result<S::TokenStream, PanicMessage>, process::HostError> {
+        self.run(dylib, symbol, index, server, <MarkedTypes<S> as Types>::TokenStream::mark(input))
+            .map(|r| r.map(<MarkedTypes<S> as Types>::TokenStream::unmark))
+    }
+
+    /// Runs an attribute proc macro in the host, see `Client::run`.
+    pub fn expand2<S: Server>(
+        &self,
+        dylib: &Path,
+        symbol: &str,
+        index: u32,
+        server: S,
+        input: S::TokenStream,
+        input2: S::TokenStream,
+    ) -> Result<Result<S::TokenStream, PanicMessage>, process::HostError> {
+        self.run(
+            dylib,
+            symbol,
+            index,
+            server,
+            (
+                <MarkedTypes<S> as Types>::TokenStream::mark(input),
+                <MarkedTypes<S> as Types>::TokenStream::mark(input2),
+            ),
+        )
+        .map(|r| r.map(<MarkedTypes<S> as Types>::TokenStream::unmark))
+    }
+}
@@ -936,6 +936,7 @@ pub struct WorkProduct {
 #[derive(Clone, Copy, Debug, RustcEncodable, RustcDecodable, PartialEq)]
 pub enum WorkProductFileKind {
     Object,
+    DwarfObject,
     Bytecode,
     BytecodeCompressed,
 }
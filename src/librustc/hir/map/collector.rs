@@ -12,8 +12,11 @@ use crate::session::CrateDisambiguator;
 use crate::session::Session;
 use crate::util::nodemap::FxHashMap;
 use syntax::ast::NodeId;
+use syntax::parse::ParseSess;
 use syntax::source_map::SourceMap;
 use syntax_pos::Span;
+use std::fs;
+use std::hash::Hasher;
 use std::iter::repeat;
 
 use crate::ich::StableHashingContext;
@@ -173,6 +176,7 @@ impl<'a, 'hir> NodeCollector<'a, 'hir> {
     pub(super) fn finalize_and_compute_crate_hash(mut self,
                                                   crate_disambiguator: CrateDisambiguator,
                                                   cstore: &dyn CrateStore,
+                                                  parse_sess: &ParseSess,
                                                   commandline_args_hash: u64)
                                                   -> (HirEntryMap<'hir>, Svh)
     {
@@ -209,8 +213,31 @@ impl<'a, 'hir> NodeCollector<'a, 'hir> {
 
         source_file_names.sort_unstable();
 
+        // Environment variables and files accessed by macros aren't reflected by
+        // the source files, so hash them as well (see `ParseSess::env_depinfo`).
+        let mut tracked_env_vars: Vec<_> = parse_sess.env_depinfo.borrow().iter()
+            .map(|&(var, value)| {
+                (var.as_interned_str(), value.map(|value| value.as_interned_str()))
+            })
+            .collect();
+        tracked_env_vars.sort_unstable();
+        // Tracked files aren't registered with the source map, so their contents
+        // are hashed here. A file that can't be read hashes as `None`.
+        let mut tracked_files: Vec<_> = parse_sess.file_depinfo.borrow().iter()
+            .map(|path| {
+                let contents_hash = fs::read(&*path.as_str()).ok().map(|contents| {
+                    let mut hasher = StableHasher::<Fingerprint>::new();
+                    hasher.write(&contents);
+                    hasher.finish()
+                });
+                (path.as_interned_str(), contents_hash)
+            })
+            .collect();
+        tracked_files.sort_unstable();
+        let tracked_inputs = (tracked_env_vars, tracked_files);
+
         let crate_hash_input = (
-            ((node_hashes, upstream_crates), source_file_names),
+            ((node_hashes, upstream_crates), (source_file_names, tracked_inputs)),
             (commandline_args_hash, crate_disambiguator.to_fingerprint())
         );
 
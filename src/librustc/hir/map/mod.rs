@@ -1160,6 +1160,7 @@ pub fn map_crate<'hir>(sess: &crate::session::Session,
         collector.finalize_and_compute_crate_hash(
             crate_disambiguator,
             cstore,
+            &sess.parse_sess,
             cmdline_args
         )
     };
@@ -79,6 +79,12 @@ declare_lint! {
     "detects unreachable patterns"
 }
 
+declare_lint! {
+    pub OVERLAPPING_PATTERNS,
+    Warn,
+    "detects range patterns whose endpoints overlap with earlier patterns"
+}
+
 declare_lint! {
     pub UNUSED_MACROS,
     Warn,
@@ -404,6 +410,7 @@ declare_lint_pass! {
         DEAD_CODE,
         UNREACHABLE_CODE,
         UNREACHABLE_PATTERNS,
+        OVERLAPPING_PATTERNS,
         UNUSED_MACROS,
         WARNINGS,
         UNUSED_FEATURES,
@@ -317,6 +317,8 @@ pub enum InterpError<'tcx, O> {
     GeneratorResumedAfterReturn,
     GeneratorResumedAfterPanic,
     InfiniteLoop,
+    /// Evaluation executed more terminators than `-Z const-eval-step-limit` allows.
+    StepLimitReached(u64),
 }
 
 pub type InterpResult<'tcx, T = ()> = Result<T, InterpErrorInfo<'tcx>>;
@@ -451,6 +453,8 @@ impl<'tcx, O> InterpError<'tcx, O> {
             GeneratorResumedAfterPanic => "generator resumed after panicking",
             InfiniteLoop =>
                 "duplicate interpreter state observed here, const evaluation will never terminate",
+            StepLimitReached(_) =>
+                "exceeded the interpreter step limit",
         }
     }
 }
@@ -524,6 +528,10 @@ impl<'tcx, O: fmt::Debug> fmt::Debug for InterpError<'tcx, O> {
                 write!(f, "encountered invalid enum discriminant {}", val),
             Exit(code) =>
                 write!(f, "exited with status code {}", code),
+            StepLimitReached(limit) =>
+                write!(f, "exceeded the interpreter step limit of {} terminators, \
+                           const evaluation may never terminate \
+                           (use `-Z const-eval-step-limit` to raise it)", limit),
             _ => write!(f, "{}", self.description()),
         }
     }
@@ -25,6 +25,16 @@ pub struct FieldInfo {
     pub align: u64,
 }
 
+/// Where a niche-filling enum stores its discriminant.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct NicheInfo {
+    /// The variant whose data holds the niche, and which the niche
+    /// does not encode.
+    pub dataful_variant: String,
+    pub offset: u64,
+    pub size: u64,
+}
+
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub enum DataTypeKind {
     Struct,
@@ -41,6 +51,7 @@ pub struct TypeSizeInfo {
     pub overall_size: u64,
     pub packed: bool,
     pub opt_discr_size: Option<u64>,
+    pub opt_niche: Option<NicheInfo>,
     pub variants: Vec<VariantInfo>,
 }
 
@@ -57,6 +68,7 @@ impl CodeStats {
                                          overall_size: Size,
                                          packed: bool,
                                          opt_discr_size: Option<Size>,
+                                         opt_niche: Option<NicheInfo>,
                                          mut variants: Vec<VariantInfo>) {
         // Sort variants so the largest ones are shown first. A stable sort is
         // used here so that source code order is preserved for all variants
@@ -71,6 +83,7 @@ impl CodeStats {
             overall_size: overall_size.bytes(),
             packed: packed,
             opt_discr_size: opt_discr_size.map(|s| s.bytes()),
+            opt_niche,
             variants,
         };
         self.type_sizes.insert(info);
@@ -102,6 +115,12 @@ impl CodeStats {
                 0
             };
 
+            if let Some(ref niche) = info.opt_niche {
+                println!("print-type-size {}discriminant: niche of {} bytes at offset {} \
+                          in variant `{}`",
+                         indent, niche.size, niche.offset, niche.dataful_variant);
+            }
+
             // We start this at discr_size (rather than 0) because
             // things like C-enums do not have variants but we still
             // want the max_variant_size at the end of the loop below
@@ -137,6 +137,30 @@ pub enum SymbolManglingVersion {
 
 impl_stable_hash_via_hash!(SymbolManglingVersion);
 
+/// What the linker should strip from the linked artifact, see `-C strip`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Strip {
+    /// Keep everything the linker would normally keep.
+    None,
+    /// Strip debuginfo, keeping the symbol table.
+    Debuginfo,
+    /// Strip debuginfo and the symbol table.
+    Symbols,
+}
+
+/// How debuginfo is split out of the object files, see `-Z split-dwarf`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SplitDwarfKind {
+    /// All debuginfo stays in the object files.
+    None,
+    /// The DWARF objects are emitted into excluded sections of the object
+    /// files, and only the skeleton units reach the linked artifact.
+    Single,
+    /// The DWARF objects are emitted into a `.dwo` file per codegen unit,
+    /// and only the skeleton units reach the linked artifact.
+    Split,
+}
+
 #[derive(Clone, Copy, PartialEq, Hash)]
 pub enum DebugInfo {
     None,
@@ -528,6 +552,7 @@ pub struct OutputFilenames {
 impl_stable_hash_via_hash!(OutputFilenames);
 
 pub const RUST_CGU_EXT: &str = "rcgu";
+pub const DWARF_OBJECT_EXT: &str = "dwo";
 
 impl OutputFilenames {
     pub fn path(&self, flavor: OutputType) -> PathBuf {
@@ -571,6 +596,20 @@ impl OutputFilenames {
         path
     }
 
+    /// Gets the path of the file the DWARF object of the given codegen unit is
+    /// written to, or `None` if debuginfo is not split out of the object files.
+    pub fn split_dwarf_path(
+        &self,
+        kind: SplitDwarfKind,
+        codegen_unit_name: Option<&str>,
+    ) -> Option<PathBuf> {
+        match kind {
+            SplitDwarfKind::None => None,
+            SplitDwarfKind::Single => Some(self.temp_path(OutputType::Object, codegen_unit_name)),
+            SplitDwarfKind::Split => Some(self.temp_path_ext(DWARF_OBJECT_EXT, codegen_unit_name)),
+        }
+    }
+
     pub fn with_extension(&self, extension: &str) -> PathBuf {
         self.out_directory
             .join(&self.filestem())
@@ -838,12 +877,16 @@ macro_rules! options {
             Some("one of: `disabled`, `trampolines`, or `aliases`");
         pub const parse_symbol_mangling_version: Option<&str> =
             Some("either `legacy` or `v0` (RFC 2603)");
+        pub const parse_strip: Option<&str> =
+            Some("one of: `none`, `debuginfo` or `symbols`");
+        pub const parse_split_dwarf_kind: Option<&str> =
+            Some("one of: `none`, `single` or `split`");
     }
 
     #[allow(dead_code)]
     mod $mod_set {
         use super::{$struct_name, Passes, Sanitizer, LtoCli, LinkerPluginLto, SwitchWithOptPath,
-            SymbolManglingVersion};
+            SymbolManglingVersion, SplitDwarfKind, Strip};
         use rustc_target::spec::{LinkerFlavor, MergeFunctions, PanicStrategy, RelroLevel};
         use std::path::PathBuf;
         use std::str::FromStr;
@@ -1125,6 +1168,26 @@ macro_rules! options {
             };
             true
         }
+
+        fn parse_strip(slot: &mut Strip, v: Option<&str>) -> bool {
+            *slot = match v {
+                Some("none") => Strip::None,
+                Some("debuginfo") => Strip::Debuginfo,
+                Some("symbols") => Strip::Symbols,
+                _ => return false,
+            };
+            true
+        }
+
+        fn parse_split_dwarf_kind(slot: &mut SplitDwarfKind, v: Option<&str>) -> bool {
+            *slot = match v {
+                Some("none") => SplitDwarfKind::None,
+                Some("single") => SplitDwarfKind::Single,
+                Some("split") | None => SplitDwarfKind::Split,
+                _ => return false,
+            };
+            true
+        }
     }
 ) }
 
@@ -1212,6 +1275,9 @@ options! {CodegenOptions, CodegenSetter, basic_codegen_options,
         "compile the program with profiling instrumentation"),
     profile_use: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED],
         "use the given `.profdata` file for profile-guided optimization"),
+    strip: Strip = (Strip::None, parse_strip, [UNTRACKED],
+        "tell the linker which information to strip from the output (`none` (default), \
+         `debuginfo` or `symbols`)"),
 }
 
 options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
@@ -1277,6 +1343,11 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "parse only; do not compile, assemble, or link"),
     dual_proc_macros: bool = (false, parse_bool, [TRACKED],
         "load proc macros for both target and host, but only link to the target"),
+    out_of_process_proc_macros: bool = (false, parse_bool, [UNTRACKED],
+        "run proc macros in a separate host process, so a crashing proc macro can't take \
+         down the compiler"),
+    proc_macro_host: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "executable to run as the host for `-Z out-of-process-proc-macros` (default: this rustc)"),
     no_codegen: bool = (false, parse_bool, [TRACKED],
         "run all passes except codegen; no output"),
     treat_err_as_bug: Option<usize> = (None, parse_treat_err_as_bug, [TRACKED],
@@ -1303,6 +1374,17 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "verify incr. comp. hashes of green query instances"),
     incremental_ignore_spans: bool = (false, parse_bool, [UNTRACKED],
         "ignore spans during ICH computation -- used for testing"),
+    incremental_cache_info: bool = (false, parse_bool, [UNTRACKED],
+        "list the sessions, work products and dep-graphs in the incremental \
+         compilation directory and halt"),
+    incremental_cache_diff: Option<Vec<String>> = (None, parse_opt_comma_list, [UNTRACKED],
+        "compare the dep-graphs of two incremental compilation session directories \
+         (`-Z incremental-cache-diff=OLD,NEW`) and halt"),
+    incremental_cache_prune_age: Option<usize> = (None, parse_opt_uint, [UNTRACKED],
+        "delete incremental compilation sessions older than N days and halt"),
+    incremental_cache_prune_size: Option<usize> = (None, parse_opt_uint, [UNTRACKED],
+        "delete the oldest incremental compilation sessions until the incremental \
+         compilation directory takes up at most N MiB and halt"),
     instrument_mcount: bool = (false, parse_bool, [TRACKED],
         "insert function instrument code for mcount-based tracing"),
     dump_dep_graph: bool = (false, parse_bool, [UNTRACKED],
@@ -1367,6 +1449,8 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "describes how to render the `rendered` field of json diagnostics"),
     unleash_the_miri_inside_of_you: bool = (false, parse_bool, [TRACKED],
         "take the breaks off const evaluation. NOTE: this is unsound"),
+    const_eval_step_limit: usize = (0, parse_uint, [TRACKED],
+        "the maximum number of terminators a single constant may execute (default: no limit)"),
     osx_rpath_install_name: bool = (false, parse_bool, [TRACKED],
         "pass `-install_name @rpath/...` to the macOS linker"),
     sanitizer: Option<Sanitizer> = (None, parse_sanitizer, [TRACKED],
@@ -1468,6 +1552,9 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
     symbol_mangling_version: SymbolManglingVersion = (SymbolManglingVersion::Legacy,
         parse_symbol_mangling_version, [TRACKED],
         "which mangling version to use for symbol names"),
+    split_dwarf: SplitDwarfKind = (SplitDwarfKind::None, parse_split_dwarf_kind, [TRACKED],
+        "split debuginfo out of the object files into DWARF objects (ELF only): `split` \
+         writes a `.dwo` file per codegen unit, `single` keeps them inside the object files"),
 }
 
 pub fn default_lib_output() -> CrateType {
@@ -2572,7 +2659,7 @@ mod dep_tracking {
     use std::collections::hash_map::DefaultHasher;
     use super::{CrateType, DebugInfo, ErrorOutputType, OptLevel, OutputTypes,
                 Passes, Sanitizer, LtoCli, LinkerPluginLto, SwitchWithOptPath,
-                SymbolManglingVersion};
+                SymbolManglingVersion, SplitDwarfKind};
     use syntax::feature_gate::UnstableFeatures;
     use rustc_target::spec::{MergeFunctions, PanicStrategy, RelroLevel, TargetTriple};
     use syntax::edition::Edition;
@@ -2642,6 +2729,7 @@ mod dep_tracking {
     impl_dep_tracking_hash_via_hash!(LinkerPluginLto);
     impl_dep_tracking_hash_via_hash!(SwitchWithOptPath);
     impl_dep_tracking_hash_via_hash!(SymbolManglingVersion);
+    impl_dep_tracking_hash_via_hash!(SplitDwarfKind);
 
     impl_dep_tracking_hash_for_sortable_vec_of!(String);
     impl_dep_tracking_hash_for_sortable_vec_of!(PathBuf);
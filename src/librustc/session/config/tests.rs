@@ -12,7 +12,7 @@ use crate::session::search_paths::SearchPath;
 use std::collections::{BTreeMap, BTreeSet};
 use std::iter::FromIterator;
 use std::path::PathBuf;
-use super::{Externs, OutputType, OutputTypes, SymbolManglingVersion};
+use super::{Externs, OutputType, OutputTypes, SymbolManglingVersion, SplitDwarfKind, Strip};
 use rustc_target::spec::{MergeFunctions, PanicStrategy, RelroLevel};
 use syntax::symbol::sym;
 use syntax::edition::{Edition, DEFAULT_EDITION};
@@ -450,6 +450,9 @@ fn test_codegen_options_tracking_hash() {
     opts.cg.save_temps = true;
     assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
 
+    opts.cg.strip = Strip::Symbols;
+    assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+
     opts.cg.incremental = Some(String::from("abc"));
     assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
 
@@ -675,6 +678,10 @@ fn test_debugging_options_tracking_hash() {
     opts.debugging_opts.mir_opt_level = 3;
     assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
 
+    opts = reference.clone();
+    opts.debugging_opts.const_eval_step_limit = 100;
+    assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
     opts = reference.clone();
     opts.debugging_opts.relro_level = Some(RelroLevel::Full);
     assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
@@ -690,6 +697,10 @@ fn test_debugging_options_tracking_hash() {
     opts = reference.clone();
     opts.debugging_opts.symbol_mangling_version = SymbolManglingVersion::V0;
     assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
+    opts = reference.clone();
+    opts.debugging_opts.split_dwarf = SplitDwarfKind::Split;
+    assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
 }
 
 #[test]
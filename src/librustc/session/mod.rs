@@ -1,4 +1,4 @@
-pub use self::code_stats::{DataTypeKind, SizeKind, FieldInfo, VariantInfo};
+pub use self::code_stats::{DataTypeKind, SizeKind, FieldInfo, VariantInfo, NicheInfo};
 use self::code_stats::CodeStats;
 
 use crate::dep_graph::cgu_reuse_tracker::CguReuseTracker;
@@ -1313,6 +1313,17 @@ fn validate_commandline_args_with_session_available(sess: &Session) {
                    with `-Cpanic=unwind` on Windows when targeting MSVC. \
                    See https://github.com/rust-lang/rust/issues/61002 for details.");
     }
+
+    // Split DWARF relies on ELF features (excluded sections and the `.dwo`
+    // conventions used by `dwp` and debuggers).
+    if sess.opts.debugging_opts.split_dwarf != config::SplitDwarfKind::None {
+        let target = &sess.target.target;
+        if target.options.is_like_osx || target.options.is_like_windows ||
+           target.options.is_like_emscripten || target.arch.starts_with("wasm") {
+            sess.err(&format!("`-Z split-dwarf` is not supported for target `{}`",
+                              sess.opts.target_triple));
+        }
+    }
 }
 
 /// Hash value constructed out of all the `-C metadata` arguments passed to the
@@ -855,27 +855,43 @@ impl<'tcx> LayoutCx<'tcx, TyCtxt<'tcx>> {
                     .all(|(i, v)| v.discr == ty::VariantDiscr::Relative(i.as_u32()));
 
                 // Niche-filling enum optimization.
+                //
+                // The largest variant keeps its own layout, and every other variant
+                // is encoded as one of the invalid values of a niche inside it.
+                // This works as long as the fields of the other variants can be
+                // placed entirely before or entirely after the niche, which is
+                // trivially true for variants with only ZST fields. The result
+                // competes with the tagged layout computed below.
+                let mut niche_filling_layout = None;
                 if !def.repr.inhibit_enum_layout_opt() && no_explicit_discriminants {
-                    let mut dataful_variant = None;
-                    let mut niche_variants = VariantIdx::MAX..=VariantIdx::new(0);
+                    let mut align = dl.aggregate_align;
+                    let mut st = variants.iter_enumerated().map(|(j, v)| {
+                        let mut st = self.univariant_uninterned(ty, v,
+                            &def.repr, StructKind::AlwaysSized)?;
+                        st.variants = Variants::Single { index: j };
+
+                        align = align.max(st.align);
 
-                    // Find one non-ZST variant.
-                    'variants: for (v, fields) in variants.iter_enumerated() {
+                        Ok(st)
+                    }).collect::<Result<IndexVec<VariantIdx, _>, _>>()?;
+
+                    // The first of the largest present variants holds the niche.
+                    let mut dataful_variant = None;
+                    for (j, fields) in variants.iter_enumerated() {
                         if absent(fields) {
-                            continue 'variants;
+                            continue;
                         }
-                        for f in fields {
-                            if !f.is_zst() {
-                                if dataful_variant.is_none() {
-                                    dataful_variant = Some(v);
-                                    continue 'variants;
-                                } else {
-                                    dataful_variant = None;
-                                    break 'variants;
-                                }
-                            }
+                        match dataful_variant {
+                            Some(i) if st[i].size >= st[j].size => {}
+                            _ => dataful_variant = Some(j),
+                        }
+                    }
+
+                    let mut niche_variants = VariantIdx::MAX..=VariantIdx::new(0);
+                    for (j, fields) in variants.iter_enumerated() {
+                        if Some(j) != dataful_variant && !absent(fields) {
+                            niche_variants = *niche_variants.start().min(&j)..=j;
                         }
-                        niche_variants = *niche_variants.start().min(&v)..=v;
                     }
 
                     if niche_variants.start() > niche_variants.end() {
@@ -886,6 +902,10 @@ impl<'tcx> LayoutCx<'tcx, TyCtxt<'tcx>> {
                         let count = (
                             niche_variants.end().as_u32() - niche_variants.start().as_u32() + 1
                         ) as u128;
+                        let size = st[i].size.align_to(align.abi);
+                        let others_are_zst = st.iter_enumerated()
+                            .all(|(j, v)| j == i || v.size == Size::ZERO);
+
                         for (field_index, &field) in variants[i].iter().enumerate() {
                             let niche = match self.find_niche(field)? {
                                 Some(niche) => niche,
@@ -896,48 +916,82 @@ impl<'tcx> LayoutCx<'tcx, TyCtxt<'tcx>> {
                                 None => continue,
                             };
 
-                            let mut align = dl.aggregate_align;
-                            let st = variants.iter_enumerated().map(|(j, v)| {
-                                let mut st = self.univariant_uninterned(ty, v,
-                                    &def.repr, StructKind::AlwaysSized)?;
-                                st.variants = Variants::Single { index: j };
+                            let offset = st[i].fields.offset(field_index) + niche.offset;
+                            let niche_end = offset + niche_scalar.value.size(dl);
 
-                                align = align.max(st.align);
+                            // Find where each other variant's fields can go, if anywhere.
+                            let shifts = st.iter_enumerated().map(|(j, v)| {
+                                if j == i || v.size <= offset {
+                                    // Nothing to move, or it fits before the niche.
+                                    Some(Size::ZERO)
+                                } else {
+                                    let shift = niche_end.align_to(v.align.abi);
+                                    if shift + v.size <= size {
+                                        Some(shift)
+                                    } else {
+                                        None
+                                    }
+                                }
+                            }).collect::<Option<IndexVec<VariantIdx, _>>>();
+                            let shifts = match shifts {
+                                Some(shifts) => shifts,
+                                None => continue,
+                            };
 
-                                Ok(st)
-                            }).collect::<Result<IndexVec<VariantIdx, _>, _>>()?;
+                            for (j, v) in st.iter_enumerated_mut() {
+                                let shift = shifts[j];
+                                if shift == Size::ZERO {
+                                    continue;
+                                }
+                                match v.fields {
+                                    FieldPlacement::Arbitrary { ref mut offsets, .. } => {
+                                        for (k, field_offset) in offsets.iter_mut().enumerate() {
+                                            if !variants[j][k].is_zst() {
+                                                *field_offset += shift;
+                                            }
+                                        }
+                                    }
+                                    _ => bug!(),
+                                }
+                                v.size += shift;
+                                // The fields no longer start at offset 0.
+                                if !v.abi.is_uninhabited() {
+                                    v.abi = Abi::Aggregate { sized: true };
+                                }
+                            }
 
-                            let offset = st[i].fields.offset(field_index) + niche.offset;
-                            let size = st[i].size;
-
-                            let mut abi = match st[i].abi {
-                                Abi::Scalar(_) => Abi::Scalar(niche_scalar.clone()),
-                                Abi::ScalarPair(ref first, ref second) => {
-                                    // We need to use scalar_unit to reset the
-                                    // valid range to the maximal one for that
-                                    // primitive, because only the niche is
-                                    // guaranteed to be initialised, not the
-                                    // other primitive.
-                                    if offset.bytes() == 0 {
-                                        Abi::ScalarPair(
-                                            niche_scalar.clone(),
-                                            scalar_unit(second.value),
-                                        )
-                                    } else {
-                                        Abi::ScalarPair(
-                                            scalar_unit(first.value),
-                                            niche_scalar.clone(),
-                                        )
+                            let mut abi = if !others_are_zst || st[i].size != size {
+                                Abi::Aggregate { sized: true }
+                            } else {
+                                match st[i].abi {
+                                    Abi::Scalar(_) => Abi::Scalar(niche_scalar.clone()),
+                                    Abi::ScalarPair(ref first, ref second) => {
+                                        // We need to use scalar_unit to reset the
+                                        // valid range to the maximal one for that
+                                        // primitive, because only the niche is
+                                        // guaranteed to be initialised, not the
+                                        // other primitive.
+                                        if offset.bytes() == 0 {
+                                            Abi::ScalarPair(
+                                                niche_scalar.clone(),
+                                                scalar_unit(second.value),
+                                            )
+                                        } else {
+                                            Abi::ScalarPair(
+                                                scalar_unit(first.value),
+                                                niche_scalar.clone(),
+                                            )
+                                        }
                                     }
+                                    _ => Abi::Aggregate { sized: true },
                                 }
-                                _ => Abi::Aggregate { sized: true },
                             };
 
                             if st.iter().all(|v| v.abi.is_uninhabited()) {
                                 abi = Abi::Uninhabited;
                             }
 
-                            return Ok(tcx.intern_layout(LayoutDetails {
+                            niche_filling_layout = Some(LayoutDetails {
                                 variants: Variants::Multiple {
                                     discr: niche_scalar,
                                     discr_kind: DiscriminantKind::Niche {
@@ -955,7 +1009,8 @@ impl<'tcx> LayoutCx<'tcx, TyCtxt<'tcx>> {
                                 abi,
                                 size,
                                 align,
-                            }));
+                            });
+                            break;
                         }
                     }
                 }
@@ -1026,6 +1081,9 @@ impl<'tcx> LayoutCx<'tcx, TyCtxt<'tcx>> {
                 size = size.align_to(align.abi);
 
                 if size.bytes() >= dl.obj_size_bound() {
+                    if let Some(niche_filling_layout) = niche_filling_layout {
+                        return Ok(tcx.intern_layout(niche_filling_layout));
+                    }
                     return Err(LayoutError::SizeOverflow(ty));
                 }
 
@@ -1164,7 +1222,7 @@ impl<'tcx> LayoutCx<'tcx, TyCtxt<'tcx>> {
                     abi = Abi::Uninhabited;
                 }
 
-                tcx.intern_layout(LayoutDetails {
+                let tagged_layout = LayoutDetails {
                     variants: Variants::Multiple {
                         discr: tag,
                         discr_kind: DiscriminantKind::Tag,
@@ -1178,7 +1236,15 @@ impl<'tcx> LayoutCx<'tcx, TyCtxt<'tcx>> {
                     abi,
                     align,
                     size
-                })
+                };
+
+                // Prefer the niche-filling layout unless the tag makes the enum smaller.
+                match niche_filling_layout {
+                    Some(niche_layout) if niche_layout.size <= tagged_layout.size => {
+                        tcx.intern_layout(niche_layout)
+                    }
+                    _ => tcx.intern_layout(tagged_layout),
+                }
             }
 
             // Types with no meaningful known layout.
@@ -1508,7 +1574,7 @@ impl<'tcx> LayoutCx<'tcx, TyCtxt<'tcx>> {
         }
 
         // (delay format until we actually need it)
-        let record = |kind, packed, opt_discr_size, variants| {
+        let record = |kind, packed, opt_discr_size, opt_niche, variants| {
             let type_desc = format!("{:?}", layout.ty);
             self.tcx.sess.code_stats.borrow_mut().record_type_size(kind,
                                                                    type_desc,
@@ -1516,6 +1582,7 @@ impl<'tcx> LayoutCx<'tcx, TyCtxt<'tcx>> {
                                                                    layout.size,
                                                                    packed,
                                                                    opt_discr_size,
+                                                                   opt_niche,
                                                                    variants);
         };
 
@@ -1527,7 +1594,7 @@ impl<'tcx> LayoutCx<'tcx, TyCtxt<'tcx>> {
 
             ty::Closure(..) => {
                 debug!("print-type-size t: `{:?}` record closure", layout.ty);
-                record(DataTypeKind::Closure, false, None, vec![]);
+                record(DataTypeKind::Closure, false, None, None, vec![]);
                 return;
             }
 
@@ -1593,17 +1660,18 @@ impl<'tcx> LayoutCx<'tcx, TyCtxt<'tcx>> {
                     record(adt_kind.into(),
                            adt_packed,
                            None,
+                           None,
                            vec![build_variant_info(Some(variant_def.ident),
                                                    &fields,
                                                    layout)]);
                 } else {
                     // (This case arises for *empty* enums; so give it
                     // zero variants.)
-                    record(adt_kind.into(), adt_packed, None, vec![]);
+                    record(adt_kind.into(), adt_packed, None, None, vec![]);
                 }
             }
 
-            Variants::Multiple { ref discr, ref discr_kind, .. } => {
+            Variants::Multiple { ref discr, ref discr_kind, discr_index, .. } => {
                 debug!("print-type-size `{:#?}` adt general variants def {}",
                        layout.ty, adt_def.variants.len());
                 let variant_infos: Vec<_> =
@@ -1615,10 +1683,17 @@ impl<'tcx> LayoutCx<'tcx, TyCtxt<'tcx>> {
                                            layout.for_variant(self, i))
                     })
                     .collect();
-                record(adt_kind.into(), adt_packed, match discr_kind {
-                    DiscriminantKind::Tag => Some(discr.value.size(self)),
-                    _ => None
-                }, variant_infos);
+                let (opt_discr_size, opt_niche) = match *discr_kind {
+                    DiscriminantKind::Tag => (Some(discr.value.size(self)), None),
+                    DiscriminantKind::Niche { dataful_variant, .. } => {
+                        (None, Some(session::NicheInfo {
+                            dataful_variant: adt_def.variants[dataful_variant].ident.to_string(),
+                            offset: layout.fields.offset(discr_index).bytes(),
+                            size: discr.value.size(self).bytes(),
+                        }))
+                    }
+                };
+                record(adt_kind.into(), adt_packed, opt_discr_size, opt_niche, variant_infos);
             }
         }
     }
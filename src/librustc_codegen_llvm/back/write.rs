@@ -27,6 +27,7 @@ use std::fs;
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use std::str;
+use std::ptr;
 use std::sync::Arc;
 use std::slice;
 use libc::{c_uint, c_void, c_char, size_t};
@@ -68,10 +69,22 @@ pub fn write_output_file(
         pm: &llvm::PassManager<'ll>,
         m: &'ll llvm::Module,
         output: &Path,
+        split_dwarf_file: Option<&Path>,
+        dwo_output: Option<&Path>,
         file_type: llvm::FileType) -> Result<(), FatalError> {
     unsafe {
         let output_c = path_to_c_string(output);
-        let result = llvm::LLVMRustWriteOutputFile(target, pm, m, output_c.as_ptr(), file_type);
+        let split_dwarf_file = split_dwarf_file.map(path_to_c_string);
+        let dwo_output = dwo_output.map(path_to_c_string);
+        let result = llvm::LLVMRustWriteOutputFile(
+            target,
+            pm,
+            m,
+            output_c.as_ptr(),
+            split_dwarf_file.as_ref().map_or(ptr::null(), |s| s.as_ptr()),
+            dwo_output.as_ref().map_or(ptr::null(), |s| s.as_ptr()),
+            file_type,
+        );
         result.into_result().map_err(|()| {
             let msg = format!("could not write output to {}", output.display());
             llvm_err(handler, &msg)
@@ -445,6 +458,7 @@ pub(crate) unsafe fn codegen(cgcx: &CodegenContext<LlvmCodegenBackend>,
     -> Result<CompiledModule, FatalError>
 {
     let _timer = cgcx.profile_activity("codegen");
+    let emit_dwarf_obj;
     {
         let llmod = module.module_llvm.llmod();
         let llcx = &*module.module_llvm.llcx;
@@ -492,7 +506,14 @@ pub(crate) unsafe fn codegen(cgcx: &CodegenContext<LlvmCodegenBackend>,
 
         let bc_out = cgcx.output_filenames.temp_path(OutputType::Bitcode, module_name);
         let obj_out = cgcx.output_filenames.temp_path(OutputType::Object, module_name);
-
+        let split_dwarf_kind = cgcx.split_dwarf_kind(module.kind);
+        let split_dwarf_file = cgcx.output_filenames.split_dwarf_path(split_dwarf_kind,
+                                                                      module_name);
+        let dwo_out = match split_dwarf_kind {
+            config::SplitDwarfKind::Split => split_dwarf_file.clone(),
+            _ => None,
+        };
+        emit_dwarf_obj = write_obj && dwo_out.is_some();
 
         if write_bc || config.emit_bc_compressed || config.embed_bitcode {
             let _timer = cgcx.profile_activity("LLVM_make_bitcode");
@@ -587,7 +608,7 @@ pub(crate) unsafe fn codegen(cgcx: &CodegenContext<LlvmCodegenBackend>,
                     llmod
                 };
                 with_codegen(tm, llmod, config.no_builtins, |cpm| {
-                    write_output_file(diag_handler, tm, cpm, llmod, &path,
+                    write_output_file(diag_handler, tm, cpm, llmod, &path, None, None,
                                       llvm::FileType::AssemblyFile)
                 })?;
             }
@@ -596,6 +617,8 @@ pub(crate) unsafe fn codegen(cgcx: &CodegenContext<LlvmCodegenBackend>,
                 let _timer = cgcx.profile_activity("LLVM_emit_obj");
                 with_codegen(tm, llmod, config.no_builtins, |cpm| {
                     write_output_file(diag_handler, tm, cpm, llmod, &obj_out,
+                                      split_dwarf_file.as_ref().map(|p| &**p),
+                                      dwo_out.as_ref().map(|p| &**p),
                                       llvm::FileType::ObjectFile)
                 })?;
             } else if asm_to_obj {
@@ -628,6 +651,7 @@ pub(crate) unsafe fn codegen(cgcx: &CodegenContext<LlvmCodegenBackend>,
         drop(handlers);
     }
     Ok(module.into_compiled_module(config.emit_obj,
+                                   emit_dwarf_obj,
                                    config.emit_bc,
                                    config.emit_bc_compressed,
                                    &cgcx.output_filenames))
@@ -696,8 +720,6 @@ pub unsafe fn with_llvm_pmb(llmod: &llvm::Module,
                             opt_level: llvm::CodeGenOptLevel,
                             prepare_for_thin_lto: bool,
                             f: &mut dyn FnMut(&llvm::PassManagerBuilder)) {
-    use std::ptr;
-
     // Create the PassManagerBuilder for LLVM. We configure it with
     // reasonable defaults and prepare it to actually populate the pass
     // manager.
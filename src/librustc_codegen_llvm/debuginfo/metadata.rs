@@ -922,7 +922,13 @@ pub fn compile_unit_metadata(
     let work_dir = SmallCStr::new(&tcx.sess.working_dir.0.to_string_lossy());
     let producer = CString::new(producer).unwrap();
     let flags = "\0";
-    let split_name = "\0";
+
+    // With split DWARF the skeleton unit records where the rest of the
+    // debuginfo for this codegen unit can be found.
+    let split_name = tcx.output_filenames(LOCAL_CRATE)
+        .split_dwarf_path(tcx.sess.opts.debugging_opts.split_dwarf, Some(codegen_unit_name))
+        .map(|path| path_to_c_string(&path))
+        .unwrap_or_default();
 
     // FIXME(#60020):
     //
@@ -956,7 +962,7 @@ pub fn compile_unit_metadata(
             tcx.sess.opts.optimize != config::OptLevel::No,
             flags.as_ptr() as *const _,
             0,
-            split_name.as_ptr() as *const _,
+            split_name.as_ptr(),
             kind);
 
         if tcx.sess.opts.debugging_opts.profile {
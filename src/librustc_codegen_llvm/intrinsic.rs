@@ -139,6 +139,14 @@ impl IntrinsicCallMethods<'tcx> for Builder<'a, 'll, 'tcx> {
                 let tp_ty = substs.type_at(0);
                 self.const_usize(self.size_of(tp_ty).bytes())
             }
+            "const_allocate" => {
+                // Heap allocation only makes sense during compile-time evaluation.
+                self.const_null(self.type_i8p())
+            }
+            "const_deallocate" => {
+                // Deallocations at runtime are a no-op, matching `const_allocate` above.
+                return;
+            }
             "va_start" => {
                 self.va_start(args[0].immediate())
             }
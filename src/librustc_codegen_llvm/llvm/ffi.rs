@@ -1711,6 +1711,8 @@ extern "C" {
                                    PM: &PassManager<'a>,
                                    M: &'a Module,
                                    Output: *const c_char,
+                                   SplitDwarfFile: *const c_char,
+                                   DwoOutput: *const c_char,
                                    FileType: FileType)
                                    -> LLVMRustResult;
     pub fn LLVMRustPrintModule(PM: &PassManager<'a>,
@@ -3,7 +3,8 @@
 
 use rustc::session::{Session, filesearch};
 use rustc::session::config::{
-    self, RUST_CGU_EXT, DebugInfo, OutputFilenames, OutputType, PrintRequest, Sanitizer
+    self, RUST_CGU_EXT, DebugInfo, OutputFilenames, OutputType, PrintRequest, Sanitizer,
+    SplitDwarfKind, Strip,
 };
 use rustc::session::search_paths::PathKind;
 use rustc::middle::dependency_format::Linkage;
@@ -51,6 +52,14 @@ pub fn link_binary<'a, B: ArchiveBuilder<'a>>(sess: &'a Session,
                                               crate_name: &str,
                                               target_cpu: &str) {
     let output_metadata = sess.opts.output_types.contains_key(&OutputType::Metadata);
+    let split_dwarf = if sess.opts.debuginfo != DebugInfo::None {
+        sess.opts.debugging_opts.split_dwarf
+    } else {
+        SplitDwarfKind::None
+    };
+    // Whether the debuginfo split out of the objects is still only reachable
+    // through the per-codegen-unit DWARF objects after linking.
+    let mut keep_dwarf_objects = false;
     for &crate_type in sess.crate_types.borrow().iter() {
         // Ignore executable crates if we have -Z no-codegen, as they will error.
         if (sess.opts.debugging_opts.no_codegen || !sess.opts.output_types.should_codegen()) &&
@@ -80,9 +89,11 @@ pub fn link_binary<'a, B: ArchiveBuilder<'a>>(sess: &'a Session,
                               RlibFlavor::Normal,
                               &out_filename,
                               &tmpdir).build();
+                    keep_dwarf_objects |= split_dwarf != SplitDwarfKind::None;
                 }
                 config::CrateType::Staticlib => {
                     link_staticlib::<B>(sess, codegen_results, &out_filename, &tmpdir);
+                    keep_dwarf_objects |= split_dwarf != SplitDwarfKind::None;
                 }
                 _ => {
                     link_natively::<B>(
@@ -93,6 +104,11 @@ pub fn link_binary<'a, B: ArchiveBuilder<'a>>(sess: &'a Session,
                         tmpdir.path(),
                         target_cpu,
                     );
+                    if split_dwarf != SplitDwarfKind::None &&
+                        !package_split_dwarf(sess, split_dwarf, codegen_results, &out_filename)
+                    {
+                        keep_dwarf_objects = true;
+                    }
                 }
             }
             if sess.opts.debugging_opts.emit_artifact_notifications {
@@ -107,11 +123,18 @@ pub fn link_binary<'a, B: ArchiveBuilder<'a>>(sess: &'a Session,
 
     // Remove the temporary object file and metadata if we aren't saving temps
     if !sess.opts.cg.save_temps {
-        if sess.opts.output_types.should_codegen() && !preserve_objects_for_their_debuginfo(sess) {
+        let preserve_objects = preserve_objects_for_their_debuginfo(sess) ||
+            (keep_dwarf_objects && split_dwarf == SplitDwarfKind::Single);
+        if sess.opts.output_types.should_codegen() && !preserve_objects {
             for obj in codegen_results.modules.iter().filter_map(|m| m.object.as_ref()) {
                 remove(sess, obj);
             }
         }
+        if !keep_dwarf_objects {
+            for obj in codegen_results.modules.iter().filter_map(|m| m.dwarf_object.as_ref()) {
+                remove(sess, obj);
+            }
+        }
         for obj in codegen_results.modules.iter().filter_map(|m| m.bytecode_compressed.as_ref()) {
             remove(sess, obj);
         }
@@ -131,6 +154,48 @@ pub fn link_binary<'a, B: ArchiveBuilder<'a>>(sess: &'a Session,
     }
 }
 
+/// Packages the split DWARF of every codegen unit into a `.dwp` file next to
+/// `out_filename`, so that the debuginfo survives the removal of the
+/// temporary objects. Returns `false` if no package could be produced, in
+/// which case the DWARF objects need to be kept around.
+fn package_split_dwarf(sess: &Session,
+                       split_dwarf: SplitDwarfKind,
+                       codegen_results: &CodegenResults,
+                       out_filename: &Path) -> bool {
+    let inputs = codegen_results.modules.iter().filter_map(|m| match split_dwarf {
+        SplitDwarfKind::Split => m.dwarf_object.as_ref(),
+        _ => m.object.as_ref(),
+    }).collect::<Vec<_>>();
+    if inputs.is_empty() {
+        return true
+    }
+
+    let mut dwp_out = out_filename.as_os_str().to_owned();
+    dwp_out.push(".dwp");
+
+    // Prefer the packager shipped with LLVM, falling back to the one from
+    // binutils. If neither is installed the DWARF objects are left in place.
+    for tool in &["llvm-dwp", "dwp"] {
+        let mut cmd = Command::new(tool);
+        cmd.arg("-o").arg(&dwp_out).args(&inputs);
+        match cmd.output() {
+            Ok(ref output) if output.status.success() => return true,
+            Ok(output) => {
+                sess.struct_warn(&format!("failed to package split DWARF with `{}`", tool))
+                    .note(&String::from_utf8_lossy(&output.stderr))
+                    .emit();
+                return false
+            }
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => {
+                sess.warn(&format!("failed to run `{}`: {}", tool, e));
+                return false
+            }
+        }
+    }
+    false
+}
+
 // The third parameter is for env vars, used on windows to set up the
 // path for MSVC to find its DLLs, and gcc to find its bundled
 // toolchain
@@ -669,9 +734,11 @@ fn link_natively<'a, B: ArchiveBuilder<'a>>(sess: &'a Session,
 
     // On macOS, debuggers need this utility to get run to do some munging of
     // the symbols. Note, though, that if the object files are being preserved
-    // for their debug information there's no need for us to run dsymutil.
+    // for their debug information there's no need for us to run dsymutil,
+    // and neither is there if the linker was told to strip the debuginfo.
     if sess.target.target.options.is_like_osx &&
         sess.opts.debuginfo != DebugInfo::None &&
+        strip_level(sess) == Strip::None &&
         !preserve_objects_for_their_debuginfo(sess)
     {
         if let Err(e) = Command::new("dsymutil").arg(out_filename).output() {
@@ -773,6 +840,19 @@ pub fn linker_and_flavor(sess: &Session) -> (PathBuf, LinkerFlavor) {
     bug!("Not enough information provided to determine how to invoke the linker");
 }
 
+/// What the linker is asked to strip from the linked artifact.
+fn strip_level(sess: &Session) -> Strip {
+    match sess.opts.cg.strip {
+        // `-Z strip-debuginfo-if-disabled` gets rid of the debuginfo of
+        // upstream crates, libstd in particular, if we didn't ask for any.
+        Strip::None if sess.opts.debuginfo == DebugInfo::None &&
+                       sess.opts.debugging_opts.strip_debuginfo_if_disabled == Some(true) => {
+            Strip::Debuginfo
+        }
+        strip => strip,
+    }
+}
+
 /// Returns a boolean indicating whether we should preserve the object files on
 /// the filesystem for their debug information. This is often useful with
 /// split-dwarf like schemes.
@@ -1129,8 +1209,8 @@ fn link_args<'a, B: ArchiveBuilder<'a>>(cmd: &mut dyn Linker,
     // Pass optimization flags down to the linker.
     cmd.optimize();
 
-    // Pass debuginfo flags down to the linker.
-    cmd.debuginfo();
+    // Pass debuginfo and strip flags down to the linker.
+    cmd.debuginfo(strip_level(sess));
 
     // We want to, by default, prevent the compiler from accidentally leaking in
     // any system libraries, so we may explicitly ask linkers to not link to any
@@ -13,7 +13,7 @@ use rustc::hir::def_id::{LOCAL_CRATE, CrateNum};
 use rustc::middle::dependency_format::Linkage;
 use rustc::session::Session;
 use rustc::session::config::{self, CrateType, OptLevel, DebugInfo,
-                             LinkerPluginLto, Lto};
+                             LinkerPluginLto, Lto, Strip};
 use rustc::ty::TyCtxt;
 use rustc_target::spec::{LinkerFlavor, LldFlavor};
 use serialize::{json, Encoder};
@@ -118,7 +118,7 @@ pub trait Linker {
     fn no_relro(&mut self);
     fn optimize(&mut self);
     fn pgo_gen(&mut self);
-    fn debuginfo(&mut self);
+    fn debuginfo(&mut self, strip: Strip);
     fn no_default_libraries(&mut self);
     fn build_dylib(&mut self, out_filename: &Path);
     fn build_static_executable(&mut self);
@@ -333,15 +333,24 @@ impl<'a> Linker for GccLinker<'a> {
         self.cmd.arg("__llvm_profile_runtime");
     }
 
-    fn debuginfo(&mut self) {
-        if let DebugInfo::None = self.sess.opts.debuginfo {
-            // If we are building without debuginfo enabled and we were called with
-            // `-Zstrip-debuginfo-if-disabled=yes`, tell the linker to strip any debuginfo
-            // found when linking to get rid of symbols from libstd.
-            if let Some(true) = self.sess.opts.debugging_opts.strip_debuginfo_if_disabled {
+    fn debuginfo(&mut self, strip: Strip) {
+        match strip {
+            Strip::None => {}
+            Strip::Debuginfo => {
+                // The macOS linker doesn't support the longhand `--strip-debug`.
                 self.linker_arg("-S");
             }
-        };
+            Strip::Symbols => {
+                if self.sess.target.target.options.is_like_osx {
+                    // `-s` is obsolete and ignored by the macOS linker, the
+                    // closest it gets is dropping debuginfo and local symbols.
+                    self.linker_arg("-S");
+                    self.linker_arg("-x");
+                } else {
+                    self.linker_arg("-s");
+                }
+            }
+        }
     }
 
     fn no_default_libraries(&mut self) {
@@ -602,27 +611,39 @@ impl<'a> Linker for MsvcLinker<'a> {
         // Nothing needed here.
     }
 
-    fn debuginfo(&mut self) {
-        // This will cause the Microsoft linker to generate a PDB file
-        // from the CodeView line tables in the object files.
-        self.cmd.arg("/DEBUG");
-
-        // This will cause the Microsoft linker to embed .natvis info into the PDB file
-        let natvis_dir_path = self.sess.sysroot.join("lib\\rustlib\\etc");
-        if let Ok(natvis_dir) = fs::read_dir(&natvis_dir_path) {
-            for entry in natvis_dir {
-                match entry {
-                    Ok(entry) => {
-                        let path = entry.path();
-                        if path.extension() == Some("natvis".as_ref()) {
-                            let mut arg = OsString::from("/NATVIS:");
-                            arg.push(path);
-                            self.cmd.arg(arg);
+    fn debuginfo(&mut self, strip: Strip) {
+        match strip {
+            // `-Z strip-debuginfo-if-disabled` asks for `Strip::Debuginfo` as
+            // well, but only `-C strip` stops the PDB file from being generated.
+            Strip::Debuginfo | Strip::Symbols if self.sess.opts.cg.strip != Strip::None => {
+                // Symbols only ever end up in the PDB file, so not generating
+                // one strips both debuginfo and symbols.
+                self.cmd.arg("/DEBUG:NONE");
+            }
+            _ => {
+                // This will cause the Microsoft linker to generate a PDB file
+                // from the CodeView line tables in the object files.
+                self.cmd.arg("/DEBUG");
+
+                // This will cause the Microsoft linker to embed .natvis info into the PDB file
+                let natvis_dir_path = self.sess.sysroot.join("lib\\rustlib\\etc");
+                if let Ok(natvis_dir) = fs::read_dir(&natvis_dir_path) {
+                    for entry in natvis_dir {
+                        match entry {
+                            Ok(entry) => {
+                                let path = entry.path();
+                                if path.extension() == Some("natvis".as_ref()) {
+                                    let mut arg = OsString::from("/NATVIS:");
+                                    arg.push(path);
+                                    self.cmd.arg(arg);
+                                }
+                            },
+                            Err(err) => {
+                                self.sess.warn(&format!("error enumerating natvis directory: {}",
+                                                        err));
+                            },
                         }
-                    },
-                    Err(err) => {
-                        self.sess.warn(&format!("error enumerating natvis directory: {}", err));
-                    },
+                    }
                 }
             }
         }
@@ -806,12 +827,14 @@ impl<'a> Linker for EmLinker<'a> {
         // noop, but maybe we need something like the gnu linker?
     }
 
-    fn debuginfo(&mut self) {
-        // Preserve names or generate source maps depending on debug info
-        self.cmd.arg(match self.sess.opts.debuginfo {
-            DebugInfo::None => "-g0",
-            DebugInfo::Limited => "-g3",
-            DebugInfo::Full => "-g4"
+    fn debuginfo(&mut self, strip: Strip) {
+        // Preserve names or generate source maps depending on debug info.
+        // `-g2` keeps function names but drops everything else.
+        self.cmd.arg(match (strip, self.sess.opts.debuginfo) {
+            (_, DebugInfo::None) | (Strip::Symbols, _) => "-g0",
+            (Strip::Debuginfo, _) => "-g2",
+            (Strip::None, DebugInfo::Limited) => "-g3",
+            (Strip::None, DebugInfo::Full) => "-g4",
         });
     }
 
@@ -970,7 +993,16 @@ impl<'a> Linker for WasmLd<'a> {
     fn pgo_gen(&mut self) {
     }
 
-    fn debuginfo(&mut self) {
+    fn debuginfo(&mut self, strip: Strip) {
+        match strip {
+            Strip::None => {}
+            Strip::Debuginfo => {
+                self.cmd.arg("--strip-debug");
+            }
+            Strip::Symbols => {
+                self.cmd.arg("--strip-all");
+            }
+        }
     }
 
     fn no_default_libraries(&mut self) {
@@ -1058,8 +1090,10 @@ impl<'a> Linker for PtxLinker<'a> {
         self.cmd.arg("-L").arg(path);
     }
 
-    fn debuginfo(&mut self) {
-        self.cmd.arg("--debug");
+    fn debuginfo(&mut self, strip: Strip) {
+        if strip == Strip::None {
+            self.cmd.arg("--debug");
+        }
     }
 
     fn add_object(&mut self, path: &Path) {
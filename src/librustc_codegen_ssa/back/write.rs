@@ -13,7 +13,7 @@ use rustc::dep_graph::{WorkProduct, WorkProductId, WorkProductFileKind};
 use rustc::dep_graph::cgu_reuse_tracker::CguReuseTracker;
 use rustc::middle::cstore::EncodedMetadata;
 use rustc::session::config::{self, OutputFilenames, OutputType, Passes, Lto,
-                             Sanitizer, SwitchWithOptPath};
+                             Sanitizer, SwitchWithOptPath, DWARF_OBJECT_EXT};
 use rustc::session::Session;
 use rustc::util::nodemap::FxHashMap;
 use rustc::hir::def_id::{CrateNum, LOCAL_CRATE};
@@ -285,6 +285,17 @@ impl<B: WriteBackendMethods> CodegenContext<B> {
         }
     }
 
+    /// How the DWARF of a module of the given kind should be split out, if at
+    /// all. Only regular modules carry debuginfo worth splitting.
+    pub fn split_dwarf_kind(&self, kind: ModuleKind) -> config::SplitDwarfKind {
+        match kind {
+            ModuleKind::Regular if self.debuginfo != config::DebugInfo::None => {
+                self.opts.debugging_opts.split_dwarf
+            }
+            _ => config::SplitDwarfKind::None,
+        }
+    }
+
     #[inline(never)]
     #[cold]
     fn profiler_active<F: FnOnce(&SelfProfiler) -> ()>(&self, f: F) {
@@ -536,6 +547,9 @@ fn copy_all_cgu_workproducts_to_incr_comp_cache_dir(
         if let Some(ref path) = module.object {
             files.push((WorkProductFileKind::Object, path.clone()));
         }
+        if let Some(ref path) = module.dwarf_object {
+            files.push((WorkProductFileKind::DwarfObject, path.clone()));
+        }
         if let Some(ref path) = module.bytecode {
             files.push((WorkProductFileKind::Bytecode, path.clone()));
         }
@@ -887,6 +901,7 @@ fn execute_copy_from_cache_work_item<B: ExtraBackendMethods>(
                                     .as_ref()
                                     .unwrap();
     let mut object = None;
+    let mut dwarf_object = None;
     let mut bytecode = None;
     let mut bytecode_compressed = None;
     for (kind, saved_file) in &module.source.saved_files {
@@ -897,6 +912,12 @@ fn execute_copy_from_cache_work_item<B: ExtraBackendMethods>(
                 object = Some(path.clone());
                 path
             }
+            WorkProductFileKind::DwarfObject => {
+                let path = cgcx.output_filenames.temp_path_ext(DWARF_OBJECT_EXT,
+                                                               Some(&module.name));
+                dwarf_object = Some(path.clone());
+                path
+            }
             WorkProductFileKind::Bytecode => {
                 let path = cgcx.output_filenames.temp_path(OutputType::Bitcode,
                                                            Some(&module.name));
@@ -934,6 +955,7 @@ fn execute_copy_from_cache_work_item<B: ExtraBackendMethods>(
         name: module.name,
         kind: ModuleKind::Regular,
         object,
+        dwarf_object,
         bytecode,
         bytecode_compressed,
     }))
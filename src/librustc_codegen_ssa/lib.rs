@@ -29,7 +29,7 @@
 
 use std::path::PathBuf;
 use rustc::dep_graph::WorkProduct;
-use rustc::session::config::{OutputFilenames, OutputType};
+use rustc::session::config::{OutputFilenames, OutputType, DWARF_OBJECT_EXT};
 use rustc::middle::lang_items::LangItem;
 use rustc::hir::def_id::CrateNum;
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
@@ -71,6 +71,7 @@ pub const RLIB_BYTECODE_EXTENSION: &str = "bc.z";
 impl<M> ModuleCodegen<M> {
     pub fn into_compiled_module(self,
                             emit_obj: bool,
+                            emit_dwarf_obj: bool,
                             emit_bc: bool,
                             emit_bc_compressed: bool,
                             outputs: &OutputFilenames) -> CompiledModule {
@@ -79,6 +80,11 @@ impl<M> ModuleCodegen<M> {
         } else {
             None
         };
+        let dwarf_object = if emit_dwarf_obj {
+            Some(outputs.temp_path_ext(DWARF_OBJECT_EXT, Some(&self.name)))
+        } else {
+            None
+        };
         let bytecode = if emit_bc {
             Some(outputs.temp_path(OutputType::Bitcode, Some(&self.name)))
         } else {
@@ -95,6 +101,7 @@ impl<M> ModuleCodegen<M> {
             name: self.name.clone(),
             kind: self.kind,
             object,
+            dwarf_object,
             bytecode,
             bytecode_compressed,
         }
@@ -106,6 +113,7 @@ pub struct CompiledModule {
     pub name: String,
     pub kind: ModuleKind,
     pub object: Option<PathBuf>,
+    pub dwarf_object: Option<PathBuf>,
     pub bytecode: Option<PathBuf>,
     pub bytecode_compressed: Option<PathBuf>,
 }
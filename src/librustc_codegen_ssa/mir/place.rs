@@ -1,5 +1,5 @@
 use rustc::ty::{self, Ty};
-use rustc::ty::layout::{self, Align, TyLayout, LayoutOf, VariantIdx, HasTyCtxt};
+use rustc::ty::layout::{self, Align, Size, TyLayout, LayoutOf, VariantIdx, HasTyCtxt};
 use rustc::mir;
 use rustc::mir::tcx::PlaceTy;
 use crate::MemFlags;
@@ -351,8 +351,14 @@ impl<'a, 'tcx, V: CodegenObject> PlaceRef<'tcx, V> {
                 ..
             } => {
                 if variant_index != dataful_variant {
-                    if bx.cx().sess().target.target.arch == "arm" ||
-                       bx.cx().sess().target.target.arch == "aarch64" {
+                    // Variants with data have already had their fields written,
+                    // so only dataless variants may be cleared wholesale.
+                    let variant_has_data =
+                        self.layout.for_variant(bx.cx(), variant_index).size != Size::ZERO;
+                    if !variant_has_data && (
+                        bx.cx().sess().target.target.arch == "arm" ||
+                        bx.cx().sess().target.target.arch == "aarch64"
+                    ) {
                         // FIXME(#34427): as workaround for LLVM bug on ARM,
                         // use memset of 0 before assigning niche value.
                         let fill_byte = bx.cx().const_u8(0);
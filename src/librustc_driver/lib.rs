@@ -40,6 +40,7 @@ use rustc::hir::def_id::LOCAL_CRATE;
 use rustc::util::common::{ErrorReported, install_panic_hook, print_time_passes_entry};
 use rustc::util::common::{set_time_depth, time};
 use rustc_metadata::locator;
+use rustc_metadata::proc_macro_host;
 use rustc_metadata::cstore::CStore;
 use rustc_codegen_utils::codegen_backend::CodegenBackend;
 use rustc_interface::interface;
@@ -181,7 +182,7 @@ pub fn run_compiler(
             match matches.free.len() {
                 0 => {
                     let config = dummy_config(sopts, cfg, diagnostic_output);
-                    interface::run_compiler(config, |compiler| {
+                    return interface::run_compiler(config, |compiler| {
                         let sopts = &compiler.session().opts;
                         if sopts.describe_lints {
                             describe_lints(
@@ -189,7 +190,7 @@ pub fn run_compiler(
                                 &*compiler.session().lint_store.borrow(),
                                 false
                             );
-                            return;
+                            return Ok(());
                         }
                         let should_stop = RustcDefaultCalls::print_crate_info(
                             &***compiler.codegen_backend(),
@@ -197,14 +198,15 @@ pub fn run_compiler(
                             None,
                             &odir,
                             &ofile
-                        );
+                        ).and_then(|| RustcDefaultCalls::inspect_incremental_cache(
+                            compiler.session(),
+                        ));
 
                         if should_stop == Compilation::Stop {
-                            return;
+                            return compiler.session().compile_status();
                         }
                         early_error(sopts.error_format, "no input filename given")
                     });
-                    return Ok(());
                 }
                 1 => panic!("make_input should have provided valid inputs"),
                 _ => early_error(sopts.error_format, &format!(
@@ -254,7 +256,7 @@ pub fn run_compiler(
             compiler.cstore(),
             &matches,
             compiler.input()
-        ));
+        )).and_then(|| RustcDefaultCalls::inspect_incremental_cache(sess));
 
         if should_stop == Compilation::Stop {
             return sess.compile_status();
@@ -600,6 +602,14 @@ impl RustcDefaultCalls {
         Compilation::Continue
     }
 
+    pub fn inspect_incremental_cache(sess: &Session) -> Compilation {
+        if rustc_incremental::inspect_incremental_cache(sess) {
+            Compilation::Stop
+        } else {
+            Compilation::Continue
+        }
+    }
+
 
     fn print_crate_info(codegen_backend: &dyn CodegenBackend,
                         sess: &Session,
@@ -1182,6 +1192,11 @@ pub fn init_rustc_env_logger() {
 }
 
 pub fn main() {
+    // Serve a compiler running proc macros out of process, see `proc_macro_host`.
+    if env::args_os().nth(1).map_or(false, |arg| arg == proc_macro_host::HOST_FLAG) {
+        process::exit(proc_macro_host::main());
+    }
+
     let start = Instant::now();
     init_rustc_env_logger();
     let mut callbacks = TimePassesCallbacks::default();
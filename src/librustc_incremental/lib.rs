@@ -35,3 +35,4 @@ pub use persist::prepare_session_directory;
 pub use persist::finalize_session_directory;
 pub use persist::delete_workproduct_files;
 pub use persist::garbage_collect_session_directories;
+pub use persist::inspect_incremental_cache;
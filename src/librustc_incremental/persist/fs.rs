@@ -138,6 +138,9 @@ pub fn dep_graph_path_from(incr_comp_session_dir: &Path) -> PathBuf {
 pub fn work_products_path(sess: &Session) -> PathBuf {
     in_incr_comp_dir_sess(sess, WORK_PRODUCTS_FILENAME)
 }
+pub fn work_products_path_from(incr_comp_session_dir: &Path) -> PathBuf {
+    in_incr_comp_dir(incr_comp_session_dir, WORK_PRODUCTS_FILENAME)
+}
 
 pub fn query_cache_path(sess: &Session) -> PathBuf {
     in_incr_comp_dir_sess(sess, QUERY_CACHE_FILENAME)
@@ -499,8 +502,8 @@ fn lock_directory(sess: &Session,
     }
 }
 
-fn delete_session_dir_lock_file(sess: &Session,
-                                lock_file_path: &Path) {
+pub fn delete_session_dir_lock_file(sess: &Session,
+                                    lock_file_path: &Path) {
     if let Err(err) = safe_remove_file(&lock_file_path) {
         sess.warn(&format!("Error deleting lock file for incremental \
                             compilation session directory `{}`: {}",
@@ -555,11 +558,11 @@ fn find_source_directory_in_iter<I>(iter: I,
     best_candidate.1
 }
 
-fn is_finalized(directory_name: &str) -> bool {
+pub fn is_finalized(directory_name: &str) -> bool {
     !directory_name.ends_with("-working")
 }
 
-fn is_session_directory(directory_name: &str) -> bool {
+pub fn is_session_directory(directory_name: &str) -> bool {
     directory_name.starts_with("s-") &&
     !directory_name.ends_with(LOCK_FILE_EXT)
 }
@@ -568,8 +571,8 @@ fn is_session_directory_lock_file(file_name: &str) -> bool {
     file_name.starts_with("s-") && file_name.ends_with(LOCK_FILE_EXT)
 }
 
-fn extract_timestamp_from_session_dir(directory_name: &str)
-                                      -> Result<SystemTime, ()> {
+pub fn extract_timestamp_from_session_dir(directory_name: &str)
+                                          -> Result<SystemTime, ()> {
     if !is_session_directory(directory_name) {
         return Err(())
     }
@@ -874,7 +877,7 @@ fn all_except_most_recent(deletion_candidates: Vec<(SystemTime, PathBuf, Option<
 /// to circumvent this limitation, we canonicalize the path of the directory
 /// before passing it to std::fs::remove_dir_all(). This will convert the path
 /// into the '\\?\' format, which supports much longer paths.
-fn safe_remove_dir_all(p: &Path) -> io::Result<()> {
+pub fn safe_remove_dir_all(p: &Path) -> io::Result<()> {
     if p.exists() {
         let canonicalized = p.canonicalize()?;
         std_fs::remove_dir_all(canonicalized)
@@ -0,0 +1,449 @@
+//! Inspection and pruning of an incremental compilation directory, as
+//! requested with the `-Z incremental-cache-*` options. These run instead of
+//! a compilation session: they only read the session directories described
+//! in `fs.rs`, except for pruning, which locks a finalized session directory
+//! exactly like garbage collection does before deleting it.
+
+use rustc::dep_graph::{DepKind, DepNode, SerializedDepGraph};
+use rustc::ich::Fingerprint;
+use rustc::session::Session;
+use rustc_data_structures::flock;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_serialize::Decodable as RustcDecodable;
+use rustc_serialize::opaque::Decoder;
+
+use std::collections::BTreeMap;
+use std::io;
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use super::data::*;
+use super::file_format;
+use super::fs::*;
+
+struct CrateDir {
+    path: PathBuf,
+    sessions: Vec<SessionDir>,
+}
+
+struct SessionDir {
+    path: PathBuf,
+    timestamp: SystemTime,
+    finalized: bool,
+    size: u64,
+}
+
+/// Performs the inspection or pruning requested with `-Z incremental-cache-*`,
+/// printing the results to stdout. Returns `false` if none was requested, i.e.
+/// if compilation should go ahead as usual.
+pub fn inspect_incremental_cache(sess: &Session) -> bool {
+    let opts = &sess.opts.debugging_opts;
+    let prune = opts.incremental_cache_prune_age.is_some() ||
+                opts.incremental_cache_prune_size.is_some();
+    if !opts.incremental_cache_info && opts.incremental_cache_diff.is_none() && !prune {
+        return false;
+    }
+
+    if let Some(ref dirs) = opts.incremental_cache_diff {
+        if dirs.len() == 2 {
+            diff_dep_graphs(sess, Path::new(&dirs[0]), Path::new(&dirs[1]));
+        } else {
+            sess.err("`-Z incremental-cache-diff` expects exactly two session directories");
+        }
+    }
+
+    if !opts.incremental_cache_info && !prune {
+        return true;
+    }
+
+    let incr_dir = match sess.opts.incremental {
+        Some(ref incr_dir) => incr_dir,
+        None => {
+            sess.err("`-Z incremental-cache-info` and `-Z incremental-cache-prune-*` \
+                      require `-C incremental`");
+            return true;
+        }
+    };
+
+    let mut crates = match read_incr_comp_dir(incr_dir) {
+        Ok(crates) => crates,
+        Err(err) => {
+            sess.err(&format!("could not read incremental compilation directory `{}`: {}",
+                              incr_dir.display(),
+                              err));
+            return true;
+        }
+    };
+
+    if prune {
+        let max_age = opts.incremental_cache_prune_age
+                          .map(|days| Duration::from_secs(days as u64 * 24 * 60 * 60));
+        let max_size = opts.incremental_cache_prune_size
+                           .map(|mib| mib as u64 * 1024 * 1024);
+        prune_sessions(sess, &mut crates, max_age, max_size);
+    }
+
+    if opts.incremental_cache_info {
+        print_info(incr_dir, &crates);
+    }
+
+    true
+}
+
+/// Collects the crate directories of `incr_dir` and their session
+/// directories, oldest session first. Anything else is ignored.
+fn read_incr_comp_dir(incr_dir: &Path) -> io::Result<Vec<CrateDir>> {
+    let mut crates = vec![];
+
+    for crate_entry in incr_dir.read_dir()? {
+        let crate_path = crate_entry?.path();
+        if !crate_path.is_dir() {
+            continue
+        }
+
+        let mut sessions = vec![];
+        for session_entry in crate_path.read_dir()? {
+            let session_path = session_entry?.path();
+            let (timestamp, finalized) = match session_path.file_name()
+                                                           .and_then(|name| name.to_str()) {
+                Some(name) if session_path.is_dir() => {
+                    match extract_timestamp_from_session_dir(name) {
+                        Ok(timestamp) => (timestamp, is_finalized(name)),
+                        Err(()) => continue,
+                    }
+                }
+                _ => continue,
+            };
+
+            sessions.push(SessionDir {
+                size: dir_size(&session_path)?,
+                path: session_path,
+                timestamp,
+                finalized,
+            });
+        }
+
+        sessions.sort_by_key(|session| session.timestamp);
+        crates.push(CrateDir { path: crate_path, sessions });
+    }
+
+    crates.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(crates)
+}
+
+fn dir_size(path: &Path) -> io::Result<u64> {
+    let mut size = 0;
+    for entry in path.read_dir()? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if metadata.is_dir() {
+            size += dir_size(&entry.path())?;
+        } else {
+            size += metadata.len();
+        }
+    }
+    Ok(size)
+}
+
+fn print_info(incr_dir: &Path, crates: &[CrateDir]) {
+    let now = SystemTime::now();
+    let mut session_count = 0;
+    let mut total_size = 0;
+
+    println!("incremental compilation directory `{}`", incr_dir.display());
+
+    for krate in crates {
+        println!("crate `{}`", krate.path.file_name().unwrap().to_string_lossy());
+
+        for session in &krate.sessions {
+            session_count += 1;
+            total_size += session.size;
+
+            let age = now.duration_since(session.timestamp).unwrap_or(Duration::from_secs(0));
+            println!("  session `{}` ({}, {} old, {})",
+                     session.path.file_name().unwrap().to_string_lossy(),
+                     if session.finalized { "finalized" } else { "working" },
+                     format_age(age),
+                     format_size(session.size));
+
+            match load_dep_graph_from(&session.path) {
+                Ok((_, dep_graph)) => {
+                    println!("    dep-graph: {} nodes, {} edges",
+                             dep_graph.nodes.len(),
+                             dep_graph.edge_list_data.len());
+                }
+                Err(message) => println!("    dep-graph: {}", message),
+            }
+
+            match load_work_products_from(&session.path) {
+                Ok(work_products) => {
+                    println!("    work products: {}", work_products.len());
+                    for swp in work_products {
+                        let files: Vec<_> = swp.work_product.saved_files
+                                               .iter()
+                                               .map(|&(_, ref file)| &file[..])
+                                               .collect();
+                        println!("      {}: {}", swp.work_product.cgu_name, files.join(", "));
+                    }
+                }
+                Err(message) => println!("    work products: {}", message),
+            }
+        }
+    }
+
+    println!("{} sessions, {} in total", session_count, format_size(total_size));
+}
+
+/// Compares the dep-graphs saved in two session directories and prints,
+/// for each kind of dep-node, how many results changed between them.
+/// The dep-nodes whose results changed are what caused their dependents
+/// to be recomputed in the newer session.
+fn diff_dep_graphs(sess: &Session, old_dir: &Path, new_dir: &Path) {
+    let load = |dir: &Path| {
+        load_dep_graph_from(dir).map_err(|message| {
+            sess.err(&format!("could not load dep-graph from `{}`: {}", dir.display(), message))
+        })
+    };
+    let (old_args_hash, old_graph) = match load(old_dir) {
+        Ok(graph) => graph,
+        Err(()) => return,
+    };
+    let (new_args_hash, new_graph) = match load(new_dir) {
+        Ok(graph) => graph,
+        Err(()) => return,
+    };
+
+    println!("comparing dep-graph of `{}` with `{}`", old_dir.display(), new_dir.display());
+    if old_args_hash != new_args_hash {
+        println!("commandline arguments differ, so no results could be reused");
+    }
+
+    #[derive(Default)]
+    struct KindDiff {
+        unchanged: usize,
+        changed: usize,
+        added: usize,
+        removed: usize,
+    }
+
+    let mut old_fingerprints: FxHashMap<DepNode, Fingerprint> =
+        old_graph.nodes.iter().cloned().zip(old_graph.fingerprints.iter().cloned()).collect();
+    let mut kinds: BTreeMap<DepKind, KindDiff> = BTreeMap::new();
+    let mut changed = vec![];
+
+    for (node, &fingerprint) in new_graph.nodes.iter().zip(new_graph.fingerprints.iter()) {
+        let diff = kinds.entry(node.kind).or_default();
+        match old_fingerprints.remove(node) {
+            Some(old_fingerprint) if old_fingerprint == fingerprint => diff.unchanged += 1,
+            Some(_) => {
+                diff.changed += 1;
+                changed.push(*node);
+            }
+            None => diff.added += 1,
+        }
+    }
+    for node in old_fingerprints.keys() {
+        kinds.entry(node.kind).or_default().removed += 1;
+    }
+
+    println!("{:<40} {:>9} {:>9} {:>9} {:>9}", "kind", "unchanged", "changed", "added", "removed");
+    for (kind, diff) in &kinds {
+        println!("{:<40} {:>9} {:>9} {:>9} {:>9}",
+                 format!("{:?}", kind),
+                 diff.unchanged,
+                 diff.changed,
+                 diff.added,
+                 diff.removed);
+    }
+
+    changed.sort();
+    println!("{} changed results", changed.len());
+    for node in changed {
+        println!("  {:?}", node);
+    }
+}
+
+/// Deletes the finalized sessions exceeding `max_age` and, oldest first,
+/// those that make the directory exceed `max_size` bytes. Sessions that are
+/// in use by another compiler are skipped, like during garbage collection.
+fn prune_sessions(sess: &Session,
+                  crates: &mut [CrateDir],
+                  max_age: Option<Duration>,
+                  max_size: Option<u64>) {
+    let mut candidates = vec![];
+    let mut locks = FxHashMap::default();
+
+    for krate in crates.iter() {
+        for session in &krate.sessions {
+            if session.finalized {
+                match flock::Lock::new(&lock_file_path(&session.path),
+                                       false,  // don't wait
+                                       false,  // don't create the lock-file
+                                       true) { // get an exclusive lock
+                    Ok(lock) => {
+                        locks.insert(session.path.clone(), lock);
+                    }
+                    Err(_) => {
+                        debug!("prune_sessions() - not pruning `{}`, still in use",
+                               session.path.display());
+                    }
+                }
+            }
+            candidates.push((session.timestamp,
+                             session.size,
+                             locks.contains_key(&session.path)));
+        }
+    }
+
+    let paths: Vec<_> = crates.iter()
+                              .flat_map(|krate| &krate.sessions)
+                              .map(|session| session.path.clone())
+                              .collect();
+    let mut deleted_size = 0;
+    let mut deleted = vec![];
+
+    for index in sessions_to_prune(&candidates, SystemTime::now(), max_age, max_size) {
+        let path = &paths[index];
+        if let Err(err) = safe_remove_dir_all(path) {
+            sess.warn(&format!("Failed to prune incremental compilation session \
+                                directory `{}`: {}",
+                               path.display(),
+                               err));
+            continue
+        }
+        delete_session_dir_lock_file(sess, &lock_file_path(path));
+
+        println!("deleted session `{}` ({})", path.display(), format_size(candidates[index].1));
+        deleted_size += candidates[index].1;
+        deleted.push(path.clone());
+    }
+
+    // Let's make it explicit that the file locks are released at this point,
+    // or rather, that we held on to them until here
+    mem::drop(locks);
+
+    println!("pruned {} sessions, {} freed", deleted.len(), format_size(deleted_size));
+
+    for krate in crates {
+        krate.sessions.retain(|session| !deleted.contains(&session.path));
+    }
+}
+
+/// Selects the sessions to prune from `(timestamp, size, can_be_deleted)`
+/// triples, returning their indices.
+fn sessions_to_prune(sessions: &[(SystemTime, u64, bool)],
+                     now: SystemTime,
+                     max_age: Option<Duration>,
+                     max_size: Option<u64>)
+                     -> Vec<usize> {
+    let mut order: Vec<usize> = (0 .. sessions.len()).collect();
+    order.sort_by_key(|&index| sessions[index].0);
+
+    let mut total_size: u64 = sessions.iter().map(|&(_, size, _)| size).sum();
+    let mut selected = vec![];
+
+    for index in order {
+        let (timestamp, size, can_be_deleted) = sessions[index];
+        if !can_be_deleted {
+            continue
+        }
+
+        let too_old = max_age.map_or(false, |max_age| {
+            now.duration_since(timestamp).map_or(false, |age| age > max_age)
+        });
+        let too_big = max_size.map_or(false, |max_size| total_size > max_size);
+
+        if too_old || too_big {
+            total_size -= size;
+            selected.push(index);
+        }
+    }
+
+    selected
+}
+
+fn load_file(path: &Path) -> Result<(Vec<u8>, usize), String> {
+    if !path.exists() {
+        return Err("none".to_string());
+    }
+
+    match file_format::read_file(false, path) {
+        Ok(Some(data_and_pos)) => Ok(data_and_pos),
+        Ok(None) => Err("written by a different compiler version".to_string()),
+        Err(err) => Err(format!("could not read `{}`: {}", path.display(), err)),
+    }
+}
+
+/// Loads the dep-graph of a session directory, along with the hash of the
+/// commandline arguments it was produced with.
+fn load_dep_graph_from(session_dir: &Path) -> Result<(u64, SerializedDepGraph), String> {
+    let (bytes, start_pos) = load_file(&dep_graph_path_from(session_dir))?;
+    let mut decoder = Decoder::new(&bytes, start_pos);
+    let commandline_args_hash = u64::decode(&mut decoder)?;
+    let dep_graph = SerializedDepGraph::decode(&mut decoder)?;
+    Ok((commandline_args_hash, dep_graph))
+}
+
+fn load_work_products_from(session_dir: &Path) -> Result<Vec<SerializedWorkProduct>, String> {
+    let (bytes, start_pos) = load_file(&work_products_path_from(session_dir))?;
+    let mut decoder = Decoder::new(&bytes, start_pos);
+    RustcDecodable::decode(&mut decoder)
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: &[&str] = &["KiB", "MiB", "GiB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 60 * 60 {
+        format!("{}m", secs / 60)
+    } else if secs < 24 * 60 * 60 {
+        format!("{}h", secs / (60 * 60))
+    } else {
+        format!("{}d", secs / (24 * 60 * 60))
+    }
+}
+
+#[test]
+fn test_sessions_to_prune() {
+    let now = SystemTime::now();
+    let days = |n| Duration::from_secs(n * 24 * 60 * 60);
+    let ago = |n| now - days(n);
+    let sessions = [
+        (ago(1), 100, true),
+        (ago(10), 200, true),
+        (ago(20), 300, false),
+        (ago(5), 400, true),
+    ];
+
+    assert_eq!(sessions_to_prune(&sessions, now, None, None), Vec::<usize>::new());
+    assert_eq!(sessions_to_prune(&sessions, now, Some(days(7)), None), vec![1]);
+    assert_eq!(sessions_to_prune(&sessions, now, None, Some(700)), vec![1, 3]);
+    assert_eq!(sessions_to_prune(&sessions, now, None, Some(0)), vec![1, 3, 0]);
+    assert_eq!(sessions_to_prune(&sessions, now, Some(days(7)), Some(600)),
+               vec![1, 3]);
+}
+
+#[test]
+fn test_format_size() {
+    assert_eq!(format_size(0), "0 B");
+    assert_eq!(format_size(1023), "1023 B");
+    assert_eq!(format_size(1536), "1.5 KiB");
+    assert_eq!(format_size(3 * 1024 * 1024), "3.0 MiB");
+}
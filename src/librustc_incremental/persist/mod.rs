@@ -5,6 +5,7 @@
 mod data;
 mod dirty_clean;
 mod fs;
+mod inspect;
 mod load;
 mod save;
 mod work_product;
@@ -15,6 +16,7 @@ pub use fs::garbage_collect_session_directories;
 pub use fs::in_incr_comp_dir;
 pub use fs::in_incr_comp_dir_sess;
 pub use fs::prepare_session_directory;
+pub use inspect::inspect_incremental_cache;
 pub use load::dep_graph_tcx_init;
 pub use load::{DepGraphFuture, load_dep_graph};
 pub use load::load_query_result_cache;
@@ -24,6 +24,7 @@ pub fn copy_cgu_workproducts_to_incr_comp_cache_dir(
              .map(|&(kind, ref path)| {
                  let extension = match kind {
                      WorkProductFileKind::Object => "o",
+                     WorkProductFileKind::DwarfObject => "dwo",
                      WorkProductFileKind::Bytecode => "bc",
                      WorkProductFileKind::BytecodeCompressed => "bc.z",
                  };
@@ -657,6 +657,22 @@ fn escape_dep_filename(filename: &FileName) -> String {
     filename.to_string().replace(" ", "\\ ")
 }
 
+// Makefile comments only need escaping newlines and `\`.
+// The result can be unescaped by anything that can unescape `escape_default` and friends.
+fn escape_dep_env(symbol: Symbol) -> String {
+    let s = symbol.as_str();
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\n' => escaped.push_str(r"\n"),
+            '\r' => escaped.push_str(r"\r"),
+            '\\' => escaped.push_str(r"\\"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
 fn write_out_deps(sess: &Session, outputs: &OutputFilenames, out_filenames: &[PathBuf]) {
     // Write out dependency rules to the dep-info file if requested
     if !sess.opts.output_types.contains_key(&OutputType::DepInfo) {
@@ -667,13 +683,22 @@ fn write_out_deps(sess: &Session, outputs: &OutputFilenames, out_filenames: &[Pa
     let result = (|| -> io::Result<()> {
         // Build a list of files used to compile the output and
         // write Makefile-compatible dependency rules
-        let files: Vec<String> = sess.source_map()
+        let mut files: Vec<String> = sess.source_map()
             .files()
             .iter()
             .filter(|fmap| fmap.is_real_file())
             .filter(|fmap| !fmap.is_imported())
             .map(|fmap| escape_dep_filename(&fmap.name))
             .collect();
+
+        // Files accessed by proc macros through `proc_macro::tracked_path`.
+        let mut tracked_files: Vec<_> = sess.parse_sess.file_depinfo.borrow().iter()
+            .map(|path| escape_dep_filename(&FileName::Real(PathBuf::from(&*path.as_str()))))
+            .filter(|path| !files.contains(path))
+            .collect();
+        tracked_files.sort();
+        files.extend(tracked_files);
+
         let mut file = fs::File::create(&deps_filename)?;
         for path in out_filenames {
             writeln!(file, "{}: {}\n", path.display(), files.join(" "))?;
@@ -685,6 +710,22 @@ fn write_out_deps(sess: &Session, outputs: &OutputFilenames, out_filenames: &[Pa
         for path in files {
             writeln!(file, "{}:", path)?;
         }
+
+        // Emit special comments with information about accessed environment variables,
+        // from `env!`, `option_env!` and `proc_macro::tracked_env`.
+        let mut env_depinfo: Vec<_> =
+            sess.parse_sess.env_depinfo.borrow().iter().cloned().collect();
+        if !env_depinfo.is_empty() {
+            env_depinfo.sort_by_cached_key(|&(var, _)| var.as_str());
+            writeln!(file)?;
+            for (var, value) in env_depinfo {
+                write!(file, "# env-dep:{}", escape_dep_env(var))?;
+                if let Some(value) = value {
+                    write!(file, "={}", escape_dep_env(value))?;
+                }
+                writeln!(file)?;
+            }
+        }
         Ok(())
     })();
 
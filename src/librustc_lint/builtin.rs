@@ -23,7 +23,7 @@
 
 use rustc::hir::def::{Res, DefKind};
 use rustc::hir::def_id::{DefId, LOCAL_CRATE};
-use rustc::ty::{self, Ty, TyCtxt};
+use rustc::ty::{self, Ty, TyCtxt, layout::{self, LayoutOf}};
 use rustc::{lint, util};
 use hir::Node;
 use util::nodemap::HirIdSet;
@@ -32,6 +32,8 @@ use lint::{LintPass, LateLintPass, EarlyLintPass, EarlyContext};
 
 use rustc::util::nodemap::FxHashSet;
 
+use std::ops::Bound;
+
 use syntax::tokenstream::{TokenTree, TokenStream};
 use syntax::ast;
 use syntax::ptr::P;
@@ -1831,3 +1833,233 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for ExplicitOutlivesRequirements {
         }
     }
 }
+
+declare_lint! {
+    pub INVALID_VALUE,
+    Warn,
+    "an invalid value is being created (such as a null reference)"
+}
+
+declare_lint_pass!(InvalidValue => [INVALID_VALUE]);
+
+impl<'a, 'tcx> LateLintPass<'a, 'tcx> for InvalidValue {
+    fn check_expr(&mut self, cx: &LateContext<'a, 'tcx>, expr: &hir::Expr) {
+
+        #[derive(Debug, Copy, Clone, PartialEq)]
+        enum InitKind { Zeroed, Uninit }
+
+        /// Information about why a type cannot be initialized this way:
+        /// the message, the path of the offending field and its span.
+        type InitError = (String, String, Option<Span>);
+
+        /// Determines whether the given expression creates a zeroed or
+        /// uninitialized value, and if so, which one.
+        fn is_dangerous_init(cx: &LateContext<'_, '_>, expr: &hir::Expr) -> Option<InitKind> {
+            const ZEROED_PATH: &[Symbol] = &[sym::core, sym::mem, sym::zeroed];
+            const UNINIT_PATH: &[Symbol] = &[sym::core, sym::mem, sym::uninitialized];
+            const MU_ZEROED_PATH: &[Symbol] =
+                &[sym::core, sym::mem, sym::maybe_uninit, sym::MaybeUninit, sym::zeroed];
+            const MU_UNINIT_PATH: &[Symbol] =
+                &[sym::core, sym::mem, sym::maybe_uninit, sym::MaybeUninit, sym::uninit];
+            const ASSUME_INIT_PATH: &[Symbol] =
+                &[sym::core, sym::mem, sym::maybe_uninit, sym::MaybeUninit, sym::assume_init];
+
+            fn called_def_id(cx: &LateContext<'_, '_>, expr: &hir::Expr) -> Option<DefId> {
+                if let hir::ExprKind::Call(ref path_expr, _) = expr.node {
+                    if let hir::ExprKind::Path(ref qpath) = path_expr.node {
+                        return cx.tables.qpath_res(qpath, path_expr.hir_id).opt_def_id();
+                    }
+                }
+                None
+            }
+
+            match expr.node {
+                hir::ExprKind::Call(..) => {
+                    let def_id = called_def_id(cx, expr)?;
+                    if cx.match_def_path(def_id, ZEROED_PATH) {
+                        return Some(InitKind::Zeroed);
+                    } else if cx.match_def_path(def_id, UNINIT_PATH) {
+                        return Some(InitKind::Uninit);
+                    }
+                }
+                hir::ExprKind::MethodCall(_, _, ref args) => {
+                    // `MaybeUninit::zeroed().assume_init()` and
+                    // `MaybeUninit::uninit().assume_init()`.
+                    let def_id = cx.tables.type_dependent_def_id(expr.hir_id)?;
+                    if cx.match_def_path(def_id, ASSUME_INIT_PATH) {
+                        let ctor_id = called_def_id(cx, &args[0])?;
+                        if cx.match_def_path(ctor_id, MU_ZEROED_PATH) {
+                            return Some(InitKind::Zeroed);
+                        } else if cx.match_def_path(ctor_id, MU_UNINIT_PATH) {
+                            return Some(InitKind::Uninit);
+                        }
+                    }
+                }
+                _ => {}
+            }
+
+            None
+        }
+
+        /// Whether `value` lies within the (possibly wrap-around) valid range of `scalar`.
+        fn scalar_allows(scalar: &layout::Scalar, value: u128) -> bool {
+            let (start, end) = (*scalar.valid_range.start(), *scalar.valid_range.end());
+            if start <= end {
+                start <= value && value <= end
+            } else {
+                value >= start || value <= end
+            }
+        }
+
+        /// Whether every bit pattern is valid for `scalar`.
+        fn scalar_is_full(cx: &LateContext<'_, '_>, scalar: &layout::Scalar) -> bool {
+            let range = scalar.valid_range_exclusive(&cx.tcx);
+            range.start == range.end
+        }
+
+        /// Whether a scalar with the given validity is unacceptable for `init`.
+        fn scalar_rejects(
+            cx: &LateContext<'_, '_>,
+            scalar: &layout::Scalar,
+            init: InitKind,
+        ) -> bool {
+            match init {
+                InitKind::Zeroed => !scalar_allows(scalar, 0),
+                InitKind::Uninit => !scalar_is_full(cx, scalar),
+            }
+        }
+
+        /// Return `Some` only if we are sure this type does *not*
+        /// allow zero initialization.
+        fn ty_find_init_error<'tcx>(
+            cx: &LateContext<'_, 'tcx>,
+            ty: Ty<'tcx>,
+            init: InitKind,
+            path: String,
+            span: Option<Span>,
+        ) -> Option<InitError> {
+            use rustc::ty::TyKind::*;
+
+            let layout = cx.layout_of(ty).ok()?;
+            if layout.abi.is_uninhabited() {
+                return Some((format!("`{}` has no valid value", ty), path, span));
+            }
+
+            let leaf_scalar_error = |msg: &str| {
+                match layout.abi {
+                    layout::Abi::Scalar(ref scalar) if scalar_rejects(cx, scalar, init) => {
+                        Some((msg.to_string(), path.clone(), span))
+                    }
+                    _ => None,
+                }
+            };
+
+            match ty.sty {
+                Ref(..) => leaf_scalar_error("references must be non-null"),
+                FnPtr(..) => leaf_scalar_error("function pointers must be non-null"),
+                Bool => leaf_scalar_error("booleans must be either `true` or `false`"),
+                Char => leaf_scalar_error("characters must be a valid Unicode codepoint"),
+                Adt(adt_def, _) if adt_def.is_box() => {
+                    leaf_scalar_error("`Box` must be non-null")
+                }
+                Adt(adt_def, substs) if !adt_def.is_union() => {
+                    // Types with a custom valid range, such as `NonNull`.
+                    match cx.tcx.layout_scalar_valid_range(adt_def.did) {
+                        (Bound::Unbounded, Bound::Unbounded) => {}
+                        _ => {
+                            let msg = match init {
+                                InitKind::Zeroed => format!("`{}` must be non-null", ty),
+                                InitKind::Uninit => format!(
+                                    "`{}` must be initialized inside its custom valid range",
+                                    ty,
+                                ),
+                            };
+                            return leaf_scalar_error(&msg);
+                        }
+                    }
+
+                    if adt_def.is_struct() || adt_def.variants.len() == 1 {
+                        // Recurse into the fields of the only variant.
+                        let variant = adt_def.variants.iter().next()?;
+                        return variant.fields.iter().find_map(|field| {
+                            ty_find_init_error(
+                                cx,
+                                field.ty(cx.tcx, substs),
+                                init,
+                                format!("{}.{}", path, field.ident),
+                                Some(cx.tcx.def_span(field.did)),
+                            )
+                        });
+                    }
+
+                    // Multi-variant enums: only an explicit tag is checked,
+                    // niche-encoded discriminants are left alone.
+                    match layout.variants {
+                        layout::Variants::Multiple {
+                            ref discr,
+                            discr_kind: layout::DiscriminantKind::Tag,
+                            ..
+                        } if scalar_rejects(cx, discr, init) => {
+                            let msg = match init {
+                                InitKind::Zeroed => "enums with no variant for a zero \
+                                                     discriminant must not be zeroed",
+                                InitKind::Uninit => "enums must be initialized with a \
+                                                     valid discriminant",
+                            };
+                            Some((msg.to_string(), path, span))
+                        }
+                        _ => None,
+                    }
+                }
+                Tuple(tys) => {
+                    tys.iter().map(|k| k.expect_ty()).enumerate().find_map(|(i, field_ty)| {
+                        ty_find_init_error(cx, field_ty, init, format!("{}.{}", path, i), span)
+                    })
+                }
+                Array(elem_ty, _) if layout.fields.count() > 0 => {
+                    ty_find_init_error(cx, elem_ty, init, format!("{}[0]", path), span)
+                }
+                // Raw pointers, integers, floats, unions and the like accept
+                // any initialized bit pattern; uninitialized integers are not
+                // (yet) linted against here.
+                _ => None,
+            }
+        }
+
+        if let Some(init) = is_dangerous_init(cx, expr) {
+            // This conjures an instance of a type out of nothing,
+            // using zeroed or uninitialized memory.
+            // We are extremely conservative with what we warn about.
+            let conjured_ty = cx.tables.expr_ty(expr);
+            if let Some((msg, path, span)) =
+                ty_find_init_error(cx, conjured_ty, init, String::new(), None)
+            {
+                let mut err = cx.struct_span_lint(
+                    INVALID_VALUE,
+                    expr.span,
+                    &format!(
+                        "the type `{}` does not permit {}",
+                        conjured_ty,
+                        match init {
+                            InitKind::Zeroed => "zero-initialization",
+                            InitKind::Uninit => "being left uninitialized",
+                        },
+                    ),
+                );
+                err.span_label(expr.span, "this code causes undefined behavior when executed");
+                err.span_label(expr.span, "help: use `MaybeUninit<T>` instead");
+                let msg = if path.is_empty() {
+                    msg
+                } else {
+                    format!("{} (in field `{}`)", msg, path)
+                };
+                if let Some(span) = span {
+                    err.span_note(span, &msg);
+                } else {
+                    err.note(&msg);
+                }
+                err.emit();
+            }
+        }
+    }
+}
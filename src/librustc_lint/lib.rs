@@ -129,6 +129,9 @@ macro_rules! late_lint_passes {
             // Tracks state across modules
             UnnameableTestItems: UnnameableTestItems::new(),
 
+            // Tracks foreign items across modules and crates
+            ClashingExternDeclarations: ClashingExternDeclarations::new(),
+
             // Tracks attributes of parents
             MissingDoc: MissingDoc::new(),
 
@@ -145,6 +148,7 @@ macro_rules! late_lint_mod_passes {
         $macro!($args, [
             HardwiredLints: HardwiredLints,
             ImproperCTypes: ImproperCTypes,
+            ImproperCTypesDefinitions: ImproperCTypesDefinitions,
             VariantSizeDifferences: VariantSizeDifferences,
             BoxPointers: BoxPointers,
             PathStatements: PathStatements,
@@ -179,6 +183,7 @@ macro_rules! late_lint_mod_passes {
             UnreachablePub: UnreachablePub,
 
             ExplicitOutlivesRequirements: ExplicitOutlivesRequirements,
+            InvalidValue: InvalidValue,
         ]);
     )
 }
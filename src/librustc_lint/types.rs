@@ -1,14 +1,16 @@
 #![allow(non_snake_case)]
 
 use rustc::hir::{ExprKind, Node};
-use crate::hir::def_id::DefId;
+use crate::hir::def::DefKind;
+use crate::hir::def_id::{CRATE_DEF_INDEX, DefId};
 use rustc::hir::lowering::is_range_literal;
 use rustc::ty::subst::SubstsRef;
-use rustc::ty::{self, AdtKind, ParamEnv, Ty, TyCtxt};
+use rustc::hir::intravisit::FnKind;
+use rustc::ty::{self, AdtKind, ParamEnv, Ty, TyCtxt, TypeFoldable};
 use rustc::ty::layout::{self, IntegerExt, LayoutOf, VariantIdx, SizeSkeleton};
 use rustc::{lint, util};
 use rustc_data_structures::indexed_vec::Idx;
-use util::nodemap::FxHashSet;
+use util::nodemap::{FxHashMap, FxHashSet};
 use lint::{LateContext, LintContext, LintArray};
 use lint::{LintPass, LateLintPass};
 
@@ -17,7 +19,7 @@ use std::{i8, i16, i32, i64, u8, u16, u32, u64, f32, f64};
 
 use syntax::{ast, attr, source_map};
 use syntax::errors::Applicability;
-use syntax::symbol::sym;
+use syntax::symbol::{Symbol, sym};
 use rustc_target::spec::abi::Abi;
 use syntax_pos::Span;
 
@@ -503,10 +505,29 @@ declare_lint! {
     "proper use of libc types in foreign modules"
 }
 
+declare_lint! {
+    IMPROPER_CTYPES_DEFINITIONS,
+    Allow,
+    "proper use of libc types in Rust-defined `extern` functions and function pointers"
+}
+
 declare_lint_pass!(ImproperCTypes => [IMPROPER_CTYPES]);
 
+declare_lint_pass!(ImproperCTypesDefinitions => [IMPROPER_CTYPES_DEFINITIONS]);
+
+/// Where the types being checked for FFI-safety come from.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CItemKind {
+    /// Items declared in `extern` blocks, checked by `IMPROPER_CTYPES`.
+    Declaration,
+    /// `extern` functions defined in Rust and `extern` function pointers
+    /// stored in `#[repr(C)]` types, checked by `IMPROPER_CTYPES_DEFINITIONS`.
+    Definition,
+}
+
 struct ImproperCTypesVisitor<'a, 'tcx> {
     cx: &'a LateContext<'a, 'tcx>,
+    mode: CItemKind,
 }
 
 enum FfiResult<'tcx> {
@@ -857,23 +878,27 @@ impl<'a, 'tcx> ImproperCTypesVisitor<'a, 'tcx> {
         }
     }
 
-    fn check_type_for_ffi_and_report_errors(&mut self, sp: Span, ty: Ty<'tcx>) {
-        // it is only OK to use this function because extern fns cannot have
-        // any generic types right now:
+    fn check_type_for_ffi_and_report_errors(&mut self, sp: Span, ty: Ty<'tcx>, descr: &str) {
+        // it is only OK to use this function because the callers make sure
+        // that no generic types are involved:
         let ty = self.cx.tcx.normalize_erasing_regions(ParamEnv::reveal_all(), ty);
+        let lint = match self.mode {
+            CItemKind::Declaration => IMPROPER_CTYPES,
+            CItemKind::Definition => IMPROPER_CTYPES_DEFINITIONS,
+        };
 
         match self.check_type_for_ffi(&mut FxHashSet::default(), ty) {
             FfiResult::FfiSafe => {}
             FfiResult::FfiPhantom(ty) => {
-                self.cx.span_lint(IMPROPER_CTYPES,
+                self.cx.span_lint(lint,
                                   sp,
-                                  &format!("`extern` block uses type `{}` which is not FFI-safe: \
-                                            composed only of PhantomData", ty));
+                                  &format!("{} uses type `{}` which is not FFI-safe: \
+                                            composed only of PhantomData", descr, ty));
             }
             FfiResult::FfiUnsafe { ty: unsafe_ty, reason, help } => {
-                let msg = format!("`extern` block uses type `{}` which is not FFI-safe: {}",
-                                  unsafe_ty, reason);
-                let mut diag = self.cx.struct_span_lint(IMPROPER_CTYPES, sp, &msg);
+                let msg = format!("{} uses type `{}` which is not FFI-safe: {}",
+                                  descr, unsafe_ty, reason);
+                let mut diag = self.cx.struct_span_lint(lint, sp, &msg);
                 if let Some(s) = help {
                     diag.help(s);
                 }
@@ -887,7 +912,7 @@ impl<'a, 'tcx> ImproperCTypesVisitor<'a, 'tcx> {
         }
     }
 
-    fn check_foreign_fn(&mut self, id: hir::HirId, decl: &hir::FnDecl) {
+    fn check_foreign_fn(&mut self, id: hir::HirId, decl: &hir::FnDecl, descr: &str) {
         let def_id = self.cx.tcx.hir().local_def_id(id);
         let sig = self.cx.tcx.fn_sig(def_id);
         let sig = self.cx.tcx.erase_late_bound_regions(&sig);
@@ -900,13 +925,13 @@ impl<'a, 'tcx> ImproperCTypesVisitor<'a, 'tcx> {
         };
 
         for (input_ty, input_hir) in inputs.iter().zip(&decl.inputs) {
-            self.check_type_for_ffi_and_report_errors(input_hir.span, input_ty);
+            self.check_type_for_ffi_and_report_errors(input_hir.span, input_ty, descr);
         }
 
         if let hir::Return(ref ret_hir) = decl.output {
             let ret_ty = sig.output();
             if !ret_ty.is_unit() {
-                self.check_type_for_ffi_and_report_errors(ret_hir.span, ret_ty);
+                self.check_type_for_ffi_and_report_errors(ret_hir.span, ret_ty, descr);
             }
         }
     }
@@ -914,18 +939,40 @@ impl<'a, 'tcx> ImproperCTypesVisitor<'a, 'tcx> {
     fn check_foreign_static(&mut self, id: hir::HirId, span: Span) {
         let def_id = self.cx.tcx.hir().local_def_id(id);
         let ty = self.cx.tcx.type_of(def_id);
-        self.check_type_for_ffi_and_report_errors(span, ty);
+        self.check_type_for_ffi_and_report_errors(span, ty, "`extern` block");
+    }
+
+    /// Checks every `extern` function pointer type reachable from `ty`
+    /// without going through another function pointer.
+    fn check_extern_fn_ptrs(&mut self, span: Span, ty: Ty<'tcx>) {
+        let mut walker = ty.walk();
+        while let Some(t) = walker.next() {
+            if let ty::FnPtr(sig) = t.sty {
+                if !is_rust_abi(sig.abi()) {
+                    self.check_type_for_ffi_and_report_errors(span, t, "`extern` fn pointer");
+                }
+                // Nested function pointers are checked as part of this one.
+                walker.skip_current_subtree();
+            }
+        }
+    }
+}
+
+fn is_rust_abi(abi: Abi) -> bool {
+    match abi {
+        Abi::Rust | Abi::RustCall | Abi::RustIntrinsic | Abi::PlatformIntrinsic => true,
+        _ => false,
     }
 }
 
 impl<'a, 'tcx> LateLintPass<'a, 'tcx> for ImproperCTypes {
     fn check_foreign_item(&mut self, cx: &LateContext<'_, '_>, it: &hir::ForeignItem) {
-        let mut vis = ImproperCTypesVisitor { cx };
+        let mut vis = ImproperCTypesVisitor { cx, mode: CItemKind::Declaration };
         let abi = cx.tcx.hir().get_foreign_abi(it.hir_id);
         if abi != Abi::RustIntrinsic && abi != Abi::PlatformIntrinsic {
             match it.node {
                 hir::ForeignItemKind::Fn(ref decl, _, _) => {
-                    vis.check_foreign_fn(it.hir_id, decl);
+                    vis.check_foreign_fn(it.hir_id, decl, "`extern` block");
                 }
                 hir::ForeignItemKind::Static(ref ty, _) => {
                     vis.check_foreign_static(it.hir_id, ty.span);
@@ -936,6 +983,290 @@ impl<'a, 'tcx> LateLintPass<'a, 'tcx> for ImproperCTypes {
     }
 }
 
+impl<'a, 'tcx> LateLintPass<'a, 'tcx> for ImproperCTypesDefinitions {
+    fn check_fn(
+        &mut self,
+        cx: &LateContext<'a, 'tcx>,
+        kind: FnKind<'tcx>,
+        decl: &'tcx hir::FnDecl,
+        _: &'tcx hir::Body,
+        _: Span,
+        hir_id: hir::HirId,
+    ) {
+        let abi = match kind.header() {
+            Some(header) => header.abi,
+            // Closures always use the Rust ABI.
+            None => return,
+        };
+        if is_rust_abi(abi) {
+            return;
+        }
+
+        // Generic functions cannot be called from C directly, and their
+        // signatures cannot be checked before monomorphization.
+        let def_id = cx.tcx.hir().local_def_id(hir_id);
+        if cx.tcx.generics_of(def_id).requires_monomorphization(cx.tcx) {
+            return;
+        }
+
+        let mut vis = ImproperCTypesVisitor { cx, mode: CItemKind::Definition };
+        vis.check_foreign_fn(hir_id, decl, "`extern` fn");
+    }
+
+    fn check_item(&mut self, cx: &LateContext<'a, 'tcx>, item: &'tcx hir::Item) {
+        let variant_data = match item.node {
+            hir::ItemKind::Struct(ref variant_data, _) |
+            hir::ItemKind::Union(ref variant_data, _) => variant_data,
+            _ => return,
+        };
+        let def_id = cx.tcx.hir().local_def_id(item.hir_id);
+        if !cx.tcx.adt_def(def_id).repr.c() {
+            return;
+        }
+
+        let mut vis = ImproperCTypesVisitor { cx, mode: CItemKind::Definition };
+        for field in variant_data.fields() {
+            let field_ty = cx.tcx.type_of(cx.tcx.hir().local_def_id(field.hir_id));
+            if field_ty.needs_subst() {
+                continue;
+            }
+            vis.check_extern_fn_ptrs(field.span, field_ty);
+        }
+    }
+}
+
+declare_lint! {
+    CLASHING_EXTERN_DECLARATIONS,
+    Warn,
+    "detects when an extern item has been declared with the same name but a different type"
+}
+
+pub struct ClashingExternDeclarations {
+    /// The first declaration seen for each link name.
+    seen_decls: FxHashMap<Symbol, DefId>,
+    /// Whether the foreign items of upstream crates have been recorded yet.
+    seen_upstream: bool,
+}
+
+impl_lint_pass!(ClashingExternDeclarations => [CLASHING_EXTERN_DECLARATIONS]);
+
+impl ClashingExternDeclarations {
+    pub fn new() -> Self {
+        ClashingExternDeclarations {
+            seen_decls: FxHashMap::default(),
+            seen_upstream: false,
+        }
+    }
+
+    /// Records the foreign items of the crates this crate directly depends on,
+    /// so that local declarations are also checked against them. Indirect
+    /// dependencies (such as the `libc` crate behind `std`) are not considered,
+    /// as their declarations are not part of this crate's FFI surface.
+    fn record_upstream_decls(&mut self, tcx: TyCtxt<'_>) {
+        for &cnum in tcx.crates().iter() {
+            let crate_root = DefId { krate: cnum, index: CRATE_DEF_INDEX };
+            if !tcx.extern_crate(crate_root).map_or(false, |krate| krate.direct) {
+                continue;
+            }
+            for module in tcx.foreign_modules(cnum).iter() {
+                for &def_id in &module.foreign_items {
+                    if let Some(name) = clashable_link_name(tcx, def_id) {
+                        self.seen_decls.entry(name).or_insert(def_id);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns the symbol name a foreign item links against, or `None` for
+/// items which cannot clash (foreign types and intrinsics).
+fn clashable_link_name(tcx: TyCtxt<'_>, def_id: DefId) -> Option<Symbol> {
+    match tcx.def_kind(def_id) {
+        Some(DefKind::Fn) => {
+            let abi = tcx.fn_sig(def_id).abi();
+            if abi == Abi::RustIntrinsic || abi == Abi::PlatformIntrinsic {
+                return None;
+            }
+        }
+        Some(DefKind::Static) => {}
+        _ => return None,
+    }
+    Some(tcx.codegen_fn_attrs(def_id).link_name.unwrap_or_else(|| tcx.item_name(def_id)))
+}
+
+/// If `ty` is an `Option`-like enum which is guaranteed to use the
+/// "nullable pointer optimization", returns the type of its payload.
+fn nullable_ptr_payload<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Option<Ty<'tcx>> {
+    if let ty::Adt(def, substs) = ty.sty {
+        if def.is_enum() && is_repr_nullable_ptr(tcx, ty, def, substs) {
+            return def.all_fields().next().map(|field| field.ty(tcx, substs));
+        }
+    }
+    None
+}
+
+/// Strips `#[repr(transparent)]` newtypes down to their non-zero-sized field.
+fn strip_transparent<'tcx>(tcx: TyCtxt<'tcx>, mut ty: Ty<'tcx>) -> Ty<'tcx> {
+    while let ty::Adt(def, substs) = ty.sty {
+        if !def.repr.transparent() || def.is_union() {
+            break;
+        }
+        let field = def.all_fields().find(|field| {
+            !is_zst(tcx, field.did, tcx.normalize_erasing_regions(
+                ParamEnv::reveal_all(),
+                field.ty(tcx, substs),
+            ))
+        });
+        match field {
+            Some(field) => ty = field.ty(tcx, substs),
+            None => break,
+        }
+    }
+    ty
+}
+
+/// Checks whether two types are FFI-equivalent, i.e. whether passing one
+/// where the other is expected is guaranteed to work across the FFI boundary.
+/// `seen` breaks cycles through recursive types.
+fn structurally_same_type<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    a: Ty<'tcx>,
+    b: Ty<'tcx>,
+    seen: &mut FxHashSet<(Ty<'tcx>, Ty<'tcx>)>,
+) -> bool {
+    let normalize = |ty| {
+        let ty = tcx.normalize_erasing_regions(ParamEnv::reveal_all(), ty);
+        let ty = nullable_ptr_payload(tcx, ty).unwrap_or(ty);
+        strip_transparent(tcx, ty)
+    };
+    let (a, b) = (normalize(a), normalize(b));
+
+    if a == b {
+        return true;
+    }
+    if !seen.insert((a, b)) {
+        // Already being compared further up: assume they match.
+        return true;
+    }
+
+    match (&a.sty, &b.sty) {
+        (&ty::Adt(a_def, a_substs), &ty::Adt(b_def, b_substs)) => {
+            a_def.adt_kind() == b_def.adt_kind()
+                && a_def.repr == b_def.repr
+                && a_def.variants.len() == b_def.variants.len()
+                && (!a_def.is_enum() || a_def.discriminants(tcx).map(|(_, discr)| discr.val)
+                    .eq(b_def.discriminants(tcx).map(|(_, discr)| discr.val)))
+                && a_def.variants.iter().zip(b_def.variants.iter()).all(|(a_var, b_var)| {
+                    a_var.fields.len() == b_var.fields.len()
+                        && a_var.fields.iter().zip(b_var.fields.iter()).all(|(a_f, b_f)| {
+                            structurally_same_type(
+                                tcx,
+                                a_f.ty(tcx, a_substs),
+                                b_f.ty(tcx, b_substs),
+                                seen,
+                            )
+                        })
+                })
+        }
+        (&ty::Array(a_ty, a_len), &ty::Array(b_ty, b_len)) => {
+            a_len == b_len && structurally_same_type(tcx, a_ty, b_ty, seen)
+        }
+        (&ty::Slice(a_ty), &ty::Slice(b_ty)) => structurally_same_type(tcx, a_ty, b_ty, seen),
+        (&ty::RawPtr(a_tm), &ty::RawPtr(b_tm)) => {
+            a_tm.mutbl == b_tm.mutbl && structurally_same_type(tcx, a_tm.ty, b_tm.ty, seen)
+        }
+        (&ty::Ref(_, a_ty, a_mutbl), &ty::Ref(_, b_ty, b_mutbl)) => {
+            a_mutbl == b_mutbl && structurally_same_type(tcx, a_ty, b_ty, seen)
+        }
+        // References are passed as plain pointers.
+        (&ty::Ref(_, ref_ty, mutbl), &ty::RawPtr(tm)) |
+        (&ty::RawPtr(tm), &ty::Ref(_, ref_ty, mutbl)) => {
+            mutbl == tm.mutbl && structurally_same_type(tcx, ref_ty, tm.ty, seen)
+        }
+        (&ty::FnPtr(a_sig), &ty::FnPtr(b_sig)) => same_fn_sig(tcx, a_sig, b_sig, seen),
+        (&ty::Tuple(a_tys), &ty::Tuple(b_tys)) => {
+            a_tys.len() == b_tys.len()
+                && a_tys.iter().zip(b_tys.iter()).all(|(a_ty, b_ty)| {
+                    structurally_same_type(tcx, a_ty.expect_ty(), b_ty.expect_ty(), seen)
+                })
+        }
+        // Everything else (scalars, foreign types, ...) only matches itself.
+        _ => false,
+    }
+}
+
+fn same_fn_sig<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    a: ty::PolyFnSig<'tcx>,
+    b: ty::PolyFnSig<'tcx>,
+    seen: &mut FxHashSet<(Ty<'tcx>, Ty<'tcx>)>,
+) -> bool {
+    let a = tcx.erase_late_bound_regions(&a);
+    let b = tcx.erase_late_bound_regions(&b);
+    a.abi == b.abi
+        && a.c_variadic == b.c_variadic
+        && a.inputs().len() == b.inputs().len()
+        && a.inputs_and_output.iter().zip(b.inputs_and_output.iter()).all(|(&a_ty, &b_ty)| {
+            structurally_same_type(tcx, a_ty, b_ty, seen)
+        })
+}
+
+/// Checks whether two foreign items can be linked against the same symbol.
+fn same_foreign_decl(tcx: TyCtxt<'_>, a: DefId, b: DefId) -> bool {
+    let mut seen = FxHashSet::default();
+    match (tcx.def_kind(a), tcx.def_kind(b)) {
+        (Some(DefKind::Fn), Some(DefKind::Fn)) => {
+            same_fn_sig(tcx, tcx.fn_sig(a), tcx.fn_sig(b), &mut seen)
+        }
+        (Some(DefKind::Static), Some(DefKind::Static)) => {
+            structurally_same_type(tcx, tcx.type_of(a), tcx.type_of(b), &mut seen)
+        }
+        _ => false,
+    }
+}
+
+impl<'a, 'tcx> LateLintPass<'a, 'tcx> for ClashingExternDeclarations {
+    fn check_foreign_item(&mut self, cx: &LateContext<'_, '_>, it: &hir::ForeignItem) {
+        let tcx = cx.tcx;
+        if !self.seen_upstream {
+            self.seen_upstream = true;
+            self.record_upstream_decls(tcx);
+        }
+
+        let def_id = tcx.hir().local_def_id(it.hir_id);
+        let name = match clashable_link_name(tcx, def_id) {
+            Some(name) => name,
+            None => return,
+        };
+        let existing = *self.seen_decls.entry(name).or_insert(def_id);
+        if existing == def_id || same_foreign_decl(tcx, existing, def_id) {
+            return;
+        }
+
+        let describe = |did| match tcx.def_kind(did) {
+            Some(DefKind::Fn) => tcx.fn_sig(did).to_string(),
+            _ => tcx.type_of(did).to_string(),
+        };
+        let (msg, label) = if let hir::ForeignItemKind::Fn(..) = it.node {
+            ("redeclared with a different signature",
+             "this signature doesn't match the previous declaration")
+        } else {
+            ("redeclared with a different type",
+             "this type doesn't match the previous declaration")
+        };
+        let mut err = cx.struct_span_lint(
+            CLASHING_EXTERN_DECLARATIONS,
+            it.span,
+            &format!("`{}` {}", name, msg),
+        );
+        err.span_label(tcx.def_span(existing), format!("`{}` previously declared here", name));
+        err.span_label(it.span, label);
+        err.note(&format!("expected `{}`\n   found `{}`", describe(existing), describe(def_id)));
+        err.emit();
+    }
+}
+
 declare_lint_pass!(VariantSizeDifferences => [VARIANT_SIZE_DIFFERENCES]);
 
 impl<'a, 'tcx> LateLintPass<'a, 'tcx> for VariantSizeDifferences {
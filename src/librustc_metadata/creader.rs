@@ -2,6 +2,7 @@
 
 use crate::cstore::{self, CStore, CrateSource, MetadataBlob};
 use crate::locator::{self, CratePaths};
+use crate::proc_macro_host;
 use crate::decoder::proc_macro_def_path_table;
 use crate::schema::CrateRoot;
 use rustc_data_structures::sync::{Lrc, RwLock, Lock};
@@ -588,7 +589,7 @@ impl<'a> CrateLoader<'a> {
         use crate::dynamic_lib::DynamicLibrary;
         use proc_macro::bridge::client::ProcMacro;
         use syntax_ext::deriving::custom::ProcMacroDerive;
-        use syntax_ext::proc_macro_impl::{AttrProcMacro, BangProcMacro};
+        use syntax_ext::proc_macro_impl::{AttrProcMacro, BangProcMacro, ProcMacroClient};
 
         let path = match dylib {
             Some(dylib) => dylib,
@@ -596,12 +597,17 @@ impl<'a> CrateLoader<'a> {
         };
         // Make sure the path contains a / or the linker will search for it.
         let path = env::current_dir().unwrap().join(path);
+        let sym = self.sess.generate_proc_macro_decls_symbol(root.disambiguator);
+
+        if self.sess.opts.debugging_opts.out_of_process_proc_macros {
+            return self.load_remote_derive_macros(root, path, sym, span);
+        }
+
         let lib = match DynamicLibrary::open(Some(&path)) {
             Ok(lib) => lib,
             Err(err) => self.sess.span_fatal(span, &err),
         };
 
-        let sym = self.sess.generate_proc_macro_decls_symbol(root.disambiguator);
         let decls = unsafe {
             let sym = match lib.symbol(&sym) {
                 Ok(f) => f,
@@ -618,17 +624,19 @@ impl<'a> CrateLoader<'a> {
                     (
                         trait_name,
                         SyntaxExtensionKind::Derive(Box::new(ProcMacroDerive {
-                            client, attrs: helper_attrs.clone()
+                            client: ProcMacroClient::Local(client), attrs: helper_attrs.clone()
                         })),
                         helper_attrs,
                     )
                 }
-                ProcMacro::Attr { name, client } => (
-                    name, SyntaxExtensionKind::Attr(Box::new(AttrProcMacro { client })), Vec::new()
-                ),
-                ProcMacro::Bang { name, client } => (
-                    name, SyntaxExtensionKind::Bang(Box::new(BangProcMacro { client })), Vec::new()
-                )
+                ProcMacro::Attr { name, client } => {
+                    let client = ProcMacroClient::Local(client);
+                    (name, SyntaxExtensionKind::Attr(Box::new(AttrProcMacro { client })), Vec::new())
+                }
+                ProcMacro::Bang { name, client } => {
+                    let client = ProcMacroClient::Local(client);
+                    (name, SyntaxExtensionKind::Bang(Box::new(BangProcMacro { client })), Vec::new())
+                }
             };
 
             (Symbol::intern(name), Lrc::new(SyntaxExtension {
@@ -644,6 +652,63 @@ impl<'a> CrateLoader<'a> {
         extensions
     }
 
+    /// Like `load_derive_macros`, but the dylib is loaded into a proc macro
+    /// host process, which then runs the macros for us.
+    fn load_remote_derive_macros(&mut self,
+                                 root: &CrateRoot<'_>,
+                                 path: PathBuf,
+                                 symbol: String,
+                                 span: Span)
+                                 -> Vec<(ast::Name, Lrc<SyntaxExtension>)> {
+        use proc_macro::bridge::process::ProcMacroDecl;
+        use syntax_ext::deriving::custom::ProcMacroDerive;
+        use syntax_ext::proc_macro_impl::{AttrProcMacro, BangProcMacro};
+        use syntax_ext::proc_macro_impl::{ProcMacroClient, RemoteProcMacro};
+
+        let host = self.cstore.proc_macro_host.lock()
+            .get_or_insert_with(|| proc_macro_host::spawn(self.sess, span))
+            .clone();
+        let decls = match host.load(&path, &symbol) {
+            Ok(decls) => decls,
+            Err(err) => self.sess.span_fatal(span, &err.to_string()),
+        };
+
+        decls.into_iter().enumerate().map(|(index, decl)| {
+            let remote = RemoteProcMacro {
+                host: host.clone(),
+                dylib: path.clone(),
+                symbol: symbol.clone(),
+                index: index as u32,
+            };
+            let (name, kind, helper_attrs) = match decl {
+                ProcMacroDecl::CustomDerive { trait_name, attributes } => {
+                    let helper_attrs =
+                        attributes.iter().map(|attr| Symbol::intern(attr)).collect::<Vec<_>>();
+                    (
+                        trait_name,
+                        SyntaxExtensionKind::Derive(Box::new(ProcMacroDerive {
+                            client: ProcMacroClient::Remote(remote), attrs: helper_attrs.clone()
+                        })),
+                        helper_attrs,
+                    )
+                }
+                ProcMacroDecl::Attr { name } => {
+                    let client = ProcMacroClient::Remote(remote);
+                    (name, SyntaxExtensionKind::Attr(Box::new(AttrProcMacro { client })), Vec::new())
+                }
+                ProcMacroDecl::Bang { name } => {
+                    let client = ProcMacroClient::Remote(remote);
+                    (name, SyntaxExtensionKind::Bang(Box::new(BangProcMacro { client })), Vec::new())
+                }
+            };
+
+            (Symbol::intern(&name), Lrc::new(SyntaxExtension {
+                helper_attrs,
+                ..SyntaxExtension::default(kind, root.edition)
+            }))
+        }).collect()
+    }
+
     /// Look for a plugin registrar. Returns library path, crate
     /// SVH and DefIndex of the registrar function.
     pub fn find_plugin_registrar(&mut self,
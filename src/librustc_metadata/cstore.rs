@@ -10,6 +10,7 @@ use rustc_data_structures::indexed_vec::IndexVec;
 use rustc::util::nodemap::{FxHashMap, NodeMap};
 
 use rustc_data_structures::sync::{Lrc, RwLock, Lock};
+use proc_macro::bridge::process::ProcessServer;
 use syntax::ast;
 use syntax::ext::base::SyntaxExtension;
 use syntax::symbol::Symbol;
@@ -93,6 +94,9 @@ pub struct CStore {
     metas: RwLock<IndexVec<CrateNum, Option<Lrc<CrateMetadata>>>>,
     /// Map from NodeId's of local extern crate statements to crate numbers
     extern_mod_crate_map: Lock<NodeMap<CrateNum>>,
+    /// Host process running proc macros, with `-Z out-of-process-proc-macros`.
+    /// Spawned when the first proc macro crate is loaded.
+    crate proc_macro_host: Lock<Option<Lrc<ProcessServer>>>,
     pub metadata_loader: Box<dyn MetadataLoader + Sync>,
 }
 
@@ -110,6 +114,7 @@ impl CStore {
             // `None`.
             metas: RwLock::new(IndexVec::from_elem_n(None, 1)),
             extern_mod_crate_map: Default::default(),
+            proc_macro_host: Default::default(),
             metadata_loader,
         }
     }
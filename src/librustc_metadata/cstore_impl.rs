@@ -34,7 +34,7 @@ use syntax::ext::base::{SyntaxExtension, SyntaxExtensionKind};
 use syntax::parse::source_file_to_stream;
 use syntax::parse::parser::emit_unclosed_delims;
 use syntax::symbol::{Symbol, sym};
-use syntax_ext::proc_macro_impl::BangProcMacro;
+use syntax_ext::proc_macro_impl::{BangProcMacro, ProcMacroClient};
 use syntax_pos::{Span, NO_EXPANSION, FileName};
 use rustc_data_structures::bit_set::BitSet;
 
@@ -430,6 +430,7 @@ impl cstore::CStore {
             return LoadedMacro::ProcMacro(proc_macros[id.index.to_proc_macro_index()].1.clone());
         } else if data.name == sym::proc_macro && data.item_name(id.index) == sym::quote {
             let client = proc_macro::bridge::client::Client::expand1(proc_macro::quote);
+            let client = ProcMacroClient::Local(client);
             let kind = SyntaxExtensionKind::Bang(Box::new(BangProcMacro { client }));
             let ext = SyntaxExtension {
                 allow_internal_unstable: Some([sym::proc_macro_def_site][..].into()),
@@ -8,6 +8,7 @@
 #![feature(proc_macro_internals)]
 #![feature(proc_macro_quote)]
 #![feature(rustc_diagnostic_macros)]
+#![feature(set_stdio)]
 #![feature(crate_visibility_modifier)]
 #![feature(specialization)]
 #![feature(rustc_private)]
@@ -42,6 +43,7 @@ pub mod creader;
 pub mod cstore;
 pub mod dynamic_lib;
 pub mod locator;
+pub mod proc_macro_host;
 
 pub fn validate_crate_name(
     sess: Option<&rustc::session::Session>,
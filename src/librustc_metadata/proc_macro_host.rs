@@ -0,0 +1,129 @@
+//! Support for running proc macros in a separate host process
+//! (`-Z out-of-process-proc-macros`), see `proc_macro::bridge::process`.
+//!
+//! The host is, by default, the running rustc itself, started with
+//! `HOST_FLAG` as its only argument (see `rustc_driver::main`).
+
+use crate::dynamic_lib::DynamicLibrary;
+
+use proc_macro::bridge::client::ProcMacro;
+use proc_macro::bridge::process::{self, ProcessServer};
+use rustc::session::Session;
+use rustc_data_structures::sync::Lrc;
+use syntax_pos::Span;
+
+use std::{env, io, mem};
+use std::process::Command;
+
+/// Command line flag which turns rustc into a proc macro host.
+pub const HOST_FLAG: &str = "--proc-macro-host";
+
+/// Spawns a proc macro host, as configured by `-Z proc-macro-host`.
+pub fn spawn(sess: &Session, span: Span) -> Lrc<ProcessServer> {
+    let program = match sess.opts.debugging_opts.proc_macro_host {
+        Some(ref program) => program.clone(),
+        None => match env::current_exe() {
+            Ok(exe) => exe,
+            Err(err) => sess.span_fatal(span, &format!(
+                "couldn't find rustc to run as a proc macro host: {}", err)),
+        },
+    };
+    let mut command = Command::new(&program);
+    command.arg(HOST_FLAG);
+    match ProcessServer::spawn(command) {
+        Ok(host) => Lrc::new(host),
+        Err(err) => sess.span_fatal(span, &format!(
+            "couldn't start proc macro host `{}`: {}", program.display(), err)),
+    }
+}
+
+/// Entry point of a proc macro host, serving the compiler that spawned it
+/// over stdin and stdout. Returns the exit code of the process.
+pub fn main() -> i32 {
+    let output = match stdio::take_stdout() {
+        Ok(output) => output,
+        Err(err) => {
+            eprintln!("error: proc macro host: couldn't set up stdout: {}", err);
+            return 1;
+        }
+    };
+
+    let stdin = io::stdin();
+    let result = process::run_host(stdin.lock(), io::BufWriter::new(output), |path, symbol| {
+        let lib = DynamicLibrary::open(Some(path))?;
+        let decls = unsafe { *(lib.symbol(symbol)? as *const &'static [ProcMacro]) };
+
+        // Intentionally leak the dynamic library, see `CrateLoader::load_derive_macros`.
+        mem::forget(lib);
+
+        Ok(decls)
+    });
+    match result {
+        Ok(()) => 0,
+        Err(err) => {
+            eprintln!("error: proc macro host: {}", err);
+            1
+        }
+    }
+}
+
+/// Stdout is reserved for the protocol, but proc macros may print to it
+/// (through `println!`, `io::stdout()` or C code alike). So the protocol gets
+/// its own handle to the original stdout, and stdout itself is pointed at
+/// stderr, where the compiler's output goes anyway.
+#[cfg(unix)]
+mod stdio {
+    use std::fs::File;
+    use std::io;
+    use std::os::unix::io::FromRawFd;
+
+    pub fn take_stdout() -> io::Result<File> {
+        unsafe {
+            let fd = libc::fcntl(libc::STDOUT_FILENO, libc::F_DUPFD_CLOEXEC, 3);
+            if fd == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            if libc::dup2(libc::STDERR_FILENO, libc::STDOUT_FILENO) == -1 {
+                let err = io::Error::last_os_error();
+                libc::close(fd);
+                return Err(err);
+            }
+            Ok(File::from_raw_fd(fd))
+        }
+    }
+}
+
+#[cfg(windows)]
+mod stdio {
+    use std::fs::File;
+    use std::io;
+    use std::os::windows::prelude::*;
+
+    use libc::c_void;
+
+    type DWORD = u32;
+    type HANDLE = *mut c_void;
+    type BOOL = i32;
+
+    const STD_OUTPUT_HANDLE: DWORD = -11i32 as DWORD;
+    const STD_ERROR_HANDLE: DWORD = -12i32 as DWORD;
+    const INVALID_HANDLE_VALUE: HANDLE = !0 as HANDLE;
+
+    extern "system" {
+        fn GetStdHandle(nStdHandle: DWORD) -> HANDLE;
+        fn SetStdHandle(nStdHandle: DWORD, hHandle: HANDLE) -> BOOL;
+    }
+
+    pub fn take_stdout() -> io::Result<File> {
+        unsafe {
+            let stdout = GetStdHandle(STD_OUTPUT_HANDLE);
+            if stdout == INVALID_HANDLE_VALUE || stdout.is_null() {
+                return Err(io::Error::last_os_error());
+            }
+            if SetStdHandle(STD_OUTPUT_HANDLE, GetStdHandle(STD_ERROR_HANDLE)) == 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(File::from_raw_handle(stdout as RawHandle))
+        }
+    }
+}
@@ -12,18 +12,20 @@ use rustc::hir::def_id::DefId;
 use rustc::mir::interpret::{ConstEvalErr, ErrorHandled, ScalarMaybeUndef};
 use rustc::mir;
 use rustc::ty::{self, TyCtxt};
-use rustc::ty::layout::{self, LayoutOf, VariantIdx};
+use rustc::ty::layout::{self, Align, LayoutOf, Size, VariantIdx};
 use rustc::ty::subst::Subst;
 use rustc::traits::Reveal;
 use rustc_data_structures::fx::FxHashMap;
 
+use syntax::attr;
 use syntax::source_map::{Span, DUMMY_SP};
+use syntax::symbol::sym;
 
 use crate::interpret::{self,
     PlaceTy, MPlaceTy, OpTy, ImmTy, Immediate, Scalar,
     RawConst, ConstValue,
     InterpResult, InterpErrorInfo, InterpError, GlobalId, InterpCx, StackPopCleanup,
-    Allocation, AllocId, MemoryKind,
+    Allocation, AllocId,
     snapshot, RefTracking, intern_const_alloc_recursive,
 };
 
@@ -152,7 +154,7 @@ fn eval_body_using_ecx<'mir, 'tcx>(
     let tcx = ecx.tcx.tcx;
     let layout = ecx.layout_of(body.return_ty().subst(tcx, cid.instance.substs))?;
     assert!(!layout.is_unsized());
-    let ret = ecx.allocate(layout, MemoryKind::Stack);
+    let ret = ecx.allocate(layout, interpret::MemoryKind::Stack);
 
     let name = ty::tls::with(|tcx| tcx.def_path_str(cid.instance.def_id()));
     let prom = cid.promoted.map_or(String::new(), |p| format!("::promoted[{:?}]", p));
@@ -228,6 +230,11 @@ pub struct CompileTimeInterpreter<'mir, 'tcx> {
     /// detector period.
     pub(super) steps_since_detector_enabled: isize,
 
+    /// The total number of terminators executed so far, checked against
+    /// `-Z const-eval-step-limit` so that loops which never revisit the same
+    /// state still terminate with an error.
+    pub(super) steps_taken: u64,
+
     /// Extra state to detect loops.
     pub(super) loop_detector: snapshot::InfiniteLoopDetector<'mir, 'tcx>,
 }
@@ -237,6 +244,7 @@ impl<'mir, 'tcx> CompileTimeInterpreter<'mir, 'tcx> {
         CompileTimeInterpreter {
             loop_detector: Default::default(),
             steps_since_detector_enabled: -STEPS_UNTIL_DETECTOR_ENABLED,
+            steps_taken: 0,
         }
     }
 }
@@ -305,16 +313,157 @@ impl<K: Hash + Eq, V> interpret::AllocMap<K, V> for FxHashMap<K, V> {
 crate type CompileTimeEvalContext<'mir, 'tcx> =
     InterpCx<'mir, 'tcx, CompileTimeInterpreter<'mir, 'tcx>>;
 
-impl interpret::MayLeak for ! {
+/// Extra memory kinds used by the CTFE machine.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+pub enum MemoryKind {
+    /// Memory allocated by `const_allocate` or `box`. It must either be deallocated before
+    /// evaluation finishes, or become part of the final value as immutable memory.
+    Heap,
+}
+
+impl interpret::MayLeak for MemoryKind {
     #[inline(always)]
     fn may_leak(self) -> bool {
-        // `self` is uninhabited
-        self
+        match self {
+            // Heap allocations that are still live at the end of evaluation are handled by
+            // interning, which either makes them part of the final value or rejects them.
+            MemoryKind::Heap => false,
+        }
+    }
+}
+
+fn heap_align<'tcx>(align: u64) -> InterpResult<'tcx, Align> {
+    match Align::from_bytes(align) {
+        Ok(align) => Ok(align),
+        Err(_) => err!(HeapAllocNonPowerOfTwoAlignment(align)),
+    }
+}
+
+/// Reads the size and alignment out of a `core::alloc::Layout`.
+fn read_layout<'mir, 'tcx>(
+    ecx: &CompileTimeEvalContext<'mir, 'tcx>,
+    layout: OpTy<'tcx>,
+) -> InterpResult<'tcx, (Size, Align)> {
+    let size = ecx.read_scalar(ecx.operand_field(layout, 0)?)?.to_usize(ecx)?;
+    let align = ecx.read_scalar(ecx.operand_field(layout, 1)?)?.to_usize(ecx)?;
+    Ok((Size::from_bytes(size), heap_align(align)?))
+}
+
+/// Evaluates the heap intrinsics and the `#[rustc_const_heap]` functions of liballoc using
+/// `MemoryKind::Heap` memory, instead of running the bodies of the latter, which call into the
+/// global allocator. Returns `false` if `name` is neither.
+///
+/// Field indices follow the declarations of `Vec` and `RawVec`.
+fn emulate_heap_fn<'mir, 'tcx>(
+    ecx: &mut CompileTimeEvalContext<'mir, 'tcx>,
+    name: &str,
+    instance: ty::Instance<'tcx>,
+    args: &[OpTy<'tcx>],
+    dest: Option<PlaceTy<'tcx>>,
+) -> InterpResult<'tcx, bool> {
+    let kind = interpret::MemoryKind::Machine(MemoryKind::Heap);
+    match name {
+        "const_allocate" => {
+            let size = Size::from_bytes(ecx.read_scalar(args[0])?.to_usize(ecx)?);
+            let align = heap_align(ecx.read_scalar(args[1])?.to_usize(ecx)?)?;
+            let ptr = ecx.memory_mut().allocate(size, align, kind);
+            ecx.write_scalar(Scalar::Ptr(ptr), dest.unwrap())?;
+        }
+        "const_deallocate" => {
+            let ptr = ecx.read_scalar(args[0])?.to_ptr()?;
+            let size = Size::from_bytes(ecx.read_scalar(args[1])?.to_usize(ecx)?);
+            let align = heap_align(ecx.read_scalar(args[2])?.to_usize(ecx)?)?;
+            ecx.memory_mut().deallocate(ptr, Some((size, align)), kind)?;
+        }
+        "alloc" | "alloc_zeroed" => {
+            let (size, align) = read_layout(ecx, args[0])?;
+            let ptr = if name == "alloc_zeroed" {
+                let bytes = vec![0; size.bytes() as usize];
+                ecx.memory_mut().allocate_with(Allocation::from_bytes(bytes, align), kind)
+            } else {
+                ecx.memory_mut().allocate(size, align, kind)
+            };
+            ecx.write_scalar(Scalar::Ptr(ptr), dest.unwrap())?;
+        }
+        "dealloc" => {
+            let ptr = ecx.read_scalar(args[0])?.to_ptr()?;
+            let (size, align) = read_layout(ecx, args[1])?;
+            ecx.memory_mut().deallocate(ptr, Some((size, align)), kind)?;
+        }
+        "realloc" => {
+            let ptr = ecx.read_scalar(args[0])?.to_ptr()?;
+            let (old_size, align) = read_layout(ecx, args[1])?;
+            let new_size = Size::from_bytes(ecx.read_scalar(args[2])?.to_usize(ecx)?);
+            let ptr = ecx.memory_mut().reallocate(
+                ptr,
+                Some((old_size, align)),
+                new_size,
+                align,
+                kind,
+            )?;
+            ecx.write_scalar(Scalar::Ptr(ptr), dest.unwrap())?;
+        }
+        "box_new" => {
+            let dest = dest.unwrap();
+            let value = args[0];
+            let ptr = ecx.memory_mut().allocate(value.layout.size, value.layout.align.abi, kind);
+            let boxed = ecx.ref_to_mplace(ImmTy::from_scalar(Scalar::Ptr(ptr), dest.layout))?;
+            ecx.copy_op(value, boxed.into())?;
+            ecx.write_scalar(Scalar::Ptr(ptr), dest)?;
+        }
+        "vec_with_capacity" => {
+            let dest = dest.unwrap();
+            let cap = ecx.read_scalar(args[0])?.to_usize(ecx)?;
+            let elem = ecx.layout_of(instance.substs.type_at(0))?;
+            let ptr_size = ecx.tcx.data_layout.pointer_size;
+            let size = match elem.size.checked_mul(cap, ecx) {
+                Some(size) => size,
+                None => return err!(MachineError("capacity overflow".to_string())),
+            };
+            let ptr = if size.bytes() == 0 {
+                // Like `RawVec`, use a dangling pointer if nothing needs to be allocated.
+                Scalar::from_uint(elem.align.abi.bytes(), ptr_size)
+            } else {
+                Scalar::Ptr(ecx.memory_mut().allocate(size, elem.align.abi, kind))
+            };
+            let buf = ecx.place_field(dest, 0)?;
+            let buf_ptr = ecx.place_field(buf, 0)?;
+            ecx.write_scalar(ptr, buf_ptr)?;
+            let buf_cap = ecx.place_field(buf, 1)?;
+            ecx.write_scalar(Scalar::from_uint(cap, ptr_size), buf_cap)?;
+            let len = ecx.place_field(dest, 1)?;
+            ecx.write_scalar(Scalar::from_uint(0u64, ptr_size), len)?;
+        }
+        "vec_drop" => {
+            // This would drop the elements, but a `Vec` created during evaluation cannot have
+            // any: none of the functions that add elements can be called.
+            let vec = ecx.deref_operand(args[0])?;
+            let len = ecx.read_scalar(ecx.mplace_field(vec, 1)?.into())?.to_usize(ecx)?;
+            if len != 0 {
+                return Err(
+                    ConstEvalError::NeedsRfc("dropping the elements of a `Vec`".to_string())
+                        .into(),
+                );
+            }
+        }
+        "raw_vec_drop" => {
+            let raw_vec = ecx.deref_operand(args[0])?;
+            let ptr = ecx.read_scalar(ecx.mplace_field(raw_vec, 0)?.into())?;
+            let cap = ecx.read_scalar(ecx.mplace_field(raw_vec, 1)?.into())?.to_usize(ecx)?;
+            let elem = ecx.layout_of(instance.substs.type_at(0))?;
+            // `vec_with_capacity` checked that this does not overflow.
+            let size = elem.size * cap;
+            if size.bytes() != 0 {
+                ecx.memory_mut().deallocate(ptr.to_ptr()?, Some((size, elem.align.abi)), kind)?;
+            }
+        }
+        _ => return Ok(false),
     }
+    Ok(true)
 }
 
 impl<'mir, 'tcx> interpret::Machine<'mir, 'tcx> for CompileTimeInterpreter<'mir, 'tcx> {
-    type MemoryKinds = !;
+    type MemoryKinds = MemoryKind;
     type PointerTag = ();
     type ExtraFnVal = !;
 
@@ -322,9 +471,9 @@ impl<'mir, 'tcx> interpret::Machine<'mir, 'tcx> for CompileTimeInterpreter<'mir,
     type MemoryExtra = ();
     type AllocExtra = ();
 
-    type MemoryMap = FxHashMap<AllocId, (MemoryKind<!>, Allocation)>;
+    type MemoryMap = FxHashMap<AllocId, (interpret::MemoryKind<MemoryKind>, Allocation)>;
 
-    const STATIC_KIND: Option<!> = None; // no copying of statics allowed
+    const STATIC_KIND: Option<MemoryKind> = None; // no copying of statics allowed
 
     #[inline(always)]
     fn enforce_validity(_ecx: &InterpCx<'mir, 'tcx, Self>) -> bool {
@@ -341,6 +490,26 @@ impl<'mir, 'tcx> interpret::Machine<'mir, 'tcx> for CompileTimeInterpreter<'mir,
         debug!("eval_fn_call: {:?}", instance);
         // Only check non-glue functions
         if let ty::InstanceDef::Item(def_id) = instance.def {
+            if Some(def_id) == ecx.tcx.lang_items().box_free_fn() {
+                // Boxes created during evaluation live in heap memory allocated by `box_alloc`.
+                let ptr = ecx.read_immediate(args[0])?.to_scalar_ptr()?.to_ptr()?;
+                ecx.memory_mut().deallocate(
+                    ptr,
+                    None,
+                    interpret::MemoryKind::Machine(MemoryKind::Heap),
+                )?;
+                ecx.goto_block(ret)?;
+                return Ok(None);
+            }
+            // The `#[rustc_const_heap]` functions are only callable with `#![feature(const_heap)]`,
+            // see `qualify_consts`.
+            let attrs = ecx.tcx.get_attrs(def_id);
+            if let Some(name) = attr::first_attr_value_str_by_name(&attrs, sym::rustc_const_heap) {
+                if emulate_heap_fn(ecx, &name.as_str(), instance, args, dest)? {
+                    ecx.goto_block(ret)?;
+                    return Ok(None);
+                }
+            }
             // Execution might have wandered off into other crates, so we cannot to a stability-
             // sensitive check here.  But we can at least rule out functions that are not const
             // at all.
@@ -390,8 +559,11 @@ impl<'mir, 'tcx> interpret::Machine<'mir, 'tcx> for CompileTimeInterpreter<'mir,
         if ecx.emulate_intrinsic(instance, args, dest)? {
             return Ok(());
         }
-        // An intrinsic that we do not support
         let intrinsic_name = &ecx.tcx.item_name(instance.def_id()).as_str()[..];
+        if emulate_heap_fn(ecx, intrinsic_name, instance, args, Some(dest))? {
+            return Ok(());
+        }
+        // An intrinsic that we do not support
         Err(
             ConstEvalError::NeedsRfc(format!("calling intrinsic `{}`", intrinsic_name)).into()
         )
@@ -420,7 +592,7 @@ impl<'mir, 'tcx> interpret::Machine<'mir, 'tcx> for CompileTimeInterpreter<'mir,
         _memory_extra: &(),
         _id: AllocId,
         alloc: Cow<'b, Allocation>,
-        _kind: Option<MemoryKind<!>>,
+        _kind: Option<interpret::MemoryKind<MemoryKind>>,
     ) -> (Cow<'b, Allocation<Self::PointerTag>>, Self::PointerTag) {
         // We do not use a tag so we can just cheaply forward the allocation
         (alloc, ())
@@ -435,15 +607,28 @@ impl<'mir, 'tcx> interpret::Machine<'mir, 'tcx> for CompileTimeInterpreter<'mir,
     }
 
     fn box_alloc(
-        _ecx: &mut InterpCx<'mir, 'tcx, Self>,
-        _dest: PlaceTy<'tcx>,
+        ecx: &mut InterpCx<'mir, 'tcx, Self>,
+        dest: PlaceTy<'tcx>,
     ) -> InterpResult<'tcx> {
-        Err(
-            ConstEvalError::NeedsRfc("heap allocations via `box` keyword".to_string()).into(),
-        )
+        trace!("box_alloc for {:?}", dest.layout.ty);
+        let boxed = dest.layout.ty.builtin_deref(false)
+            .expect("box_alloc destination is not a box").ty;
+        let layout = ecx.layout_of(boxed)?;
+        let ptr = ecx.memory_mut().allocate(
+            layout.size,
+            layout.align.abi,
+            interpret::MemoryKind::Machine(MemoryKind::Heap),
+        );
+        ecx.write_scalar(Scalar::Ptr(ptr), dest)
     }
 
     fn before_terminator(ecx: &mut InterpCx<'mir, 'tcx, Self>) -> InterpResult<'tcx> {
+        let limit = ecx.tcx.sess.opts.debugging_opts.const_eval_step_limit as u64;
+        ecx.machine.steps_taken += 1;
+        if limit != 0 && ecx.machine.steps_taken > limit {
+            return err!(StepLimitReached(limit));
+        }
+
         {
             let steps = &mut ecx.machine.steps_since_detector_enabled;
 
@@ -961,6 +961,68 @@ impl<'tcx> IntRange<'tcx> {
             None
         }
     }
+
+    /// Converts this range back into a pattern, for use in diagnostics.
+    fn to_pat(&self, tcx: TyCtxt<'tcx>) -> Pattern<'tcx> {
+        let kind = match Self::range_to_ctor(tcx, self.ty, self.range.clone()) {
+            ConstantValue(value) => PatternKind::Constant { value },
+            ConstantRange(lo, hi, ty, end) => {
+                let param_ty = ty::ParamEnv::empty().and(ty);
+                PatternKind::Range(PatternRange {
+                    lo: ty::Const::from_bits(tcx, lo, param_ty),
+                    hi: ty::Const::from_bits(tcx, hi, param_ty),
+                    ty,
+                    end,
+                })
+            }
+            _ => bug!("`range_to_ctor` returned a non-range constructor"),
+        };
+        Pattern { ty: self.ty, span: DUMMY_SP, kind: Box::new(kind) }
+    }
+}
+
+/// The values shared by a range pattern and an earlier pattern that
+/// covers one of its endpoints.
+pub struct RangeOverlap<'tcx> {
+    /// The values matched by both patterns.
+    pub overlap: Pattern<'tcx>,
+    /// The later range with the overlapping values removed.
+    pub narrowed: Pattern<'tcx>,
+}
+
+/// Checks whether the range pattern `pat` partially overlaps with the range
+/// pattern `earlier`, i.e., whether `earlier` straddles the lower or upper end
+/// of `pat`. Ranges nested in one another are not reported: a range which is
+/// fully covered is left to the unreachable pattern check, and a later range
+/// extending an earlier one is usually written that way on purpose.
+pub fn range_endpoint_overlap<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    pat: &Pattern<'tcx>,
+    earlier: &Pattern<'tcx>,
+) -> Option<RangeOverlap<'tcx>> {
+    let range = IntRange::from_pat(tcx, pat)?;
+    let earlier = IntRange::from_pat(tcx, earlier)?;
+    if range.ty != earlier.ty {
+        return None;
+    }
+    let overlap = range.intersection(&earlier)?;
+
+    let (lo, hi) = (*range.range.start(), *range.range.end());
+    let (earlier_lo, earlier_hi) = (*earlier.range.start(), *earlier.range.end());
+    let narrowed = if earlier_lo < lo && earlier_hi < hi {
+        // The earlier range ends inside of this one.
+        (earlier_hi + 1)..=hi
+    } else if earlier_lo > lo && earlier_hi > hi {
+        // The earlier range starts inside of this one.
+        lo..=(earlier_lo - 1)
+    } else {
+        return None;
+    };
+
+    Some(RangeOverlap {
+        overlap: overlap.to_pat(tcx),
+        narrowed: IntRange { range: narrowed, ty: range.ty }.to_pat(tcx),
+    })
 }
 
 // A request for missing constructor data in terms of either:
@@ -1,4 +1,5 @@
 use super::_match::{MatchCheckCtxt, Matrix, expand_pattern, is_useful};
+use super::_match::range_endpoint_overlap;
 use super::_match::Usefulness::*;
 use super::_match::WitnessPreference::*;
 
@@ -358,6 +359,36 @@ fn pat_is_catchall(pat: &Pat) -> bool {
     }
 }
 
+/// Lints on a range pattern whose lower or upper end is already matched by
+/// an earlier pattern, which is usually an off-by-one mistake.
+fn check_range_overlap<'tcx>(
+    cx: &MatchCheckCtxt<'_, 'tcx>,
+    seen_pats: &[&Pattern<'tcx>],
+    pat: &Pattern<'tcx>,
+    hir_pat: &hir::Pat,
+) {
+    for earlier in seen_pats {
+        if let Some(overlap) = range_endpoint_overlap(cx.tcx, pat, earlier) {
+            let mut err = cx.tcx.struct_span_lint_hir(
+                lint::builtin::OVERLAPPING_PATTERNS,
+                hir_pat.hir_id,
+                pat.span,
+                "multiple patterns covering the same range",
+            );
+            err.span_label(earlier.span, format!("this range overlaps on `{}`", overlap.overlap));
+            err.span_label(pat.span, "overlapping patterns");
+            err.span_suggestion(
+                pat.span,
+                "to avoid the overlap, narrow this range",
+                overlap.narrowed.to_string(),
+                Applicability::MaybeIncorrect,
+            );
+            err.emit();
+            return;
+        }
+    }
+}
+
 // Check for unreachable patterns
 fn check_arms<'a, 'tcx>(
     cx: &mut MatchCheckCtxt<'a, 'tcx>,
@@ -366,6 +397,8 @@ fn check_arms<'a, 'tcx>(
 ) {
     let mut seen = Matrix::empty();
     let mut catchall = None;
+    // Unguarded patterns seen so far, checked for overlapping range endpoints.
+    let mut seen_pats: Vec<&'a Pattern<'tcx>> = vec![];
     for (arm_index, &(ref pats, guard)) in arms.iter().enumerate() {
         for &(pat, hir_pat) in pats {
             let v = smallvec![pat];
@@ -429,11 +462,16 @@ fn check_arms<'a, 'tcx>(
                         hir::MatchSource::TryDesugar => {}
                     }
                 }
-                Useful => (),
+                Useful => {
+                    if source == hir::MatchSource::Normal {
+                        check_range_overlap(cx, &seen_pats, pat, hir_pat);
+                    }
+                }
                 UsefulWithWitness(_) => bug!()
             }
             if guard.is_none() {
                 seen.push(v);
+                seen_pats.push(pat);
                 if catchall.is_none() && pat_is_catchall(hir_pat) {
                     catchall = Some(pat.span);
                 }
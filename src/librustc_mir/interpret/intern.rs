@@ -18,7 +18,7 @@ use syntax_pos::Span;
 use super::{
     ValueVisitor, MemoryKind, Pointer, AllocId, MPlaceTy, InterpError, Scalar,
 };
-use crate::const_eval::{CompileTimeInterpreter, CompileTimeEvalContext};
+use crate::const_eval::{self, CompileTimeInterpreter, CompileTimeEvalContext};
 
 struct InternVisitor<'rt, 'mir, 'tcx> {
     /// previously encountered safe references
@@ -53,6 +53,15 @@ enum InternMode {
     Const,
 }
 
+impl InternMode {
+    fn descr(self) -> &'static str {
+        match self {
+            InternMode::Static => "static",
+            InternMode::ConstBase | InternMode::Const => "constant",
+        }
+    }
+}
+
 /// Signalling data structure to ensure we don't recurse
 /// into the memory of other constants or statics
 struct IsStaticOrFn;
@@ -89,6 +98,23 @@ impl<'rt, 'mir, 'tcx> InternVisitor<'rt, 'mir, 'tcx> {
         // changes in this function.
         match kind {
             MemoryKind::Stack | MemoryKind::Vtable => {},
+            // Heap allocations may only end up in the final value if nothing can mutate or
+            // deallocate them at runtime, i.e. if they are behind a shared reference and have no
+            // interior mutability.
+            MemoryKind::Machine(const_eval::MemoryKind::Heap) => {
+                if mutability == Mutability::Mutable {
+                    tcx.sess.struct_span_err(
+                        tcx.span,
+                        &format!(
+                            "heap allocations in the final value of a {} must be immutable",
+                            self.mode.descr(),
+                        ),
+                    )
+                    .note("this allocation is reachable through a mutable reference \
+                           or contains an `UnsafeCell`")
+                    .emit();
+                }
+            }
         }
         // Ensure llvm knows to only put this into immutable memory if the value is immutable either
         // by being behind a reference or by being part of a static or const without interior
@@ -316,7 +342,21 @@ pub fn intern_const_alloc_recursive(
 
     let mut todo: Vec<_> = leftover_relocations.iter().cloned().collect();
     while let Some(alloc_id) = todo.pop() {
-        if let Some((_, alloc)) = ecx.memory_mut().alloc_map.remove(&alloc_id) {
+        if let Some((kind, alloc)) = ecx.memory_mut().alloc_map.remove(&alloc_id) {
+            if let MemoryKind::Machine(const_eval::MemoryKind::Heap) = kind {
+                // Heap allocations that are not behind a shared reference could be mutated or
+                // freed at runtime, so we cannot turn them into static memory.
+                tcx.sess.struct_span_err(
+                    tcx.span,
+                    &format!(
+                        "heap allocations in the final value of a {} must be behind a shared \
+                         reference",
+                        base_intern_mode.descr(),
+                    ),
+                )
+                .note("this allocation is reachable through a raw pointer, a `Box` or a union")
+                .emit();
+            }
             // We can't call the `intern` method here, as its logic is tailored to safe references.
             // So we hand-roll the interning logic here again
             let alloc = tcx.intern_const_alloc(alloc);
@@ -274,6 +274,7 @@ impl<'mir, 'tcx> ConstPropagator<'mir, 'tcx> {
                     | Unimplemented(_)
                     // don't report const evaluator limits
                     | StackFrameLimitReached
+                    | StepLimitReached(_)
                     | NoMirFor(..)
                     | InlineAsm
                     => {},
@@ -0,0 +1,392 @@
+//! Destination propagation and dead store elimination.
+//!
+//! Building a value in a temporary and then moving it to its final place is how MIR lowers
+//! most expressions, which leaves functions like
+//!
+//!     _2 = Foo { .. };
+//!     ...
+//!     _0 = move _2;
+//!
+//! with a copy of the whole aggregate that LLVM does not always manage to remove. This pass
+//! looks for assignments `DEST = SRC` where both sides are locals, and replaces `SRC` with
+//! `DEST` throughout the body whenever the two locals never hold distinct values at the same
+//! time. The assignment then becomes `DEST = DEST` and is removed. Afterwards, stores to
+//! temporaries that are never read again are removed as well.
+//!
+//! # Soundness
+//!
+//! Two locals can share their storage if neither of them is written to while the other one
+//! holds a value that is still going to be read. We find out when a local holds such a value
+//! with the liveness analysis in `util::liveness`, and record a conflict between every local
+//! written by a statement and all locals live right after it. A statement also conflicts the
+//! locals it writes with all other locals it mentions, since e.g. `_1 = (move _2.1, move _2.0)`
+//! or a call writing its destination while reading its arguments cannot have the two overlap.
+//!
+//! The liveness analysis only considers a local used at the point it is borrowed, so it knows
+//! nothing about reads and writes through references. Any local that has its address taken
+//! anywhere in the body, even of a single field, is therefore never touched by this pass.
+//! Because MIR can only create pointers to a local by borrowing it, the remaining locals can
+//! only be accessed by statements that mention them, which makes the liveness exact for them.
+//! Inline assembly gets the same treatment, as its operands are opaque.
+//!
+//! The liveness analysis also lets a call define its destination on the unwind edge. Locals
+//! that are the destination of a call that can unwind, and that are used on a cleanup path,
+//! are skipped as well, so that their previous value is never clobbered.
+//!
+//! Storage markers are not considered writes: the storage markers of merged locals are removed,
+//! extending their storage over the whole body, which is always correct.
+//!
+//! To keep debuginfo intact, user variables are only replaced when compiling without debuginfo,
+//! and only temporaries have stores to them removed. Arguments are never touched.
+
+use rustc::mir::{
+    Body, Local, LocalKind, Location, Operand, Place, PlaceBase, Rvalue, Statement,
+    StatementKind, Terminator, TerminatorKind, RETURN_PLACE,
+};
+use rustc::mir::visit::{MutVisitor, PlaceContext, Visitor};
+use rustc::session::config::DebugInfo;
+use rustc::ty::TyCtxt;
+use rustc_data_structures::bit_set::{BitMatrix, BitSet};
+use rustc_data_structures::indexed_vec::IndexVec;
+use crate::transform::{MirPass, MirSource};
+use crate::util::liveness::{self, DefUse, LiveVarSet};
+
+/// The conflict matrix is quadratic in the number of locals, and the analysis has to visit
+/// every statement, so very large bodies are left alone.
+const MAX_LOCALS: usize = 500;
+const MAX_BLOCKS: usize = 250;
+
+pub struct DestinationPropagation;
+
+impl MirPass for DestinationPropagation {
+    fn run_pass<'tcx>(&self, tcx: TyCtxt<'tcx>, source: MirSource<'tcx>, body: &mut Body<'tcx>) {
+        if tcx.sess.opts.debugging_opts.mir_opt_level < 1 {
+            return;
+        }
+
+        if body.local_decls.len() > MAX_LOCALS || body.basic_blocks().len() > MAX_BLOCKS {
+            debug!("DestinationPropagation: skipping {:?}, body too large", source.def_id());
+            return;
+        }
+
+        let excluded = excluded_locals(body);
+        let replace_vars = tcx.sess.opts.debuginfo == DebugInfo::None;
+        if propagate_destinations(body, &excluded, replace_vars) {
+            debug!("DestinationPropagation: merged locals in {:?}", source.def_id());
+        }
+        eliminate_dead_stores(body, &excluded);
+    }
+}
+
+/// Replaces the source of `DEST = SRC` assignments with their destination where possible.
+fn propagate_destinations(
+    body: &mut Body<'_>,
+    excluded: &BitSet<Local>,
+    replace_vars: bool,
+) -> bool {
+    let num_locals = body.local_decls.len();
+    let mut conflicts = BitMatrix::new(num_locals, num_locals);
+    let mut candidates = vec![];
+
+    for_each_access(body, |location, accesses, live| {
+        let copy = local_copy(body, location);
+        if let Some(copy) = copy {
+            candidates.push(copy);
+        }
+
+        for &written in &accesses.writes {
+            for other in live.iter().chain(accesses.mentions.iter().cloned()) {
+                // The copy itself is fine, it turns into a self-assignment. Should the
+                // source still be live after it, that conflict is recorded above.
+                let is_copy = copy == Some((written, other)) && !live.contains(other);
+                if other != written && !is_copy {
+                    conflicts.insert(written, other);
+                    conflicts.insert(other, written);
+                }
+            }
+        }
+    });
+
+    // `for_each_access` walks each block backwards, restore program order so that chains
+    // of copies are merged front to back.
+    candidates.reverse();
+
+    let mut renames: IndexVec<Local, Local> = body.local_decls.indices().collect();
+    let mut merged = BitSet::new_empty(num_locals);
+    for (dest, src) in candidates {
+        let dest = find(&renames, dest);
+        let src = find(&renames, src);
+        if dest == src ||
+            excluded.contains(dest) ||
+            excluded.contains(src) ||
+            !is_replaceable(body.local_kind(src), replace_vars) ||
+            body.local_kind(dest) == LocalKind::Arg ||
+            body.local_decls[src].ty != body.local_decls[dest].ty ||
+            conflicts.contains(src, dest)
+        {
+            continue;
+        }
+
+        debug!("propagate_destinations: replacing {:?} with {:?}", src, dest);
+
+        // From now on `dest` stands for both locals, so it conflicts with everything
+        // either of them conflicts with.
+        conflicts.union_rows(src, dest);
+        for other in conflicts.iter(src).collect::<Vec<_>>() {
+            conflicts.insert(other, dest);
+        }
+        renames[src] = dest;
+        merged.insert(src);
+        merged.insert(dest);
+    }
+
+    if merged.is_empty() {
+        return false;
+    }
+
+    for local in body.local_decls.indices() {
+        renames[local] = find(&renames, local);
+    }
+    RenameLocals { renames: &renames }.visit_body(body);
+
+    for data in body.basic_blocks_mut() {
+        for statement in &mut data.statements {
+            let remove = match statement.kind {
+                StatementKind::StorageLive(local) |
+                StatementKind::StorageDead(local) => merged.contains(local),
+                StatementKind::Assign(
+                    Place::Base(PlaceBase::Local(dest)),
+                    box Rvalue::Use(Operand::Copy(Place::Base(PlaceBase::Local(src)))),
+                ) |
+                StatementKind::Assign(
+                    Place::Base(PlaceBase::Local(dest)),
+                    box Rvalue::Use(Operand::Move(Place::Base(PlaceBase::Local(src)))),
+                ) => dest == src,
+                _ => false,
+            };
+            if remove {
+                statement.make_nop();
+            }
+        }
+    }
+
+    true
+}
+
+/// Removes assignments to temporaries that are never read afterwards.
+fn eliminate_dead_stores(body: &mut Body<'_>, excluded: &BitSet<Local>) {
+    let mut dead_stores = vec![];
+    for_each_access(body, |location, _, live| {
+        let statement = match body[location.block].statements.get(location.statement_index) {
+            Some(statement) => statement,
+            None => return,
+        };
+        if let StatementKind::Assign(Place::Base(PlaceBase::Local(local)), _) = statement.kind {
+            // MIR rvalues have no side effects, so the assignment can go if the value
+            // stored is never read.
+            if !live.contains(local) &&
+                !excluded.contains(local) &&
+                body.local_kind(local) == LocalKind::Temp
+            {
+                dead_stores.push(location);
+            }
+        }
+    });
+
+    for location in dead_stores {
+        debug!("eliminate_dead_stores: removing {:?}", location);
+        body[location.block].statements[location.statement_index].make_nop();
+    }
+}
+
+/// Returns `(DEST, SRC)` if the statement at `location` is an assignment `DEST = SRC` between
+/// two locals.
+fn local_copy(body: &Body<'_>, location: Location) -> Option<(Local, Local)> {
+    match body[location.block].statements.get(location.statement_index)?.kind {
+        StatementKind::Assign(
+            Place::Base(PlaceBase::Local(dest)),
+            box Rvalue::Use(Operand::Copy(Place::Base(PlaceBase::Local(src)))),
+        ) |
+        StatementKind::Assign(
+            Place::Base(PlaceBase::Local(dest)),
+            box Rvalue::Use(Operand::Move(Place::Base(PlaceBase::Local(src)))),
+        ) => Some((dest, src)),
+        _ => None,
+    }
+}
+
+/// Whether a local of the given kind may be replaced by another one.
+fn is_replaceable(kind: LocalKind, replace_vars: bool) -> bool {
+    match kind {
+        LocalKind::Temp => true,
+        LocalKind::Var => replace_vars,
+        LocalKind::Arg | LocalKind::ReturnPointer => false,
+    }
+}
+
+fn find(renames: &IndexVec<Local, Local>, mut local: Local) -> Local {
+    while renames[local] != local {
+        local = renames[local];
+    }
+    local
+}
+
+/// Walks every block backwards, calling `f` for each statement and terminator with what it
+/// accesses and the locals that are live right after it.
+fn for_each_access<'tcx>(
+    body: &Body<'tcx>,
+    mut f: impl FnMut(Location, &Accesses, &LiveVarSet),
+) {
+    let liveness = liveness::liveness_of_locals_and_return_place(body);
+    let mut live = LiveVarSet::new_empty(body.local_decls.len());
+
+    for (block, data) in body.basic_blocks().iter_enumerated() {
+        live.overwrite(&liveness.outs[block]);
+
+        let location = body.terminator_loc(block);
+        let mut accesses = Accesses::default();
+        accesses.visit_terminator(data.terminator(), location);
+        f(location, &accesses, &live);
+        accesses.apply(&mut live);
+
+        for (statement_index, statement) in data.statements.iter().enumerate().rev() {
+            let location = Location { block, statement_index };
+            let mut accesses = Accesses::default();
+            accesses.visit_statement(statement, location);
+            f(location, &accesses, &live);
+            accesses.apply(&mut live);
+        }
+    }
+}
+
+/// The locals accessed by a single statement or terminator.
+#[derive(Default)]
+struct Accesses {
+    /// Locals whose value is overwritten as a whole, as far as liveness is concerned.
+    defs: Vec<Local>,
+    /// Locals whose value is read, including by drops.
+    uses: Vec<Local>,
+    /// Locals that are written to in any way, including through projections.
+    writes: Vec<Local>,
+    /// All locals accessed, storage markers aside.
+    mentions: Vec<Local>,
+}
+
+impl Accesses {
+    /// Turns the locals live after the accesses into the ones live before them.
+    fn apply(&self, live: &mut LiveVarSet) {
+        for &local in &self.defs {
+            live.remove(local);
+        }
+        for &local in &self.uses {
+            live.insert(local);
+        }
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for Accesses {
+    fn visit_terminator(&mut self, terminator: &Terminator<'tcx>, location: Location) {
+        self.super_terminator(terminator, location);
+        // Matches `liveness::liveness_of_locals_and_return_place`.
+        if let TerminatorKind::Return = terminator.kind {
+            self.uses.push(RETURN_PLACE);
+            self.mentions.push(RETURN_PLACE);
+        }
+    }
+
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext, _: Location) {
+        match liveness::categorize(context) {
+            Some(DefUse::Def) => self.defs.push(local),
+            Some(DefUse::Use) | Some(DefUse::Drop) => self.uses.push(local),
+            None => {}
+        }
+        if context.is_use() {
+            self.mentions.push(local);
+        }
+        if context.is_mutating_use() {
+            self.writes.push(local);
+        }
+    }
+}
+
+/// Collects the locals this pass must not touch, see the module documentation.
+fn excluded_locals(body: &Body<'_>) -> BitSet<Local> {
+    let mut finder = ExcludedLocalsFinder {
+        excluded: BitSet::new_empty(body.local_decls.len()),
+        in_asm: false,
+    };
+    finder.visit_body(body);
+
+    let mut cleanup_uses = BitSet::new_empty(body.local_decls.len());
+    for data in body.basic_blocks().iter().filter(|data| data.is_cleanup) {
+        for statement in &data.statements {
+            let mut accesses = Accesses::default();
+            accesses.visit_statement(statement, Location::START);
+            for &local in &accesses.mentions {
+                cleanup_uses.insert(local);
+            }
+        }
+        let mut accesses = Accesses::default();
+        accesses.visit_terminator(data.terminator(), Location::START);
+        for &local in &accesses.mentions {
+            cleanup_uses.insert(local);
+        }
+    }
+
+    for data in body.basic_blocks() {
+        if let TerminatorKind::Call {
+            destination: Some((ref place, _)),
+            cleanup: Some(_),
+            ..
+        } = data.terminator().kind {
+            if let Some(local) = place.base_local() {
+                if cleanup_uses.contains(local) {
+                    finder.excluded.insert(local);
+                }
+            }
+        }
+    }
+
+    finder.excluded
+}
+
+struct ExcludedLocalsFinder {
+    excluded: BitSet<Local>,
+    in_asm: bool,
+}
+
+impl<'tcx> Visitor<'tcx> for ExcludedLocalsFinder {
+    fn visit_statement(&mut self, statement: &Statement<'tcx>, location: Location) {
+        self.in_asm = match statement.kind {
+            StatementKind::InlineAsm(..) => true,
+            _ => false,
+        };
+        self.super_statement(statement, location);
+        self.in_asm = false;
+    }
+
+    fn visit_rvalue(&mut self, rvalue: &Rvalue<'tcx>, location: Location) {
+        if let Rvalue::Ref(_, _, ref place) = *rvalue {
+            if let Some(local) = place.base_local() {
+                self.excluded.insert(local);
+            }
+        }
+        self.super_rvalue(rvalue, location);
+    }
+
+    fn visit_local(&mut self, &local: &Local, _: PlaceContext, _: Location) {
+        if self.in_asm {
+            self.excluded.insert(local);
+        }
+    }
+}
+
+struct RenameLocals<'a> {
+    renames: &'a IndexVec<Local, Local>,
+}
+
+impl<'tcx> MutVisitor<'tcx> for RenameLocals<'_> {
+    fn visit_local(&mut self, local: &mut Local, _: PlaceContext, _: Location) {
+        *local = self.renames[*local];
+    }
+}
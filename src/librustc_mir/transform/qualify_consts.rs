@@ -29,6 +29,9 @@ use std::fmt;
 use std::ops::{Deref, Index, IndexMut};
 use std::usize;
 
+use crate::dataflow::move_paths::{HasMoveData, LookupResult, MoveData};
+use crate::dataflow::{do_dataflow, on_all_drop_children_bits, DebugFormatted};
+use crate::dataflow::{DataflowResultsCursor, MaybeInitializedPlaces, MoveDataParamEnv};
 use crate::transform::{MirPass, MirSource};
 use super::promote_consts::{self, Candidate, TempState};
 
@@ -137,6 +140,35 @@ impl<'a, 'tcx> ConstCx<'a, 'tcx> {
         Some(def_id) == self.tcx.lang_items().panic_fn() ||
         Some(def_id) == self.tcx.lang_items().begin_panic_fn()
     }
+
+    /// Whether `def_id` is one of the `#[rustc_const_heap]` functions, whose calls const eval
+    /// emulates with heap memory (see `const_eval::emulate_heap_fn`).
+    fn is_const_heap_fn(&self, def_id: DefId) -> bool {
+        self.tcx.has_attr(def_id, sym::rustc_const_heap)
+    }
+
+    /// Whether dropping a value of type `ty` only frees heap memory that const eval knows how
+    /// to deallocate: a `Box` of a type without drop glue, or a type whose destructor is a
+    /// `#[rustc_const_heap]` function and whose fields are either trivially dropped or
+    /// themselves of such a type.
+    fn is_const_heap_drop(&self, ty: Ty<'tcx>) -> bool {
+        match ty.sty {
+            ty::Adt(def, _) if def.is_box() => {
+                !ty.boxed_ty().needs_drop(self.tcx, self.param_env)
+            }
+            ty::Adt(def, substs) => match self.tcx.adt_destructor(def.did) {
+                Some(dtor) if self.is_const_heap_fn(dtor.did) => {
+                    def.all_fields().all(|field| {
+                        let field_ty = field.ty(self.tcx, substs);
+                        !field_ty.needs_drop(self.tcx, self.param_env) ||
+                            self.is_const_heap_drop(field_ty)
+                    })
+                }
+                _ => false,
+            },
+            _ => false,
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -634,6 +666,12 @@ struct Checker<'a, 'tcx> {
 
     temp_promotion_state: IndexVec<Local, TempState>,
     promotion_candidates: Vec<Candidate>,
+
+    /// The blocks whose `Drop` terminator can't drop anything, when the body
+    /// may branch (see `check_control_flow`). Once branches are allowed, a
+    /// move on one path does not turn a drop on another path into a no-op,
+    /// so moves are tracked with dataflow instead of in `per_local`.
+    dead_drops: Option<BitSet<BasicBlock>>,
 }
 
 macro_rules! unleash_miri {
@@ -695,7 +733,8 @@ impl<'a, 'tcx> Checker<'a, 'tcx> {
             def_id,
             rpo,
             temp_promotion_state: temps,
-            promotion_candidates: vec![]
+            promotion_candidates: vec![],
+            dead_drops: None,
         }
     }
 
@@ -875,55 +914,138 @@ impl<'a, 'tcx> Checker<'a, 'tcx> {
         }
     }
 
-    /// Check a whole const, static initializer or const fn.
-    fn check_const(&mut self) -> (u8, &'tcx BitSet<Local>) {
-        debug!("const-checking {} {:?}", self.mode, self.def_id);
-
+    /// Checks every reachable block of a body that may branch or loop, as
+    /// allowed by the `const_if_match` and `const_loop` features.
+    ///
+    /// Qualifications only ever grow while walking the body, so visiting the
+    /// blocks in reverse postorder sees each assignment before the uses it
+    /// dominates. Values flowing around a loop's back edge are accounted for
+    /// by qualifying every reassignable local from its type up front.
+    fn check_control_flow(&mut self) {
         let body = self.body;
+        let features = self.tcx.features();
+        self.dead_drops = Some(find_dead_drops(self.tcx, body, self.def_id, self.param_env));
 
-        let mut seen_blocks = BitSet::new_empty(body.basic_blocks().len());
-        let mut bb = START_BLOCK;
-        loop {
-            seen_blocks.insert(bb.index());
-
-            self.visit_basic_block_data(bb, &body[bb]);
-
-            let target = match body[bb].terminator().kind {
-                TerminatorKind::Goto { target } |
-                TerminatorKind::FalseUnwind { real_target: target, .. } |
-                TerminatorKind::Drop { target, .. } |
-                TerminatorKind::DropAndReplace { target, .. } |
-                TerminatorKind::Assert { target, .. } |
-                TerminatorKind::Call { destination: Some((_, target)), .. } => {
-                    Some(target)
+        let has_loops = body.basic_blocks().iter().any(|data| {
+            match data.terminator().kind {
+                TerminatorKind::FalseUnwind { .. } => true,
+                _ => false,
+            }
+        });
+        if has_loops {
+            for (local, decl) in body.local_decls.iter_enumerated() {
+                match body.local_kind(local) {
+                    LocalKind::Arg | LocalKind::ReturnPointer => continue,
+                    LocalKind::Var | LocalKind::Temp => {}
                 }
-
-                // Non-terminating calls cannot produce any value.
-                TerminatorKind::Call { destination: None, .. } => {
-                    break;
+                // Promotable temps are assigned exactly once, before any use.
+                if self.temp_promotion_state[local].is_promotable() {
+                    continue;
+                }
+                let qualifs = self.qualifs_in_any_value_of_ty(decl.ty);
+                if qualifs[HasMutInterior] {
+                    self.cx.per_local[HasMutInterior].insert(local);
+                }
+                if qualifs[NeedsDrop] {
+                    self.cx.per_local[NeedsDrop].insert(local);
                 }
+            }
+        }
 
-                TerminatorKind::SwitchInt {..} |
-                TerminatorKind::Resume |
+        self.rpo.reset();
+        while let Some((bb, data)) = self.rpo.next() {
+            // Like the straight-line walk, ignore the unwind paths.
+            if data.is_cleanup {
+                continue;
+            }
+
+            self.visit_basic_block_data(bb, data);
+
+            let span = data.terminator().source_info.span;
+            let gate = match data.terminator().kind {
+                TerminatorKind::SwitchInt { .. } |
+                TerminatorKind::FalseEdges { .. } if !features.const_if_match => {
+                    Some((sym::const_if_match, "`if`, `match`, `&&` and `||` are"))
+                }
+                TerminatorKind::FalseUnwind { .. } if !features.const_loop => {
+                    Some((sym::const_loop, "`loop` and `while` are"))
+                }
                 TerminatorKind::Abort |
                 TerminatorKind::GeneratorDrop |
-                TerminatorKind::Yield { .. } |
-                TerminatorKind::Unreachable |
-                TerminatorKind::FalseEdges { .. } => None,
-
-                TerminatorKind::Return => {
-                    break;
+                TerminatorKind::Yield { .. } => {
+                    self.not_const();
+                    None
                 }
+                _ => None,
             };
 
-            match target {
-                // No loops allowed.
-                Some(target) if !seen_blocks.contains(target.index()) => {
-                    bb = target;
+            if let Some((feature, what)) = gate {
+                if self.tcx.sess.opts.debugging_opts.unleash_the_miri_inside_of_you {
+                    continue;
                 }
-                _ => {
-                    self.not_const();
-                    break;
+                emit_feature_err(
+                    &self.tcx.sess.parse_sess, feature,
+                    span, GateIssue::Language,
+                    &format!("{} unstable in {}s", what, self.mode),
+                );
+            }
+        }
+    }
+
+    /// Check a whole const, static initializer or const fn.
+    fn check_const(&mut self) -> (u8, &'tcx BitSet<Local>) {
+        debug!("const-checking {} {:?}", self.mode, self.def_id);
+
+        let body = self.body;
+
+        let features = self.tcx.features();
+        if features.const_if_match || features.const_loop {
+            self.check_control_flow();
+        } else {
+            let mut seen_blocks = BitSet::new_empty(body.basic_blocks().len());
+            let mut bb = START_BLOCK;
+            loop {
+                seen_blocks.insert(bb.index());
+
+                self.visit_basic_block_data(bb, &body[bb]);
+
+                let target = match body[bb].terminator().kind {
+                    TerminatorKind::Goto { target } |
+                    TerminatorKind::FalseUnwind { real_target: target, .. } |
+                    TerminatorKind::Drop { target, .. } |
+                    TerminatorKind::DropAndReplace { target, .. } |
+                    TerminatorKind::Assert { target, .. } |
+                    TerminatorKind::Call { destination: Some((_, target)), .. } => {
+                        Some(target)
+                    }
+
+                    // Non-terminating calls cannot produce any value.
+                    TerminatorKind::Call { destination: None, .. } => {
+                        break;
+                    }
+
+                    TerminatorKind::SwitchInt {..} |
+                    TerminatorKind::Resume |
+                    TerminatorKind::Abort |
+                    TerminatorKind::GeneratorDrop |
+                    TerminatorKind::Yield { .. } |
+                    TerminatorKind::Unreachable |
+                    TerminatorKind::FalseEdges { .. } => None,
+
+                    TerminatorKind::Return => {
+                        break;
+                    }
+                };
+
+                match target {
+                    // No loops allowed.
+                    Some(target) if !seen_blocks.contains(target.index()) => {
+                        bb = target;
+                    }
+                    _ => {
+                        self.not_const();
+                        break;
+                    }
                 }
             }
         }
@@ -962,6 +1084,55 @@ impl<'a, 'tcx> Checker<'a, 'tcx> {
     }
 }
 
+/// Returns the blocks whose `Drop` terminator can't drop anything, because
+/// everything it would drop has been moved out on every path reaching it.
+fn find_dead_drops<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    def_id: DefId,
+    param_env: ty::ParamEnv<'tcx>,
+) -> BitSet<BasicBlock> {
+    let mut dead_drops = BitSet::new_empty(body.basic_blocks().len());
+    let move_data = match MoveData::gather_moves(body, tcx) {
+        Ok(move_data) => move_data,
+        // Illegal moves are reported by borrowck, consider every drop live.
+        Err(_) => return dead_drops,
+    };
+    let env = MoveDataParamEnv {
+        move_data,
+        param_env,
+    };
+    let dead_unwinds = BitSet::new_empty(body.basic_blocks().len());
+    let flow_inits =
+        do_dataflow(tcx, body, def_id, &[], &dead_unwinds,
+                    MaybeInitializedPlaces::new(tcx, body, &env),
+                    |bd, p| DebugFormatted::new(&bd.move_data().move_paths[p]));
+    let mut flow_inits = DataflowResultsCursor::new(flow_inits, body);
+
+    for (bb, data) in body.basic_blocks().iter_enumerated() {
+        let place = match data.terminator().kind {
+            TerminatorKind::Drop { location: ref place, .. } |
+            TerminatorKind::DropAndReplace { location: ref place, .. } => place,
+            _ => continue,
+        };
+        let path = match env.move_data.rev_lookup.find(place) {
+            LookupResult::Exact(path) => path,
+            LookupResult::Parent(..) => continue,
+        };
+
+        flow_inits.seek(body.terminator_loc(bb));
+        let mut maybe_live = false;
+        on_all_drop_children_bits(tcx, body, &env, path, |child| {
+            maybe_live |= flow_inits.contains(child);
+        });
+        if !maybe_live {
+            dead_drops.insert(bb);
+        }
+    }
+
+    dead_drops
+}
+
 impl<'a, 'tcx> Visitor<'tcx> for Checker<'a, 'tcx> {
     fn visit_place_base(
         &mut self,
@@ -1101,8 +1272,11 @@ impl<'a, 'tcx> Visitor<'tcx> for Checker<'a, 'tcx> {
         match *operand {
             Operand::Move(ref place) => {
                 // Mark the consumed locals to indicate later drops are noops.
+                // With branches, the move need not happen on every path to a drop.
                 if let Place::Base(PlaceBase::Local(local)) = *place {
-                    self.cx.per_local[NeedsDrop].remove(local);
+                    if self.dead_drops.is_none() {
+                        self.cx.per_local[NeedsDrop].remove(local);
+                    }
                 }
             }
             Operand::Copy(_) |
@@ -1307,6 +1481,18 @@ impl<'a, 'tcx> Visitor<'tcx> for Checker<'a, 'tcx> {
                                             &format!("panicking in {}s is unstable", self.mode),
                                         );
                                     }
+                                } else if self.is_const_heap_fn(def_id) {
+                                    // Check the const_heap feature gate.
+                                    if !self.tcx.features().const_heap {
+                                        emit_feature_err(
+                                            &self.tcx.sess.parse_sess,
+                                            sym::const_heap,
+                                            self.span,
+                                            GateIssue::Language,
+                                            &format!("heap allocations in {}s are unstable",
+                                                     self.mode),
+                                        );
+                                    }
                                 } else if let Some(feature)
                                               = self.tcx.is_unstable_const_fn(def_id) {
                                     // Check `#[unstable]` const fns or `#[rustc_const_unstable]`
@@ -1405,8 +1591,12 @@ impl<'a, 'tcx> Visitor<'tcx> for Checker<'a, 'tcx> {
                 _ => self.super_terminator_kind(kind, location),
             }
 
+            let is_dead = self.dead_drops.as_ref().map_or(false, |dead_drops| {
+                dead_drops.contains(location.block)
+            });
+
             // Deny *any* live drops anywhere other than functions.
-            if self.mode.requires_const_checking() {
+            if self.mode.requires_const_checking() && !is_dead {
                 unleash_miri!(self);
                 // HACK(eddyb): emulate a bit of dataflow analysis,
                 // conservatively, that drop elaboration will do.
@@ -1423,7 +1613,8 @@ impl<'a, 'tcx> Visitor<'tcx> for Checker<'a, 'tcx> {
                 if let Some(span) = needs_drop {
                     // Double-check the type being dropped, to minimize false positives.
                     let ty = place.ty(self.body, self.tcx).ty;
-                    if ty.needs_drop(self.tcx, self.param_env) {
+                    let heap_drop = self.tcx.features().const_heap && self.is_const_heap_drop(ty);
+                    if ty.needs_drop(self.tcx, self.param_env) && !heap_drop {
                         struct_span_err!(self.tcx.sess, span, E0493,
                                          "destructors cannot be evaluated at compile-time")
                             .span_label(span, format!("{}s cannot evaluate destructors",
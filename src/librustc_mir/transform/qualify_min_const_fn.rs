@@ -127,10 +127,10 @@ fn check_rvalue(
 ) -> McfResult {
     match rvalue {
         Rvalue::Repeat(operand, _) | Rvalue::Use(operand) => {
-            check_operand(operand, span)
+            check_operand(tcx, operand, span)
         }
         Rvalue::Len(place) | Rvalue::Discriminant(place) | Rvalue::Ref(_, _, place) => {
-            check_place(place, span)
+            check_place(tcx, place, span)
         }
         Rvalue::Cast(CastKind::Misc, operand, cast_ty) => {
             use rustc::ty::cast::CastTy;
@@ -144,11 +144,11 @@ fn check_rvalue(
                 (CastTy::RPtr(_), CastTy::Float) => bug!(),
                 (CastTy::RPtr(_), CastTy::Int(_)) => bug!(),
                 (CastTy::Ptr(_), CastTy::RPtr(_)) => bug!(),
-                _ => check_operand(operand, span),
+                _ => check_operand(tcx, operand, span),
             }
         }
         Rvalue::Cast(CastKind::Pointer(PointerCast::MutToConstPointer), operand, _) => {
-            check_operand(operand, span)
+            check_operand(tcx, operand, span)
         }
         Rvalue::Cast(CastKind::Pointer(PointerCast::UnsafeFnPointer), _, _) |
         Rvalue::Cast(CastKind::Pointer(PointerCast::ClosureFnPointer(_)), _, _) |
@@ -162,8 +162,8 @@ fn check_rvalue(
         )),
         // binops are fine on integers
         Rvalue::BinaryOp(_, lhs, rhs) | Rvalue::CheckedBinaryOp(_, lhs, rhs) => {
-            check_operand(lhs, span)?;
-            check_operand(rhs, span)?;
+            check_operand(tcx, lhs, span)?;
+            check_operand(tcx, rhs, span)?;
             let ty = lhs.ty(body, tcx);
             if ty.is_integral() || ty.is_bool() || ty.is_char() {
                 Ok(())
@@ -182,7 +182,7 @@ fn check_rvalue(
         Rvalue::UnaryOp(_, operand) => {
             let ty = operand.ty(body, tcx);
             if ty.is_integral() || ty.is_bool() {
-                check_operand(operand, span)
+                check_operand(tcx, operand, span)
             } else {
                 Err((
                     span,
@@ -192,7 +192,7 @@ fn check_rvalue(
         }
         Rvalue::Aggregate(_, operands) => {
             for operand in operands {
-                check_operand(operand, span)?;
+                check_operand(tcx, operand, span)?;
             }
             Ok(())
         }
@@ -207,15 +207,21 @@ fn check_statement(
     let span = statement.source_info.span;
     match &statement.kind {
         StatementKind::Assign(place, rval) => {
-            check_place(place, span)?;
+            check_place(tcx, place, span)?;
             check_rvalue(tcx, body, rval, span)
         }
 
+        StatementKind::FakeRead(FakeReadCause::ForMatchedPlace, place)
+            if tcx.features().const_if_match =>
+        {
+            check_place(tcx, place, span)
+        }
+
         StatementKind::FakeRead(FakeReadCause::ForMatchedPlace, _) => {
             Err((span, "loops and conditional expressions are not stable in const fn".into()))
         }
 
-        StatementKind::FakeRead(_, place) => check_place(place, span),
+        StatementKind::FakeRead(_, place) => check_place(tcx, place, span),
 
         // just an assignment
         StatementKind::SetDiscriminant { .. } => Ok(()),
@@ -234,28 +240,31 @@ fn check_statement(
 }
 
 fn check_operand(
+    tcx: TyCtxt<'tcx>,
     operand: &Operand<'tcx>,
     span: Span,
 ) -> McfResult {
     match operand {
         Operand::Move(place) | Operand::Copy(place) => {
-            check_place(place, span)
+            check_place(tcx, place, span)
         }
         Operand::Constant(_) => Ok(()),
     }
 }
 
 fn check_place(
+    tcx: TyCtxt<'tcx>,
     place: &Place<'tcx>,
     span: Span,
 ) -> McfResult {
     place.iterate(|place_base, place_projection| {
         for proj in place_projection {
             match proj.elem {
-                ProjectionElem::Downcast(..) => {
+                ProjectionElem::Downcast(..) if !tcx.features().const_if_match => {
                     return Err((span, "`match` or `if let` in `const fn` is unstable".into()));
                 }
-                ProjectionElem::ConstantIndex { .. }
+                ProjectionElem::Downcast(..)
+                | ProjectionElem::ConstantIndex { .. }
                 | ProjectionElem::Subslice { .. }
                 | ProjectionElem::Deref
                 | ProjectionElem::Field(..)
@@ -285,17 +294,26 @@ fn check_terminator(
         | TerminatorKind::Resume => Ok(()),
 
         TerminatorKind::Drop { location, .. } => {
-            check_place(location, span)
+            check_place(tcx, location, span)
         }
         TerminatorKind::DropAndReplace { location, value, .. } => {
-            check_place(location, span)?;
-            check_operand(value, span)
+            check_place(tcx, location, span)?;
+            check_operand(tcx, value, span)
         },
 
+        TerminatorKind::FalseEdges { .. } if tcx.features().const_if_match => Ok(()),
+
+        TerminatorKind::SwitchInt { discr, .. } if tcx.features().const_if_match => {
+            check_operand(tcx, discr, span)
+        }
+
         TerminatorKind::FalseEdges { .. } | TerminatorKind::SwitchInt { .. } => Err((
             span,
             "loops and conditional expressions are not stable in const fn".into(),
         )),
+        // Exhaustive `match`es lower their impossible `otherwise` edge to `Unreachable`.
+        TerminatorKind::Unreachable if tcx.features().const_if_match => Ok(()),
+
         | TerminatorKind::Abort | TerminatorKind::Unreachable => {
             Err((span, "const fn with unreachable code is not stable".into()))
         }
@@ -341,10 +359,10 @@ fn check_terminator(
                     )),
                 }
 
-                check_operand(func, span)?;
+                check_operand(tcx, func, span)?;
 
                 for arg in args {
-                    check_operand(arg, span)?;
+                    check_operand(tcx, arg, span)?;
                 }
                 Ok(())
             } else {
@@ -358,7 +376,9 @@ fn check_terminator(
             msg: _,
             target: _,
             cleanup: _,
-        } => check_operand(cond, span),
+        } => check_operand(tcx, cond, span),
+
+        TerminatorKind::FalseUnwind { .. } if tcx.features().const_loop => Ok(()),
 
         TerminatorKind::FalseUnwind { .. } => {
             Err((span, "loops are not allowed in const fn".into()))
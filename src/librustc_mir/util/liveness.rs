@@ -58,13 +58,28 @@ pub struct LivenessResult {
 /// `mir`, including drops.
 pub fn liveness_of_locals(
     body: &Body<'_>,
+) -> LivenessResult {
+    compute(body, false)
+}
+
+/// Like `liveness_of_locals`, but also treats `return` as a use of the
+/// return place, whose value is read by the caller.
+pub fn liveness_of_locals_and_return_place(
+    body: &Body<'_>,
+) -> LivenessResult {
+    compute(body, true)
+}
+
+fn compute(
+    body: &Body<'_>,
+    return_is_use: bool,
 ) -> LivenessResult {
     let num_live_vars = body.local_decls.len();
 
     let def_use: IndexVec<_, DefsUses> = body
         .basic_blocks()
         .iter()
-        .map(|b| block(b, num_live_vars))
+        .map(|b| block(b, num_live_vars, return_is_use))
         .collect();
 
     let mut outs: IndexVec<_, LiveVarSet> = body
@@ -246,6 +261,7 @@ impl<'tcx> Visitor<'tcx> for DefsUsesVisitor
 fn block(
     b: &BasicBlockData<'_>,
     locals: usize,
+    return_is_use: bool,
 ) -> DefsUses {
     let mut visitor = DefsUsesVisitor {
         defs_uses: DefsUses {
@@ -262,6 +278,13 @@ fn block(
     // Visit the various parts of the basic block in reverse. If we go
     // forward, the logic in `add_def` and `add_use` would be wrong.
     visitor.visit_terminator(b.terminator(), dummy_location);
+    if let TerminatorKind::Return = b.terminator().kind {
+        if return_is_use {
+            // The MIR visitor doesn't consider `return` to be a use of the
+            // return place, but its value is read by the caller.
+            visitor.defs_uses.add_use(RETURN_PLACE);
+        }
+    }
     for statement in b.statements.iter().rev() {
         visitor.visit_statement(statement, dummy_location);
     }
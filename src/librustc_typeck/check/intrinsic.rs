@@ -168,6 +168,15 @@ pub fn check_intrinsic_type(tcx: TyCtxt<'_>, it: &hir::ForeignItem) {
             }
             "needs_drop" => (1, Vec::new(), tcx.types.bool),
 
+            "const_allocate" => {
+                (0, vec![tcx.types.usize, tcx.types.usize], tcx.mk_mut_ptr(tcx.types.u8))
+            }
+            "const_deallocate" => {
+                (0,
+                 vec![tcx.mk_mut_ptr(tcx.types.u8), tcx.types.usize, tcx.types.usize],
+                 tcx.mk_unit())
+            }
+
             "type_name" => (1, Vec::new(), tcx.mk_static_str()),
             "type_id" => (1, Vec::new(), tcx.types.u64),
             "offset" | "arith_offset" => {
@@ -906,6 +906,63 @@ where
     {
         self.base.retain(f)
     }
+
+    /// Creates an iterator which uses a closure to determine if an element should be removed.
+    ///
+    /// If the closure returns true, the element is removed from the map and yielded.
+    /// If the closure returns false, or panics, the element remains in the map and will not be
+    /// yielded.
+    ///
+    /// Note that `drain_filter` lets you mutate every value in the filter closure, regardless of
+    /// whether you choose to keep or remove it.
+    ///
+    /// If the iterator is only partially consumed or not consumed at all, each of the remaining
+    /// elements will still be subjected to the closure and removed and dropped if it returns true.
+    ///
+    /// It is unspecified how many more elements will be subjected to the closure
+    /// if a panic occurs in the closure, or a panic occurs while dropping an element,
+    /// or if the `DrainFilter` value is leaked.
+    ///
+    /// # Examples
+    ///
+    /// Splitting a map into even and odd keys, reusing the original map:
+    ///
+    /// ```
+    /// #![feature(hash_drain_filter)]
+    /// use std::collections::HashMap;
+    ///
+    /// let mut map: HashMap<i32, i32> = (0..8).map(|x| (x, x)).collect();
+    /// let drained: HashMap<i32, i32> = map.drain_filter(|k, _v| k % 2 == 0).collect();
+    ///
+    /// let mut evens = drained.keys().copied().collect::<Vec<_>>();
+    /// let mut odds = map.keys().copied().collect::<Vec<_>>();
+    /// evens.sort();
+    /// odds.sort();
+    ///
+    /// assert_eq!(evens, vec![0, 2, 4, 6]);
+    /// assert_eq!(odds, vec![1, 3, 5, 7]);
+    /// ```
+    #[inline]
+    #[unstable(feature = "hash_drain_filter", issue = "0")]
+    pub fn drain_filter<F>(&mut self, pred: F) -> DrainFilter<'_, K, V, S, F>
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        DrainFilter {
+            pred,
+            inner: self.drain_filter_inner(),
+        }
+    }
+
+    #[inline]
+    pub(super) fn drain_filter_inner(&mut self) -> DrainFilterInner<'_, K, V, S> {
+        let map: *mut base::HashMap<K, V, S> = &mut self.base;
+        DrainFilterInner {
+            map,
+            iter: unsafe { (*map).iter_mut() },
+            panic_flag: false,
+        }
+    }
 }
 
 impl<K, V, S> HashMap<K, V, S>
@@ -1188,6 +1245,38 @@ pub struct Drain<'a, K: 'a, V: 'a> {
     base: base::Drain<'a, K, V>,
 }
 
+/// A draining, filtering iterator over the entries of a `HashMap`.
+///
+/// This `struct` is created by the [`drain_filter`] method on [`HashMap`]. See its
+/// documentation for more.
+///
+/// [`drain_filter`]: struct.HashMap.html#method.drain_filter
+/// [`HashMap`]: struct.HashMap.html
+#[unstable(feature = "hash_drain_filter", issue = "0")]
+pub struct DrainFilter<'a, K, V, S, F>
+where
+    K: 'a + Hash,
+    V: 'a,
+    S: 'a + BuildHasher,
+    F: FnMut(&K, &mut V) -> bool,
+{
+    pred: F,
+    inner: DrainFilterInner<'a, K, V, S>,
+}
+
+/// The part of `DrainFilter` that does not depend on the type of the predicate,
+/// shared with `HashSet`'s `DrainFilter`.
+pub(super) struct DrainFilterInner<'a, K: 'a, V: 'a, S: 'a> {
+    // `iter` walks the raw table while entries it has already yielded are
+    // erased through `map`. Erasing an entry neither moves the other entries
+    // nor resizes the table, so `iter` remains valid throughout.
+    map: *mut base::HashMap<K, V, S>,
+    iter: base::IterMut<'a, K, V>,
+    /// Set while the predicate runs, so that `DrainFilter`'s destructor does
+    /// not call a predicate that has panicked again.
+    panic_flag: bool,
+}
+
 impl<'a, K, V> Drain<'a, K, V> {
     /// Returns a iterator of references over the remaining items.
     #[inline]
@@ -1933,6 +2022,104 @@ where
     }
 }
 
+#[unstable(feature = "hash_drain_filter", issue = "0")]
+impl<K, V, S, F> Iterator for DrainFilter<'_, K, V, S, F>
+where
+    K: Hash,
+    S: BuildHasher,
+    F: FnMut(&K, &mut V) -> bool,
+{
+    type Item = (K, V);
+
+    #[inline]
+    fn next(&mut self) -> Option<(K, V)> {
+        self.inner.next(&mut self.pred)
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+#[unstable(feature = "hash_drain_filter", issue = "0")]
+impl<K, V, S, F> FusedIterator for DrainFilter<'_, K, V, S, F>
+where
+    K: Hash,
+    S: BuildHasher,
+    F: FnMut(&K, &mut V) -> bool,
+{
+}
+
+#[unstable(feature = "hash_drain_filter", issue = "0")]
+impl<K, V, S, F> Drop for DrainFilter<'_, K, V, S, F>
+where
+    K: Hash,
+    S: BuildHasher,
+    F: FnMut(&K, &mut V) -> bool,
+{
+    fn drop(&mut self) {
+        // Consume the remaining elements unless the predicate has panicked.
+        if !self.inner.panicked() {
+            self.for_each(drop);
+        }
+    }
+}
+
+#[unstable(feature = "hash_drain_filter", issue = "0")]
+impl<K, V, S, F> fmt::Debug for DrainFilter<'_, K, V, S, F>
+where
+    K: Hash,
+    S: BuildHasher,
+    F: FnMut(&K, &mut V) -> bool,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("DrainFilter { .. }")
+    }
+}
+
+impl<'a, K: Hash, V, S: BuildHasher> DrainFilterInner<'a, K, V, S> {
+    #[inline]
+    pub(super) fn next<F>(&mut self, pred: &mut F) -> Option<(K, V)>
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        while let Some((k, v)) = self.iter.next() {
+            self.panic_flag = true;
+            let drained = pred(k, v);
+            self.panic_flag = false;
+            if drained {
+                return Some(unsafe { self.remove(k) });
+            }
+        }
+        None
+    }
+
+    #[inline]
+    pub(super) fn panicked(&self) -> bool {
+        self.panic_flag
+    }
+
+    #[inline]
+    pub(super) fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+
+    /// Erases the entry `key` points into from the table and returns it.
+    ///
+    /// The entry is located by address rather than by comparing keys, so this
+    /// never calls into the key's `Eq` implementation. `key` must have been
+    /// yielded by `iter` and not removed yet.
+    unsafe fn remove(&mut self, key: &K) -> (K, V) {
+        let map = &mut *self.map;
+        let mut state = map.hasher().build_hasher();
+        key.hash(&mut state);
+        let key: *const K = key;
+        match map.raw_entry_mut().from_hash(state.finish(), |k| k as *const K == key) {
+            base::RawEntryMut::Occupied(entry) => entry.remove_entry(),
+            base::RawEntryMut::Vacant(_) => unreachable!(),
+        }
+    }
+}
+
 impl<'a, K, V> Entry<'a, K, V> {
     #[stable(feature = "rust1", since = "1.0.0")]
     /// Ensures a value is in the entry by inserting the default if empty, and returns
@@ -2604,6 +2791,7 @@ mod test_map {
     use super::HashMap;
     use super::RandomState;
     use crate::cell::RefCell;
+    use crate::panic::{catch_unwind, AssertUnwindSafe};
     use rand::{thread_rng, Rng};
     use realstd::collections::CollectionAllocErr::*;
     use realstd::usize;
@@ -3392,6 +3580,86 @@ mod test_map {
         assert_eq!(map[&6], 60);
     }
 
+    #[test]
+    fn test_drain_filter() {
+        {
+            let mut map: HashMap<i32, i32> = (0..8).map(|x| (x, x * 10)).collect();
+            let drained = map.drain_filter(|&k, _| k % 2 == 0);
+            let mut out = drained.collect::<Vec<_>>();
+            out.sort_unstable();
+            assert_eq!(vec![(0, 0), (2, 20), (4, 40), (6, 60)], out);
+            assert_eq!(map.len(), 4);
+        }
+        {
+            let mut map: HashMap<i32, i32> = (0..8).map(|x| (x, x * 10)).collect();
+            map.drain_filter(|&k, _| k % 2 == 0);
+            assert_eq!(map.len(), 4);
+        }
+    }
+
+    #[test]
+    fn test_drain_filter_mutating() {
+        let mut map: HashMap<i32, i32> = (0..100).map(|x| (x, x)).collect();
+        let drained = map.drain_filter(|_, v| {
+            *v += 1;
+            *v % 3 == 0
+        }).count();
+        assert_eq!(drained, 33);
+        assert_eq!(map.len(), 67);
+        for (&k, &v) in &map {
+            assert_eq!(v, k + 1);
+            assert!(v % 3 != 0);
+        }
+    }
+
+    #[test]
+    fn test_drain_filter_drop_early() {
+        let mut map: HashMap<i32, i32> = (0..1000).map(|x| (x, x)).collect();
+        {
+            let mut iter = map.drain_filter(|&k, _| k % 5 == 0);
+            assert!(iter.next().is_some());
+            assert!(iter.next().is_some());
+        }
+        assert_eq!(map.len(), 800);
+        assert!(map.keys().all(|&k| k % 5 != 0));
+        // The table must still be usable after removing entries mid-iteration.
+        for i in 0..1000 {
+            map.insert(i, i);
+        }
+        assert_eq!(map.len(), 1000);
+    }
+
+    #[test]
+    fn test_drain_filter_pred_panic() {
+        static mut DROPS: i32 = 0;
+        static mut PREDS: i32 = 0;
+
+        struct D;
+        impl Drop for D {
+            fn drop(&mut self) {
+                unsafe { DROPS += 1; }
+            }
+        }
+
+        let mut map = (0..3).map(|i| (i, D)).collect::<HashMap<_, _>>();
+
+        catch_unwind(AssertUnwindSafe(|| {
+            map.drain_filter(|_, _| {
+                unsafe { PREDS += 1; }
+                panic!("panic in predicate")
+            }).for_each(drop)
+        })).unwrap_err();
+
+        // The predicate is not called again once it has panicked, and no
+        // element was removed.
+        assert_eq!(unsafe { PREDS }, 1);
+        assert_eq!(unsafe { DROPS }, 0);
+        assert_eq!(map.len(), 3);
+
+        drop(map);
+        assert_eq!(unsafe { DROPS }, 3);
+    }
+
     #[test]
     fn test_try_reserve() {
         let mut empty_bytes: HashMap<u8, u8> = HashMap::new();
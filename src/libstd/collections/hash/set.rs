@@ -879,6 +879,46 @@ impl<T, S> HashSet<T, S>
     {
         self.map.retain(|k, _| f(k));
     }
+
+    /// Creates an iterator which uses a closure to determine if a value should be removed.
+    ///
+    /// If the closure returns true, then the value is removed and yielded.
+    /// If the closure returns false, the value will remain in the set and will not be yielded
+    /// by the iterator.
+    ///
+    /// If the iterator is only partially consumed or not consumed at all, each of the remaining
+    /// values will still be subjected to the closure and removed and dropped if it returns true.
+    ///
+    /// It is unspecified how many more values will be subjected to the closure
+    /// if a panic occurs in the closure, or if a panic occurs while dropping a value, or if the
+    /// `DrainFilter` itself is leaked.
+    ///
+    /// # Examples
+    ///
+    /// Splitting a set into even and odd values, reusing the original set:
+    ///
+    /// ```
+    /// #![feature(hash_drain_filter)]
+    /// use std::collections::HashSet;
+    ///
+    /// let mut set: HashSet<i32> = (0..8).collect();
+    /// let drained: HashSet<i32> = set.drain_filter(|v| v % 2 == 0).collect();
+    ///
+    /// let mut evens = drained.into_iter().collect::<Vec<_>>();
+    /// let mut odds = set.into_iter().collect::<Vec<_>>();
+    /// evens.sort();
+    /// odds.sort();
+    ///
+    /// assert_eq!(evens, vec![0, 2, 4, 6]);
+    /// assert_eq!(odds, vec![1, 3, 5, 7]);
+    /// ```
+    #[inline]
+    #[unstable(feature = "hash_drain_filter", issue = "0")]
+    pub fn drain_filter<F>(&mut self, pred: F) -> DrainFilter<'_, T, S, F>
+        where F: FnMut(&T) -> bool
+    {
+        DrainFilter { pred, inner: self.map.drain_filter_inner() }
+    }
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
@@ -1123,6 +1163,23 @@ pub struct Drain<'a, K: 'a> {
     iter: map::Drain<'a, K, ()>,
 }
 
+/// A draining, filtering iterator over the items of a `HashSet`.
+///
+/// This `struct` is created by the [`drain_filter`] method on [`HashSet`].
+/// See its documentation for more.
+///
+/// [`HashSet`]: struct.HashSet.html
+/// [`drain_filter`]: struct.HashSet.html#method.drain_filter
+#[unstable(feature = "hash_drain_filter", issue = "0")]
+pub struct DrainFilter<'a, K, S, F>
+    where K: 'a + Hash,
+          S: 'a + BuildHasher,
+          F: FnMut(&K) -> bool
+{
+    pred: F,
+    inner: map::DrainFilterInner<'a, K, (), S>,
+}
+
 /// A lazy iterator producing elements in the intersection of `HashSet`s.
 ///
 /// This `struct` is created by the [`intersection`] method on [`HashSet`].
@@ -1322,6 +1379,57 @@ impl<K: fmt::Debug> fmt::Debug for Drain<'_, K> {
     }
 }
 
+#[unstable(feature = "hash_drain_filter", issue = "0")]
+impl<K, S, F> Iterator for DrainFilter<'_, K, S, F>
+    where K: Hash,
+          S: BuildHasher,
+          F: FnMut(&K) -> bool
+{
+    type Item = K;
+
+    #[inline]
+    fn next(&mut self) -> Option<K> {
+        let pred = &mut self.pred;
+        let mut mapped_pred = |k: &K, _v: &mut ()| pred(k);
+        self.inner.next(&mut mapped_pred).map(|(k, _)| k)
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+#[unstable(feature = "hash_drain_filter", issue = "0")]
+impl<K, S, F> FusedIterator for DrainFilter<'_, K, S, F>
+    where K: Hash,
+          S: BuildHasher,
+          F: FnMut(&K) -> bool
+{}
+
+#[unstable(feature = "hash_drain_filter", issue = "0")]
+impl<K, S, F> Drop for DrainFilter<'_, K, S, F>
+    where K: Hash,
+          S: BuildHasher,
+          F: FnMut(&K) -> bool
+{
+    fn drop(&mut self) {
+        // Consume the remaining elements unless the predicate has panicked.
+        if !self.inner.panicked() {
+            self.for_each(drop);
+        }
+    }
+}
+
+#[unstable(feature = "hash_drain_filter", issue = "0")]
+impl<K, S, F> fmt::Debug for DrainFilter<'_, K, S, F>
+    where K: Hash,
+          S: BuildHasher,
+          F: FnMut(&K) -> bool
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad("DrainFilter { .. }")
+    }
+}
+
 #[stable(feature = "rust1", since = "1.0.0")]
 impl<T, S> Clone for Intersection<'_, T, S> {
     #[inline]
@@ -1951,4 +2059,22 @@ mod test_set {
         assert!(set.contains(&4));
         assert!(set.contains(&6));
     }
+
+    #[test]
+    fn test_drain_filter() {
+        let mut x: HashSet<_> = [1].iter().copied().collect();
+        let mut y: HashSet<_> = [1].iter().copied().collect();
+
+        x.drain_filter(|_| true);
+        y.drain_filter(|_| false);
+        assert_eq!(x.len(), 0);
+        assert_eq!(y.len(), 1);
+
+        let mut set: HashSet<i32> = (0..1000).collect();
+        let mut drained = set.drain_filter(|v| v % 3 == 0).collect::<Vec<_>>();
+        drained.sort_unstable();
+        assert_eq!(drained, (0..1000).filter(|v| v % 3 == 0).collect::<Vec<_>>());
+        assert_eq!(set.len(), 666);
+        assert!(set.iter().all(|v| v % 3 != 0));
+    }
 }
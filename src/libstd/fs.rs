@@ -1616,7 +1616,9 @@ pub fn rename<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> io::Result<()>
 /// On Windows, this function currently corresponds to `CopyFileEx`. Alternate
 /// NTFS streams are copied but only the size of the main stream is returned by
 /// this function. On MacOS, this function corresponds to `fclonefileat` and
-/// `fcopyfile`.
+/// `fcopyfile`. On Linux and Android the data is copied with `copy_file_range`
+/// when the kernel supports it, falling back to `sendfile` and finally to a
+/// read/write loop.
 /// Note that, this [may change in the future][changes].
 ///
 /// [changes]: ../io/index.html#platform-specific-behavior
@@ -24,6 +24,19 @@ use crate::mem;
 /// `write` returns an error. All instances of `ErrorKind::Interrupted` are
 /// handled by this function and the underlying operation is retried.
 ///
+/// # Platform-specific behavior
+///
+/// On Linux (including Android), this function uses `copy_file_range(2)`,
+/// `sendfile(2)` or `splice(2)` syscalls to move data directly between file
+/// descriptors if possible, for example when both ends are a [`File`],
+/// [`TcpStream`] or a child process pipe.
+///
+/// Note that platform-specific behavior [may change in the future][changes].
+///
+/// [`File`]: ../fs/struct.File.html
+/// [`TcpStream`]: ../net/struct.TcpStream.html
+/// [changes]: index.html#platform-specific-behavior
+///
 /// # Examples
 ///
 /// ```
@@ -42,6 +55,20 @@ use crate::mem;
 #[stable(feature = "rust1", since = "1.0.0")]
 pub fn copy<R: ?Sized, W: ?Sized>(reader: &mut R, writer: &mut W) -> io::Result<u64>
     where R: Read, W: Write
+{
+    cfg_if::cfg_if! {
+        if #[cfg(any(target_os = "linux", target_os = "android"))] {
+            crate::sys::kernel_copy::copy_spec(reader, writer)
+        } else {
+            generic_copy(reader, writer)
+        }
+    }
+}
+
+/// The general read-write-loop implementation of
+/// `io::copy` that is used when specializations are not available or not applicable.
+pub(crate) fn generic_copy<R: ?Sized, W: ?Sized>(reader: &mut R, writer: &mut W) -> io::Result<u64>
+    where R: Read, W: Write
 {
     let mut buf = unsafe {
         #[allow(deprecated)]
@@ -201,7 +201,7 @@ impl Condvar {
         let poisoned = unsafe {
             let lock = mutex::guard_lock(&guard);
             self.verify(lock);
-            self.inner.wait(lock);
+            mutex::guard_wait(&guard, |lock| self.inner.wait(lock));
             mutex::guard_poison(&guard).get()
         };
         if poisoned {
@@ -402,7 +402,7 @@ impl Condvar {
         let (poisoned, result) = unsafe {
             let lock = mutex::guard_lock(&guard);
             self.verify(lock);
-            let success = self.inner.wait_timeout(lock, dur);
+            let success = mutex::guard_wait(&guard, |lock| self.inner.wait_timeout(lock, dur));
             (mutex::guard_poison(&guard).get(), WaitTimeoutResult(!success))
         };
         if poisoned {
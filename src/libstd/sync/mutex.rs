@@ -5,6 +5,7 @@ use crate::ops::{Deref, DerefMut};
 use crate::ptr;
 use crate::sys_common::mutex as sys;
 use crate::sys_common::poison::{self, TryLockError, TryLockResult, LockResult};
+use crate::time::{Duration, Instant};
 
 /// A mutual exclusion primitive useful for protecting shared data
 ///
@@ -115,6 +116,9 @@ pub struct Mutex<T: ?Sized> {
     // ensure that the native mutex is used correctly we box the inner mutex to
     // give it a constant address.
     inner: Box<sys::Mutex>,
+    // Only present for fair mutexes. Lockers wait for their turn in this queue
+    // before acquiring `inner`, and give it back after releasing `inner`.
+    queue: Option<Box<sys::FairQueue>>,
     poison: poison::Flag,
     data: UnsafeCell<T>,
 }
@@ -166,9 +170,95 @@ impl<T> Mutex<T> {
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn new(t: T) -> Mutex<T> {
+        Mutex::with_policy(t, poison::Flag::new(), false)
+    }
+
+    /// Creates a new mutex in an unlocked state which is never poisoned.
+    ///
+    /// A panic while holding the lock of this mutex does not poison it, so
+    /// [`lock`] and [`try_lock`] only ever fail because the lock is
+    /// contended. It is up to the user to make sure the protected data is
+    /// still in a consistent state after such a panic.
+    ///
+    /// [`lock`]: #method.lock
+    /// [`try_lock`]: #method.try_lock
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(lock_policy)]
+    ///
+    /// use std::sync::{Arc, Mutex};
+    /// use std::thread;
+    ///
+    /// let mutex = Arc::new(Mutex::new_non_poisoning(0));
+    /// let c_mutex = mutex.clone();
+    ///
+    /// let _ = thread::spawn(move || {
+    ///     let _lock = c_mutex.lock().unwrap();
+    ///     panic!(); // the mutex does not get poisoned
+    /// }).join();
+    /// assert!(!mutex.is_poisoned());
+    /// assert_eq!(*mutex.lock().unwrap(), 0);
+    /// ```
+    #[unstable(feature = "lock_policy", issue = "0")]
+    pub fn new_non_poisoning(t: T) -> Mutex<T> {
+        Mutex::with_policy(t, poison::Flag::disabled(), false)
+    }
+
+    /// Creates a new mutex in an unlocked state which hands out the lock in
+    /// the order it was requested.
+    ///
+    /// Threads blocked in [`lock`] or [`try_lock_for`] acquire the lock
+    /// first-come, first-served, so a thread which repeatedly locks and
+    /// unlocks the mutex can't starve the others. [`try_lock`] fails while
+    /// other threads are waiting for the lock. This makes the mutex slower
+    /// under contention than one created with [`new`].
+    ///
+    /// [`lock`]: #method.lock
+    /// [`try_lock`]: #method.try_lock
+    /// [`try_lock_for`]: #method.try_lock_for
+    /// [`new`]: #method.new
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(lock_policy)]
+    ///
+    /// use std::sync::{Arc, Mutex};
+    /// use std::thread;
+    ///
+    /// let mutex = Arc::new(Mutex::new_fair(0));
+    /// let handles: Vec<_> = (0..4).map(|_| {
+    ///     let mutex = mutex.clone();
+    ///     thread::spawn(move || {
+    ///         for _ in 0..100 {
+    ///             *mutex.lock().unwrap() += 1;
+    ///         }
+    ///     })
+    /// }).collect();
+    /// for handle in handles {
+    ///     handle.join().unwrap();
+    /// }
+    /// assert_eq!(*mutex.lock().unwrap(), 400);
+    /// ```
+    #[unstable(feature = "lock_policy", issue = "0")]
+    pub fn new_fair(t: T) -> Mutex<T> {
+        Mutex::with_policy(t, poison::Flag::new(), true)
+    }
+
+    fn with_policy(t: T, poison: poison::Flag, fair: bool) -> Mutex<T> {
+        let queue = if fair {
+            let mut queue = box sys::FairQueue::new();
+            unsafe { queue.init(); }
+            Some(queue)
+        } else {
+            None
+        };
         let mut m = Mutex {
             inner: box sys::Mutex::new(),
-            poison: poison::Flag::new(),
+            queue,
+            poison,
             data: UnsafeCell::new(t),
         };
         unsafe {
@@ -217,6 +307,9 @@ impl<T: ?Sized> Mutex<T> {
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn lock(&self) -> LockResult<MutexGuard<'_, T>> {
         unsafe {
+            if let Some(queue) = &self.queue {
+                queue.acquire();
+            }
             self.inner.raw_lock();
             MutexGuard::new(self)
         }
@@ -260,14 +353,81 @@ impl<T: ?Sized> Mutex<T> {
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn try_lock(&self) -> TryLockResult<MutexGuard<'_, T>> {
         unsafe {
+            if let Some(queue) = &self.queue {
+                if !queue.try_acquire() {
+                    return Err(TryLockError::WouldBlock);
+                }
+            }
             if self.inner.try_lock() {
                 Ok(MutexGuard::new(self)?)
             } else {
+                self.release_turn();
                 Err(TryLockError::WouldBlock)
             }
         }
     }
 
+    /// Attempts to acquire this lock, blocking the current thread for at most
+    /// `dur`.
+    ///
+    /// If the lock could not be acquired before the timeout expired, then
+    /// [`Err`] is returned. Otherwise, an RAII guard is returned. The lock
+    /// will be unlocked when the guard is dropped.
+    ///
+    /// # Errors
+    ///
+    /// If another user of this mutex panicked while holding the mutex, then
+    /// this call will return failure if the mutex would otherwise be
+    /// acquired.
+    ///
+    /// [`Err`]: ../../std/result/enum.Result.html#variant.Err
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(lock_timeout)]
+    ///
+    /// use std::sync::{Arc, Mutex};
+    /// use std::thread;
+    /// use std::time::Duration;
+    ///
+    /// let mutex = Arc::new(Mutex::new(0));
+    /// let c_mutex = mutex.clone();
+    ///
+    /// thread::spawn(move || {
+    ///     match c_mutex.try_lock_for(Duration::from_millis(100)) {
+    ///         Ok(mut guard) => *guard = 10,
+    ///         Err(_) => println!("try_lock_for timed out"),
+    ///     }
+    /// }).join().expect("thread::spawn failed");
+    /// assert_eq!(*mutex.lock().unwrap(), 10);
+    /// ```
+    #[unstable(feature = "lock_timeout", issue = "0")]
+    pub fn try_lock_for(&self, dur: Duration) -> TryLockResult<MutexGuard<'_, T>> {
+        unsafe {
+            let start = Instant::now();
+            if let Some(queue) = &self.queue {
+                if !queue.acquire_for(dur) {
+                    return Err(TryLockError::WouldBlock);
+                }
+            }
+            let remaining = dur.checked_sub(start.elapsed()).unwrap_or_default();
+            if self.inner.try_lock_for(remaining) {
+                Ok(MutexGuard::new(self)?)
+            } else {
+                self.release_turn();
+                Err(TryLockError::WouldBlock)
+            }
+        }
+    }
+
+    /// Lets the next waiter of a fair mutex take its turn.
+    unsafe fn release_turn(&self) {
+        if let Some(queue) = &self.queue {
+            queue.release();
+        }
+    }
+
     /// Determines whether the mutex is poisoned.
     ///
     /// If another thread is active, the mutex can still become poisoned at any
@@ -319,14 +479,17 @@ impl<T: ?Sized> Mutex<T> {
         // but because `Mutex` impl-s `Drop`, we can't move out of it, so
         // we'll have to destructure it manually instead.
         unsafe {
-            // Like `let Mutex { inner, poison, data } = self`.
-            let (inner, poison, data) = {
-                let Mutex { ref inner, ref poison, ref data } = self;
-                (ptr::read(inner), ptr::read(poison), ptr::read(data))
+            // Like `let Mutex { inner, queue, poison, data } = self`.
+            let (inner, queue, poison, data) = {
+                let Mutex { ref inner, ref queue, ref poison, ref data } = self;
+                (ptr::read(inner), ptr::read(queue), ptr::read(poison), ptr::read(data))
             };
             mem::forget(self);
             inner.destroy();  // Keep in sync with the `Drop` impl.
             drop(inner);
+            if let Some(queue) = queue {
+                queue.destroy();
+            }
 
             poison::map_result(poison.borrow(), |_| data.into_inner())
         }
@@ -368,7 +531,12 @@ unsafe impl<#[may_dangle] T: ?Sized> Drop for Mutex<T> {
         // dropped, that's not our job)
         //
         // IMPORTANT: This code must be kept in sync with `Mutex::into_inner`.
-        unsafe { self.inner.destroy() }
+        unsafe {
+            self.inner.destroy();
+            if let Some(queue) = &self.queue {
+                queue.destroy();
+            }
+        }
     }
 }
 
@@ -447,6 +615,7 @@ impl<T: ?Sized> Drop for MutexGuard<'_, T> {
         unsafe {
             self.__lock.poison.done(&self.__poison);
             self.__lock.inner.raw_unlock();
+            self.__lock.release_turn();
         }
     }
 }
@@ -473,12 +642,36 @@ pub fn guard_poison<'a, T: ?Sized>(guard: &MutexGuard<'a, T>) -> &'a poison::Fla
     &guard.__lock.poison
 }
 
+/// Calls `wait` with the OS mutex of `guard`, which `wait` is expected to
+/// release and reacquire like a condition variable does.
+///
+/// A fair mutex gives up its turn for the duration of the wait, and waits for
+/// a new one afterwards without holding the OS mutex, as the thread that has
+/// the turn next may be blocked on it.
+pub unsafe fn guard_wait<'a, T: ?Sized, R, F>(guard: &MutexGuard<'a, T>, wait: F) -> R
+    where F: FnOnce(&'a sys::Mutex) -> R
+{
+    let lock = &guard.__lock.inner;
+    match &guard.__lock.queue {
+        None => wait(lock),
+        Some(queue) => {
+            queue.release();
+            let r = wait(lock);
+            lock.raw_unlock();
+            queue.acquire();
+            lock.raw_lock();
+            r
+        }
+    }
+}
+
 #[cfg(all(test, not(target_os = "emscripten")))]
 mod tests {
     use crate::sync::mpsc::channel;
     use crate::sync::{Arc, Mutex, Condvar};
     use crate::sync::atomic::{AtomicUsize, Ordering};
     use crate::thread;
+    use crate::time::Duration;
 
     struct Packet<T>(Arc<(Mutex<T>, Condvar)>);
 
@@ -703,4 +896,102 @@ mod tests {
         let comp: &[i32] = &[4, 2, 5];
         assert_eq!(&*mutex.lock().unwrap(), comp);
     }
+
+    #[test]
+    fn test_try_lock_for() {
+        let m = Arc::new(Mutex::new(0));
+        let m2 = m.clone();
+        let (locked_tx, locked_rx) = channel();
+        let (release_tx, release_rx) = channel::<()>();
+        let t = thread::spawn(move || {
+            let _lock = m2.lock().unwrap();
+            locked_tx.send(()).unwrap();
+            release_rx.recv().unwrap();
+        });
+        locked_rx.recv().unwrap();
+        assert!(m.try_lock_for(Duration::from_millis(10)).is_err());
+
+        release_tx.send(()).unwrap();
+        *m.try_lock_for(Duration::from_secs(60)).unwrap() += 1;
+        t.join().unwrap();
+        assert_eq!(*m.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_non_poisoning() {
+        let m = Arc::new(Mutex::new_non_poisoning(NonCopy(1)));
+        let m2 = m.clone();
+        let _ = thread::spawn(move || {
+            let _lock = m2.lock().unwrap();
+            panic!("test panic in inner thread to poison mutex");
+        }).join();
+
+        assert!(!m.is_poisoned());
+        assert_eq!(*m.lock().unwrap(), NonCopy(1));
+        assert_eq!(*m.try_lock().unwrap(), NonCopy(1));
+    }
+
+    #[test]
+    fn test_fair_order() {
+        let m = Arc::new(Mutex::new_fair(Vec::new()));
+        let guard = m.lock().unwrap();
+        let threads: Vec<_> = (0..4).map(|i| {
+            let m2 = m.clone();
+            let t = thread::spawn(move || m2.lock().unwrap().push(i));
+            // Only spawn the next thread once this one is queued up.
+            while m.queue.as_ref().unwrap().waiting() != i + 1 {
+                thread::yield_now();
+            }
+            t
+        }).collect();
+        drop(guard);
+        for t in threads {
+            t.join().unwrap();
+        }
+        assert_eq!(*m.lock().unwrap(), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_fair_try_lock() {
+        let m = Arc::new(Mutex::new_fair(0));
+        let guard = m.lock().unwrap();
+        assert!(m.try_lock().is_err());
+        assert!(m.try_lock_for(Duration::from_millis(10)).is_err());
+        // The timed out attempt must not hold up the queue.
+        assert_eq!(m.queue.as_ref().unwrap().waiting(), 0);
+
+        let m2 = m.clone();
+        let t = thread::spawn(move || *m2.lock().unwrap() += 1);
+        while m.queue.as_ref().unwrap().waiting() != 1 {
+            thread::yield_now();
+        }
+        drop(guard);
+        t.join().unwrap();
+        assert_eq!(*m.try_lock().unwrap(), 1);
+        assert_eq!(*m.try_lock_for(Duration::from_millis(10)).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_fair_condvar() {
+        let packet = Packet(Arc::new((Mutex::new_fair(false), Condvar::new())));
+        let packet2 = Packet(packet.0.clone());
+        let (tx, rx) = channel();
+        let _t = thread::spawn(move|| {
+            // wait until parent gets in
+            rx.recv().unwrap();
+            let &(ref lock, ref cvar) = &*packet2.0;
+            let mut lock = lock.lock().unwrap();
+            *lock = true;
+            cvar.notify_one();
+        });
+
+        let &(ref lock, ref cvar) = &*packet.0;
+        let mut lock = lock.lock().unwrap();
+        tx.send(()).unwrap();
+        assert!(!*lock);
+        while !*lock {
+            let (guard, _) = cvar.wait_timeout(lock, Duration::from_millis(1)).unwrap();
+            lock = guard;
+        }
+    }
 }
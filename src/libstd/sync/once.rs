@@ -143,7 +143,9 @@ struct Waiter {
 // Helper struct used to clean up after a closure call with a `Drop`
 // implementation to also run on panic.
 struct Finish<'a> {
-    panicked: bool,
+    // The state stored when this is dropped. It starts out as `POISONED` so
+    // that a panicking closure poisons the `Once`.
+    set_state_on_drop_to: usize,
     me: &'a Once,
 }
 
@@ -222,7 +224,10 @@ impl Once {
         }
 
         let mut f = Some(f);
-        self.call_inner(false, &mut |_| f.take().unwrap()());
+        self.call_inner(false, &mut |_| {
+            f.take().unwrap()();
+            true
+        });
     }
 
     /// Performs the same function as [`call_once`] except ignores poisoning.
@@ -280,10 +285,25 @@ impl Once {
 
         let mut f = Some(f);
         self.call_inner(true, &mut |p| {
-            f.take().unwrap()(&OnceState { poisoned: p })
+            f.take().unwrap()(&OnceState { poisoned: p });
+            true
         });
     }
 
+    /// Like `call_once`, but the closure can report that initialization
+    /// failed by returning `false`. In that case the `Once` is put back into
+    /// the state it was in before, so a later call can try again. Used by
+    /// `OnceLock::get_or_try_init`.
+    pub(super) fn call_once_fallible<F>(&self, f: F) where F: FnOnce() -> bool {
+        // Fast path check
+        if self.is_completed() {
+            return;
+        }
+
+        let mut f = Some(f);
+        self.call_inner(false, &mut |_| f.take().unwrap()());
+    }
+
     /// Returns `true` if some `call_once` call has completed
     /// successfully. Specifically, `is_completed` will return false in
     /// the following situations:
@@ -345,10 +365,13 @@ impl Once {
     // Finally, this takes an `FnMut` instead of a `FnOnce` because there's
     // currently no way to take an `FnOnce` and call it via virtual dispatch
     // without some allocation overhead.
+    //
+    // `init` returns whether initialization succeeded; if it did not, the
+    // state is restored to what it was before `init` was called.
     #[cold]
     fn call_inner(&self,
                   ignore_poisoning: bool,
-                  init: &mut dyn FnMut(bool)) {
+                  init: &mut dyn FnMut(bool) -> bool) {
 
         // This cold path uses SeqCst consistently because the
         // performance difference really does not matter there, and
@@ -386,11 +409,11 @@ impl Once {
                     // up other waiters both in the normal return and panicking
                     // case.
                     let mut complete = Finish {
-                        panicked: true,
+                        set_state_on_drop_to: POISONED,
                         me: self,
                     };
-                    init(state == POISONED);
-                    complete.panicked = false;
+                    let success = init(state == POISONED);
+                    complete.set_state_on_drop_to = if success { COMPLETE } else { state };
                     return
                 }
 
@@ -445,11 +468,7 @@ impl Drop for Finish<'_> {
     fn drop(&mut self) {
         // Swap out our state with however we finished. We should only ever see
         // an old state which was RUNNING.
-        let queue = if self.panicked {
-            self.me.state.swap(POISONED, Ordering::SeqCst)
-        } else {
-            self.me.state.swap(COMPLETE, Ordering::SeqCst)
-        };
+        let queue = self.me.state.swap(self.set_state_on_drop_to, Ordering::SeqCst);
         assert_eq!(queue & STATE_MASK, RUNNING);
 
         // Decode the RUNNING to a list of waiters, then walk that entire list
@@ -3,8 +3,10 @@ use crate::fmt;
 use crate::mem;
 use crate::ops::{Deref, DerefMut};
 use crate::ptr;
+use crate::sys_common::mutex as sys_mutex;
 use crate::sys_common::poison::{self, LockResult, TryLockError, TryLockResult};
 use crate::sys_common::rwlock as sys;
+use crate::time::{Duration, Instant};
 
 /// A reader-writer lock
 ///
@@ -20,7 +22,9 @@ use crate::sys_common::rwlock as sys;
 ///
 /// The priority policy of the lock is dependent on the underlying operating
 /// system's implementation, and this type does not guarantee that any
-/// particular policy will be used.
+/// particular policy will be used. A lock created with
+/// [`new_writer_preferring`] instead makes sure that readers can't starve
+/// writers.
 ///
 /// The type parameter `T` represents the data that this lock protects. It is
 /// required that `T` satisfies [`Send`] to be shared across threads and
@@ -63,9 +67,14 @@ use crate::sys_common::rwlock as sys;
 /// [`Send`]: ../../std/marker/trait.Send.html
 /// [`Sync`]: ../../std/marker/trait.Sync.html
 /// [`Mutex`]: struct.Mutex.html
+/// [`new_writer_preferring`]: #method.new_writer_preferring
 #[stable(feature = "rust1", since = "1.0.0")]
 pub struct RwLock<T: ?Sized> {
     inner: Box<sys::RWLock>,
+    // Only present for writer preferring locks. Writers hold this mutex while
+    // they wait for the lock, and readers pass through it before acquiring
+    // the lock, so no new readers are let in once a writer is waiting.
+    writer_gate: Option<Box<sys_mutex::Mutex>>,
     poison: poison::Flag,
     data: UnsafeCell<T>,
 }
@@ -130,9 +139,77 @@ impl<T> RwLock<T> {
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn new(t: T) -> RwLock<T> {
+        RwLock::with_policy(t, poison::Flag::new(), false)
+    }
+
+    /// Creates a new instance of an `RwLock<T>` which is unlocked and is never
+    /// poisoned.
+    ///
+    /// A panic while holding the write lock does not poison the lock, so the
+    /// locking methods only ever fail because the lock is contended. It is up
+    /// to the user to make sure the protected data is still in a consistent
+    /// state after such a panic.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(lock_policy)]
+    ///
+    /// use std::sync::{Arc, RwLock};
+    /// use std::thread;
+    ///
+    /// let lock = Arc::new(RwLock::new_non_poisoning(0));
+    /// let c_lock = lock.clone();
+    ///
+    /// let _ = thread::spawn(move || {
+    ///     let _lock = c_lock.write().unwrap();
+    ///     panic!(); // the lock does not get poisoned
+    /// }).join();
+    /// assert!(!lock.is_poisoned());
+    /// ```
+    #[unstable(feature = "lock_policy", issue = "0")]
+    pub fn new_non_poisoning(t: T) -> RwLock<T> {
+        RwLock::with_policy(t, poison::Flag::disabled(), false)
+    }
+
+    /// Creates a new instance of an `RwLock<T>` which is unlocked and gives
+    /// waiting writers precedence over new readers.
+    ///
+    /// Once a writer is waiting for the lock, further readers block until that
+    /// writer has acquired and released it, so a steady stream of readers
+    /// can't starve writers. As a consequence, a thread which already holds a
+    /// read lock must not acquire another one, as that deadlocks if a writer
+    /// started waiting in between.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(lock_policy)]
+    ///
+    /// use std::sync::RwLock;
+    ///
+    /// let lock = RwLock::new_writer_preferring(5);
+    /// assert_eq!(*lock.read().unwrap(), 5);
+    /// *lock.write().unwrap() += 1;
+    /// assert_eq!(*lock.read().unwrap(), 6);
+    /// ```
+    #[unstable(feature = "lock_policy", issue = "0")]
+    pub fn new_writer_preferring(t: T) -> RwLock<T> {
+        RwLock::with_policy(t, poison::Flag::new(), true)
+    }
+
+    fn with_policy(t: T, poison: poison::Flag, prefer_writers: bool) -> RwLock<T> {
+        let writer_gate = if prefer_writers {
+            let mut gate = box sys_mutex::Mutex::new();
+            unsafe { gate.init(); }
+            Some(gate)
+        } else {
+            None
+        };
         RwLock {
             inner: box sys::RWLock::new(),
-            poison: poison::Flag::new(),
+            writer_gate,
+            poison,
             data: UnsafeCell::new(t),
         }
     }
@@ -182,6 +259,10 @@ impl<T: ?Sized> RwLock<T> {
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn read(&self) -> LockResult<RwLockReadGuard<'_, T>> {
         unsafe {
+            if let Some(gate) = &self.writer_gate {
+                gate.raw_lock();
+                gate.raw_unlock();
+            }
             self.inner.read();
             RwLockReadGuard::new(self)
         }
@@ -221,6 +302,12 @@ impl<T: ?Sized> RwLock<T> {
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn try_read(&self) -> TryLockResult<RwLockReadGuard<'_, T>> {
         unsafe {
+            if let Some(gate) = &self.writer_gate {
+                if !gate.try_lock() {
+                    return Err(TryLockError::WouldBlock);
+                }
+                gate.raw_unlock();
+            }
             if self.inner.try_read() {
                 Ok(RwLockReadGuard::new(self)?)
             } else {
@@ -229,6 +316,60 @@ impl<T: ?Sized> RwLock<T> {
         }
     }
 
+    /// Attempts to acquire this rwlock with shared read access, blocking the
+    /// current thread for at most `dur`.
+    ///
+    /// If the access could not be granted before the timeout expired, then
+    /// `Err` is returned. Otherwise, an RAII guard is returned which will
+    /// release the shared access when it is dropped.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the [`Poisoned`] error if the RwLock is poisoned.
+    /// An RwLock is poisoned whenever a writer panics while holding an exclusive
+    /// lock. `Poisoned` will only be returned if the lock would have otherwise been
+    /// acquired.
+    ///
+    /// This function will return the [`WouldBlock`] error if the RwLock could not
+    /// be acquired before the timeout expired.
+    ///
+    /// [`Poisoned`]: ../../std/sync/enum.TryLockError.html#variant.Poisoned
+    /// [`WouldBlock`]: ../../std/sync/enum.TryLockError.html#variant.WouldBlock
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(lock_timeout)]
+    ///
+    /// use std::sync::RwLock;
+    /// use std::time::Duration;
+    ///
+    /// let lock = RwLock::new(1);
+    ///
+    /// let w = lock.write().unwrap();
+    /// assert!(lock.try_read_for(Duration::from_millis(10)).is_err());
+    /// drop(w);
+    /// assert_eq!(*lock.try_read_for(Duration::from_millis(10)).unwrap(), 1);
+    /// ```
+    #[inline]
+    #[unstable(feature = "lock_timeout", issue = "0")]
+    pub fn try_read_for(&self, dur: Duration) -> TryLockResult<RwLockReadGuard<'_, T>> {
+        unsafe {
+            let start = Instant::now();
+            if let Some(gate) = &self.writer_gate {
+                if !gate.try_lock_for(dur) {
+                    return Err(TryLockError::WouldBlock);
+                }
+                gate.raw_unlock();
+            }
+            if self.inner.try_read_for(remaining(start, dur)) {
+                Ok(RwLockReadGuard::new(self)?)
+            } else {
+                Err(TryLockError::WouldBlock)
+            }
+        }
+    }
+
     /// Locks this rwlock with exclusive write access, blocking the current
     /// thread until it can be acquired.
     ///
@@ -264,7 +405,13 @@ impl<T: ?Sized> RwLock<T> {
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn write(&self) -> LockResult<RwLockWriteGuard<'_, T>> {
         unsafe {
-            self.inner.write();
+            match &self.writer_gate {
+                Some(gate) => {
+                    let _gate = gate.lock();
+                    self.inner.write();
+                }
+                None => self.inner.write(),
+            }
             RwLockWriteGuard::new(self)
         }
     }
@@ -303,7 +450,77 @@ impl<T: ?Sized> RwLock<T> {
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn try_write(&self) -> TryLockResult<RwLockWriteGuard<'_, T>> {
         unsafe {
-            if self.inner.try_write() {
+            let acquired = match &self.writer_gate {
+                Some(gate) => {
+                    if !gate.try_lock() {
+                        return Err(TryLockError::WouldBlock);
+                    }
+                    let acquired = self.inner.try_write();
+                    gate.raw_unlock();
+                    acquired
+                }
+                None => self.inner.try_write(),
+            };
+            if acquired {
+                Ok(RwLockWriteGuard::new(self)?)
+            } else {
+                Err(TryLockError::WouldBlock)
+            }
+        }
+    }
+
+    /// Attempts to lock this rwlock with exclusive write access, blocking the
+    /// current thread for at most `dur`.
+    ///
+    /// If the lock could not be acquired before the timeout expired, then
+    /// `Err` is returned. Otherwise, an RAII guard is returned which will
+    /// release the lock when it is dropped.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the [`Poisoned`] error if the RwLock is poisoned.
+    /// An RwLock is poisoned whenever a writer panics while holding an exclusive
+    /// lock. `Poisoned` will only be returned if the lock would have otherwise been
+    /// acquired.
+    ///
+    /// This function will return the [`WouldBlock`] error if the RwLock could not
+    /// be acquired before the timeout expired.
+    ///
+    /// [`Poisoned`]: ../../std/sync/enum.TryLockError.html#variant.Poisoned
+    /// [`WouldBlock`]: ../../std/sync/enum.TryLockError.html#variant.WouldBlock
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(lock_timeout)]
+    ///
+    /// use std::sync::RwLock;
+    /// use std::time::Duration;
+    ///
+    /// let lock = RwLock::new(1);
+    ///
+    /// let n = lock.read().unwrap();
+    /// assert!(lock.try_write_for(Duration::from_millis(10)).is_err());
+    /// drop(n);
+    /// *lock.try_write_for(Duration::from_millis(10)).unwrap() = 2;
+    /// ```
+    #[inline]
+    #[unstable(feature = "lock_timeout", issue = "0")]
+    pub fn try_write_for(&self, dur: Duration) -> TryLockResult<RwLockWriteGuard<'_, T>> {
+        unsafe {
+            let start = Instant::now();
+            let acquired = match &self.writer_gate {
+                Some(gate) => {
+                    if !gate.try_lock_for(dur) {
+                        return Err(TryLockError::WouldBlock);
+                    }
+                    let acquired = self.inner.try_write_for(remaining(start, dur));
+                    gate.raw_unlock();
+                    acquired
+                }
+                None => self.inner.try_write_for(dur),
+            };
+            if acquired {
                 Ok(RwLockWriteGuard::new(self)?)
             } else {
                 Err(TryLockError::WouldBlock)
@@ -368,14 +585,17 @@ impl<T: ?Sized> RwLock<T> {
         // but because `RwLock` impl-s `Drop`, we can't move out of it, so
         // we'll have to destructure it manually instead.
         unsafe {
-            // Like `let RwLock { inner, poison, data } = self`.
-            let (inner, poison, data) = {
-                let RwLock { ref inner, ref poison, ref data } = self;
-                (ptr::read(inner), ptr::read(poison), ptr::read(data))
+            // Like `let RwLock { inner, writer_gate, poison, data } = self`.
+            let (inner, writer_gate, poison, data) = {
+                let RwLock { ref inner, ref writer_gate, ref poison, ref data } = self;
+                (ptr::read(inner), ptr::read(writer_gate), ptr::read(poison), ptr::read(data))
             };
             mem::forget(self);
             inner.destroy(); // Keep in sync with the `Drop` impl.
             drop(inner);
+            if let Some(gate) = writer_gate {
+                gate.destroy();
+            }
 
             poison::map_result(poison.borrow(), |_| data.into_inner())
         }
@@ -415,10 +635,20 @@ impl<T: ?Sized> RwLock<T> {
 unsafe impl<#[may_dangle] T: ?Sized> Drop for RwLock<T> {
     fn drop(&mut self) {
         // IMPORTANT: This code needs to be kept in sync with `RwLock::into_inner`.
-        unsafe { self.inner.destroy() }
+        unsafe {
+            self.inner.destroy();
+            if let Some(gate) = &self.writer_gate {
+                gate.destroy();
+            }
+        }
     }
 }
 
+/// Returns how much of `dur` is left since `start`, or zero if it has elapsed.
+fn remaining(start: Instant, dur: Duration) -> Duration {
+    dur.checked_sub(start.elapsed()).unwrap_or_default()
+}
+
 #[stable(feature = "rust1", since = "1.0.0")]
 impl<T: ?Sized + fmt::Debug> fmt::Debug for RwLock<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -562,6 +792,7 @@ mod tests {
     use crate::thread;
     use crate::sync::{Arc, RwLock, TryLockError};
     use crate::sync::atomic::{AtomicUsize, Ordering};
+    use crate::time::Duration;
 
     #[derive(Eq, PartialEq, Debug)]
     struct NonCopy(i32);
@@ -797,4 +1028,62 @@ mod tests {
             Ok(x) => panic!("get_mut of poisoned RwLock is Ok: {:?}", x),
         }
     }
+
+    #[test]
+    fn test_try_lock_for() {
+        let lock = RwLock::new(0);
+
+        let read_guard = lock.read().unwrap();
+        assert!(lock.try_read_for(Duration::from_millis(10)).is_ok());
+        match lock.try_write_for(Duration::from_millis(10)) {
+            Err(TryLockError::WouldBlock) => (),
+            Ok(_) => assert!(false, "try_write_for should not succeed while read_guard is in scope"),
+            Err(_) => assert!(false, "unexpected error"),
+        }
+        drop(read_guard);
+
+        let write_guard = lock.try_write_for(Duration::from_millis(10)).unwrap();
+        match lock.try_read_for(Duration::from_millis(10)) {
+            Err(TryLockError::WouldBlock) => (),
+            Ok(_) => assert!(false, "try_read_for should not succeed while write_guard is in scope"),
+            Err(_) => assert!(false, "unexpected error"),
+        }
+        drop(write_guard);
+    }
+
+    #[test]
+    fn test_non_poisoning() {
+        let lock = Arc::new(RwLock::new_non_poisoning(NonCopy(1)));
+        let lock2 = lock.clone();
+        let _ = thread::spawn(move || {
+            let _lock = lock2.write().unwrap();
+            panic!("test panic in inner thread to poison RwLock");
+        }).join();
+
+        assert!(!lock.is_poisoned());
+        assert_eq!(*lock.read().unwrap(), NonCopy(1));
+        assert_eq!(Arc::try_unwrap(lock).unwrap().into_inner().unwrap(), NonCopy(1));
+    }
+
+    #[test]
+    fn test_writer_preferring() {
+        let lock = Arc::new(RwLock::new_writer_preferring(0));
+        let read_guard = lock.read().unwrap();
+
+        let lock2 = lock.clone();
+        let writer = thread::spawn(move || {
+            *lock2.write().unwrap() += 1;
+        });
+
+        // Once the writer is waiting for the lock, new readers are turned away
+        // even though the lock is only held for reading.
+        while lock.try_read().is_ok() {
+            thread::yield_now();
+        }
+        assert!(lock.try_read_for(Duration::from_millis(10)).is_err());
+
+        drop(read_guard);
+        writer.join().unwrap();
+        assert_eq!(*lock.read().unwrap(), 1);
+    }
 }
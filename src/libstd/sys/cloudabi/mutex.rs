@@ -3,6 +3,7 @@ use crate::mem;
 use crate::sync::atomic::{AtomicU32, Ordering};
 use crate::sys::cloudabi::abi;
 use crate::sys::rwlock::{self, RWLock};
+use crate::time::Duration;
 
 extern "C" {
     #[thread_local]
@@ -33,6 +34,10 @@ impl Mutex {
         self.0.try_write()
     }
 
+    pub unsafe fn try_lock_for(&self, dur: Duration) -> bool {
+        self.0.try_write_for(dur)
+    }
+
     pub unsafe fn lock(&self) {
         self.0.write()
     }
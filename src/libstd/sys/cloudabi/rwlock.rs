@@ -2,6 +2,8 @@ use crate::cell::UnsafeCell;
 use crate::mem;
 use crate::sync::atomic::{AtomicU32, Ordering};
 use crate::sys::cloudabi::abi;
+use crate::sys_common::mutex::try_lock_polling;
+use crate::time::Duration;
 
 extern "C" {
     #[thread_local]
@@ -59,6 +61,11 @@ impl RWLock {
         true
     }
 
+    pub unsafe fn try_read_for(&self, dur: Duration) -> bool {
+        // The kernel lock interface has no timeout, so poll instead.
+        try_lock_polling(dur, || self.try_read())
+    }
+
     pub unsafe fn read(&self) {
         if !self.try_read() {
             // Call into the kernel to acquire a read lock.
@@ -168,6 +175,10 @@ impl RWLock {
         }
     }
 
+    pub unsafe fn try_write_for(&self, dur: Duration) -> bool {
+        try_lock_polling(dur, || self.try_write())
+    }
+
     pub unsafe fn write(&self) {
         if !self.try_write() {
             // Call into the kernel to acquire a write lock.
@@ -1,6 +1,7 @@
 use crate::cmp;
 use crate::ffi::CStr;
 use crate::io;
+use crate::num::NonZeroUsize;
 use crate::mem;
 use crate::ptr;
 use crate::sys::cloudabi::abi;
@@ -100,6 +101,16 @@ impl Drop for Thread {
     }
 }
 
+pub fn available_parallelism() -> io::Result<NonZeroUsize> {
+    match unsafe { libc::sysconf(libc::_SC_NPROCESSORS_ONLN) } {
+        -1 => Err(io::Error::last_os_error()),
+        0 => Err(io::Error::new(io::ErrorKind::NotFound,
+                                "the number of hardware threads is not known \
+                                 for the target platform")),
+        cpus => Ok(unsafe { NonZeroUsize::new_unchecked(cpus as usize) }),
+    }
+}
+
 #[cfg_attr(test, allow(dead_code))]
 pub mod guard {
     pub type Guard = !;
@@ -1,6 +1,8 @@
 use crate::cell::UnsafeCell;
 use crate::intrinsics::{atomic_cxchg, atomic_xchg};
 use crate::ptr;
+use crate::sys_common::mutex::try_lock_polling;
+use crate::time::Duration;
 
 use crate::sys::syscall::{futex, getpid, FUTEX_WAIT, FUTEX_WAKE};
 
@@ -68,6 +70,11 @@ impl Mutex {
         mutex_try_lock(self.lock.get())
     }
 
+    /// Try to lock the mutex, giving up after `dur`
+    pub unsafe fn try_lock_for(&self, dur: Duration) -> bool {
+        try_lock_polling(dur, || self.try_lock())
+    }
+
     /// Lock the mutex
     #[inline]
     pub unsafe fn lock(&self) {
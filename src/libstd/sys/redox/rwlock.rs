@@ -1,4 +1,5 @@
 use super::mutex::Mutex;
+use crate::time::Duration;
 
 pub struct RWLock {
     mutex: Mutex
@@ -24,6 +25,11 @@ impl RWLock {
         self.mutex.try_lock()
     }
 
+    #[inline]
+    pub unsafe fn try_read_for(&self, dur: Duration) -> bool {
+        self.mutex.try_lock_for(dur)
+    }
+
     #[inline]
     pub unsafe fn write(&self) {
         self.mutex.lock();
@@ -34,6 +40,11 @@ impl RWLock {
         self.mutex.try_lock()
     }
 
+    #[inline]
+    pub unsafe fn try_write_for(&self, dur: Duration) -> bool {
+        self.mutex.try_lock_for(dur)
+    }
+
     #[inline]
     pub unsafe fn read_unlock(&self) {
         self.mutex.unlock();
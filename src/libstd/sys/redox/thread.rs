@@ -1,5 +1,6 @@
 use crate::ffi::CStr;
 use crate::io;
+use crate::num::NonZeroUsize;
 use crate::mem;
 use crate::sys_common::thread::start_thread;
 use crate::sys::{cvt, syscall};
@@ -77,6 +78,13 @@ impl Thread {
     }
 }
 
+pub fn available_parallelism() -> io::Result<NonZeroUsize> {
+    // FIXME: implement on Redox
+    Err(io::Error::new(io::ErrorKind::NotFound,
+                       "the number of hardware threads is not known \
+                        for the target platform"))
+}
+
 pub mod guard {
     pub type Guard = !;
     pub unsafe fn current() -> Option<Guard> { None }
@@ -4,6 +4,9 @@ use super::abi::thread;
 
 use super::waitqueue::{WaitVariable, WaitQueue, SpinMutex, NotifiedTcs, try_lock_or_false};
 
+use crate::sys::thread::Thread;
+use crate::time::{Duration, Instant};
+
 pub struct Mutex {
     inner: SpinMutex<WaitVariable<bool>>,
 }
@@ -54,10 +57,32 @@ impl Mutex {
         }
     }
 
+    #[inline]
+    pub unsafe fn try_lock_for(&self, dur: Duration) -> bool {
+        try_lock_yielding(dur, || self.try_lock())
+    }
+
     #[inline]
     pub unsafe fn destroy(&self) {}
 }
 
+/// Repeatedly calls `try_lock` until it succeeds or `dur` has elapsed.
+///
+/// FIXME: the wait queues don't support timeouts and enclaves can't sleep, so
+/// the only option is to yield between attempts.
+pub(super) fn try_lock_yielding<F: FnMut() -> bool>(dur: Duration, mut try_lock: F) -> bool {
+    let start = Instant::now();
+    loop {
+        if try_lock() {
+            return true;
+        }
+        if start.elapsed() >= dur {
+            return false;
+        }
+        Thread::yield_now();
+    }
+}
+
 struct ReentrantLock {
     owner: Option<Tcs>,
     count: usize
@@ -4,6 +4,9 @@ use super::waitqueue::{
     try_lock_or_false, NotifiedTcs, SpinMutex, SpinMutexGuard, WaitQueue, WaitVariable,
 };
 use crate::mem;
+use crate::time::Duration;
+
+use super::mutex::try_lock_yielding;
 
 pub struct RWLock {
     readers: SpinMutex<WaitVariable<Option<NonZeroUsize>>>,
@@ -55,6 +58,11 @@ impl RWLock {
         }
     }
 
+    #[inline]
+    pub unsafe fn try_read_for(&self, dur: Duration) -> bool {
+        try_lock_yielding(dur, || self.try_read())
+    }
+
     #[inline]
     pub unsafe fn write(&self) {
         let rguard = self.readers.lock();
@@ -84,6 +92,11 @@ impl RWLock {
         }
     }
 
+    #[inline]
+    pub unsafe fn try_write_for(&self, dur: Duration) -> bool {
+        try_lock_yielding(dur, || self.try_write())
+    }
+
     #[inline]
     unsafe fn __read_unlock(
         &self,
@@ -6,9 +6,8 @@ use crate::sys::unsupported;
 use crate::time::Duration;
 use crate::path::{Component, Path, PathBuf};
 
-use crate::sync::Arc;
+use crate::sync::{Arc, LazyLock};
 use crate::collections::HashMap;
-use lazy_static::lazy_static;
 use sunrise_libuser::fs::{DirectoryEntry, DirectoryEntryType, FileTimeStampRaw, IDirectoryProxy, IFileSystemServiceProxy, IFileSystemProxy, IFileProxy};
 
 use crate::sys::os::getcwd;
@@ -53,10 +52,9 @@ impl From<LibUserError> for Error {
 }
 
 
-lazy_static! {
-    /// Registry of all filesystem prefix registered
-    static ref SCHEMA_REGISTRY: Mutex<HashMap<&'static str, Arc<IFileSystemProxy>>> = Mutex::new(HashMap::new());
-}
+/// Registry of all filesystem prefix registered
+static SCHEMA_REGISTRY: LazyLock<Mutex<HashMap<&'static str, Arc<IFileSystemProxy>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
 
 #[cfg(not(test))]
 pub fn init() {
@@ -8,10 +8,9 @@ use crate::io;
 use crate::path::{self, PathBuf};
 use crate::slice;
 use crate::str;
-use crate::sync::Mutex;
+use crate::sync::{LazyLock, Mutex};
 use crate::vec::Vec;
 use crate::collections::HashMap;
-use lazy_static::lazy_static;
 pub fn errno() -> i32 {
     0
 }
@@ -91,10 +90,9 @@ pub fn current_exe() -> io::Result<PathBuf> {
     panic!("not supported on sunrise yet")
 }
 
-lazy_static! {
-    /// Storage of all events of the current process.
-    static ref ENVIRONMENT_STORAGE: Mutex<HashMap<OsString, OsString>> = Mutex::new(HashMap::new());
-}
+/// Storage of all events of the current process.
+static ENVIRONMENT_STORAGE: LazyLock<Mutex<HashMap<OsString, OsString>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
 
 pub struct Env(Vec<(OsString, OsString)>, usize);
 
@@ -2,18 +2,15 @@ use crate::io;
 
 use sunrise_libuser::error::Error;
 use sunrise_libuser::twili::{ITwiliServiceProxy, IPipeProxy};
-use crate::sync::{LockResult, RwLock};
-use lazy_static::lazy_static;
+use crate::sync::{LazyLock, LockResult, RwLock};
 
 pub struct Stdin;
 pub struct Stdout;
 pub struct Stderr;
 
-lazy_static! {
-    static ref PIPE_STDIN: RwLock<Option<IPipeProxy>> = RwLock::new(None);
-    static ref PIPE_STDOUT: RwLock<Option<IPipeProxy>> = RwLock::new(None);
-    static ref PIPE_STDERR: RwLock<Option<IPipeProxy>> = RwLock::new(None);
-}
+static PIPE_STDIN: LazyLock<RwLock<Option<IPipeProxy>>> = LazyLock::new(|| RwLock::new(None));
+static PIPE_STDOUT: LazyLock<RwLock<Option<IPipeProxy>>> = LazyLock::new(|| RwLock::new(None));
+static PIPE_STDERR: LazyLock<RwLock<Option<IPipeProxy>>> = LazyLock::new(|| RwLock::new(None));
 
 pub fn init() -> Result<(), Error> {
     let (stdin, stdout, stderr) = ITwiliServiceProxy::new()?.open_pipes()?;
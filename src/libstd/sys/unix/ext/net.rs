@@ -29,6 +29,18 @@ use crate::sys::{self, cvt};
 use crate::sys::net::Socket;
 use crate::sys_common::{self, AsInner, FromInner, IntoInner};
 
+#[cfg(any(target_os = "android", target_os = "linux"))]
+mod ancillary;
+#[cfg(any(target_os = "android", target_os = "ios", target_os = "linux", target_os = "macos"))]
+mod ucred;
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+pub use self::ancillary::*;
+#[cfg(any(target_os = "android", target_os = "ios", target_os = "linux", target_os = "macos"))]
+#[unstable(feature = "peer_credentials_unix_socket", issue = "0")]
+pub use self::ucred::UCred;
+
 #[cfg(any(target_os = "linux", target_os = "android",
           target_os = "dragonfly", target_os = "freebsd",
           target_os = "openbsd", target_os = "netbsd",
@@ -542,6 +554,145 @@ impl UnixStream {
     pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
         self.0.shutdown(how)
     }
+
+    /// Gets the peer credentials for this Unix domain socket.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(peer_credentials_unix_socket)]
+    /// use std::os::unix::net::UnixStream;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let socket = UnixStream::connect("/tmp/sock")?;
+    ///     let peer_cred = socket.peer_cred().expect("Couldn't get peer credentials");
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "peer_credentials_unix_socket", issue = "0")]
+    #[cfg(any(target_os = "android", target_os = "ios", target_os = "linux", target_os = "macos"))]
+    pub fn peer_cred(&self) -> io::Result<UCred> {
+        ucred::peer_cred(self)
+    }
+
+    /// Receives data and ancillary data from socket.
+    ///
+    /// On success, returns the number of bytes read.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(unix_socket_ancillary_data)]
+    /// use std::os::unix::net::{UnixStream, SocketAncillary, AncillaryData};
+    /// use std::io::IoSliceMut;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let socket = UnixStream::connect("/tmp/sock")?;
+    ///     let mut buf1 = [1; 8];
+    ///     let mut buf2 = [2; 16];
+    ///     let mut buf3 = [3; 8];
+    ///     let mut bufs = &mut [
+    ///         IoSliceMut::new(&mut buf1),
+    ///         IoSliceMut::new(&mut buf2),
+    ///         IoSliceMut::new(&mut buf3),
+    ///     ][..];
+    ///     let mut ancillary_buffer = [0; 128];
+    ///     let mut ancillary = SocketAncillary::new(&mut ancillary_buffer[..]);
+    ///     let size = socket.recv_vectored_with_ancillary(bufs, &mut ancillary)?;
+    ///     println!("received {}", size);
+    ///     for ancillary_result in ancillary.messages() {
+    ///         if let AncillaryData::ScmRights(scm_rights) = ancillary_result.unwrap() {
+    ///             for fd in scm_rights {
+    ///                 println!("receive file descriptor: {}", fd);
+    ///             }
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+    pub fn recv_vectored_with_ancillary(
+        &self,
+        bufs: &mut [IoSliceMut<'_>],
+        ancillary: &mut SocketAncillary<'_>,
+    ) -> io::Result<usize> {
+        let (count, _, _) = ancillary::recv_vectored_with_ancillary_from(&self.0, bufs, ancillary)?;
+
+        Ok(count)
+    }
+
+    /// Sends data and ancillary data on the socket.
+    ///
+    /// On success, returns the number of bytes written.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(unix_socket_ancillary_data)]
+    /// use std::os::unix::net::{UnixStream, SocketAncillary};
+    /// use std::io::IoSlice;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let socket = UnixStream::connect("/tmp/sock")?;
+    ///     let buf1 = [1; 8];
+    ///     let buf2 = [2; 16];
+    ///     let buf3 = [3; 8];
+    ///     let bufs = &[
+    ///         IoSlice::new(&buf1),
+    ///         IoSlice::new(&buf2),
+    ///         IoSlice::new(&buf3),
+    ///     ][..];
+    ///     let fds = [0, 1, 2];
+    ///     let mut ancillary_buffer = [0; 128];
+    ///     let mut ancillary = SocketAncillary::new(&mut ancillary_buffer[..]);
+    ///     ancillary.add_fds(&fds[..]);
+    ///     socket.send_vectored_with_ancillary(bufs, &mut ancillary)
+    ///         .expect("send_vectored_with_ancillary function failed");
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+    pub fn send_vectored_with_ancillary(
+        &self,
+        bufs: &[IoSlice<'_>],
+        ancillary: &mut SocketAncillary<'_>,
+    ) -> io::Result<usize> {
+        ancillary::send_vectored_with_ancillary_to(&self.0, None, bufs, ancillary)
+    }
+
+    /// Moves the socket to pass unix credentials as control message in `SocketAncillary`.
+    ///
+    /// Set the socket option `SO_PASSCRED`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(unix_socket_ancillary_data)]
+    /// use std::os::unix::net::UnixStream;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let socket = UnixStream::connect("/tmp/sock")?;
+    ///     socket.set_passcred(true).expect("Couldn't set passcred");
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+    pub fn set_passcred(&self, passcred: bool) -> io::Result<()> {
+        self.0.set_passcred(passcred)
+    }
+
+    /// Get the current value of the socket for passing unix credentials in `SocketAncillary`.
+    /// This value can be change by `set_passcred`.
+    ///
+    /// Get the socket option `SO_PASSCRED`.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+    pub fn passcred(&self) -> io::Result<bool> {
+        self.0.passcred()
+    }
 }
 
 #[stable(feature = "unix_socket", since = "1.10.0")]
@@ -1455,6 +1606,149 @@ impl UnixDatagram {
     pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
         self.0.shutdown(how)
     }
+
+    /// Receives data and ancillary data from socket.
+    ///
+    /// On success, returns the number of bytes read, if the data was truncated and the address
+    /// from whence the msg came.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(unix_socket_ancillary_data)]
+    /// use std::os::unix::net::{UnixDatagram, SocketAncillary, AncillaryData};
+    /// use std::io::IoSliceMut;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let sock = UnixDatagram::unbound()?;
+    ///     let mut buf1 = [1; 8];
+    ///     let mut buf2 = [2; 16];
+    ///     let mut buf3 = [3; 8];
+    ///     let mut bufs = &mut [
+    ///         IoSliceMut::new(&mut buf1),
+    ///         IoSliceMut::new(&mut buf2),
+    ///         IoSliceMut::new(&mut buf3),
+    ///     ][..];
+    ///     let mut ancillary_buffer = [0; 128];
+    ///     let mut ancillary = SocketAncillary::new(&mut ancillary_buffer[..]);
+    ///     let (size, _truncated, sender) = sock.recv_vectored_with_ancillary_from(bufs, &mut ancillary)?;
+    ///     println!("received {}", size);
+    ///     for ancillary_result in ancillary.messages() {
+    ///         if let AncillaryData::ScmRights(scm_rights) = ancillary_result.unwrap() {
+    ///             for fd in scm_rights {
+    ///                 println!("receive file descriptor: {}", fd);
+    ///             }
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+    pub fn recv_vectored_with_ancillary_from(
+        &self,
+        bufs: &mut [IoSliceMut<'_>],
+        ancillary: &mut SocketAncillary<'_>,
+    ) -> io::Result<(usize, bool, SocketAddr)> {
+        let (count, truncated, addr) =
+            ancillary::recv_vectored_with_ancillary_from(&self.0, bufs, ancillary)?;
+        let addr = addr?;
+
+        Ok((count, truncated, addr))
+    }
+
+    /// Receives data and ancillary data from socket.
+    ///
+    /// On success, returns the number of bytes read and if the data was truncated.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+    pub fn recv_vectored_with_ancillary(
+        &self,
+        bufs: &mut [IoSliceMut<'_>],
+        ancillary: &mut SocketAncillary<'_>,
+    ) -> io::Result<(usize, bool)> {
+        let (count, truncated, addr) =
+            ancillary::recv_vectored_with_ancillary_from(&self.0, bufs, ancillary)?;
+        addr?;
+
+        Ok((count, truncated))
+    }
+
+    /// Sends data and ancillary data on the socket to the specified address.
+    ///
+    /// On success, returns the number of bytes written.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(unix_socket_ancillary_data)]
+    /// use std::os::unix::net::{UnixDatagram, SocketAncillary};
+    /// use std::io::IoSlice;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let sock = UnixDatagram::unbound()?;
+    ///     let buf1 = [1; 8];
+    ///     let buf2 = [2; 16];
+    ///     let buf3 = [3; 8];
+    ///     let bufs = &[
+    ///         IoSlice::new(&buf1),
+    ///         IoSlice::new(&buf2),
+    ///         IoSlice::new(&buf3),
+    ///     ][..];
+    ///     let fds = [0, 1, 2];
+    ///     let mut ancillary_buffer = [0; 128];
+    ///     let mut ancillary = SocketAncillary::new(&mut ancillary_buffer[..]);
+    ///     ancillary.add_fds(&fds[..]);
+    ///     sock.send_vectored_with_ancillary_to(bufs, &mut ancillary, "/some/sock")
+    ///         .expect("send_vectored_with_ancillary_to function failed");
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+    pub fn send_vectored_with_ancillary_to<P: AsRef<Path>>(
+        &self,
+        bufs: &[IoSlice<'_>],
+        ancillary: &mut SocketAncillary<'_>,
+        path: P,
+    ) -> io::Result<usize> {
+        ancillary::send_vectored_with_ancillary_to(&self.0, Some(path.as_ref()), bufs, ancillary)
+    }
+
+    /// Sends data and ancillary data on the socket to the socket's peer.
+    ///
+    /// The peer address may be set by the `connect` method, and this method
+    /// will return an error if the socket has not already been connected.
+    ///
+    /// On success, returns the number of bytes written.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+    pub fn send_vectored_with_ancillary(
+        &self,
+        bufs: &[IoSlice<'_>],
+        ancillary: &mut SocketAncillary<'_>,
+    ) -> io::Result<usize> {
+        ancillary::send_vectored_with_ancillary_to(&self.0, None, bufs, ancillary)
+    }
+
+    /// Moves the socket to pass unix credentials as control message in `SocketAncillary`.
+    ///
+    /// Set the socket option `SO_PASSCRED`.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+    pub fn set_passcred(&self, passcred: bool) -> io::Result<()> {
+        self.0.set_passcred(passcred)
+    }
+
+    /// Get the current value of the socket for passing unix credentials in `SocketAncillary`.
+    /// This value can be change by `set_passcred`.
+    ///
+    /// Get the socket option `SO_PASSCRED`.
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[unstable(feature = "unix_socket_ancillary_data", issue = "0")]
+    pub fn passcred(&self) -> io::Result<bool> {
+        self.0.passcred()
+    }
 }
 
 #[stable(feature = "unix_socket", since = "1.10.0")]
@@ -1485,6 +1779,8 @@ mod test {
     use crate::io::prelude::*;
     use crate::time::Duration;
     use crate::sys_common::io::test::tmpdir;
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    use crate::iter::FromIterator;
 
     use super::*;
 
@@ -1847,4 +2143,129 @@ mod test {
     fn abstract_namespace_not_allowed() {
         assert!(UnixStream::connect("\0asdf").is_err());
     }
+
+    #[test]
+    #[cfg(any(target_os = "android", target_os = "ios", target_os = "linux", target_os = "macos"))]
+    fn test_unix_stream_peer_cred() {
+        let (sock_a, sock_b) = or_panic!(UnixStream::pair());
+
+        let cred_a = or_panic!(sock_a.peer_cred());
+        let cred_b = or_panic!(sock_b.peer_cred());
+        assert_eq!(cred_a, cred_b);
+
+        let uid = unsafe { libc::geteuid() };
+        let gid = unsafe { libc::getegid() };
+        assert_eq!(cred_a.uid, uid);
+        assert_eq!(cred_a.gid, gid);
+        if cfg!(any(target_os = "android", target_os = "linux")) {
+            assert_eq!(cred_a.pid, Some(crate::process::id() as libc::pid_t));
+        }
+    }
+
+    #[test]
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    fn test_send_vectored_fds_unix_stream() {
+        let (s1, s2) = or_panic!(UnixStream::pair());
+
+        let buf1 = [1; 8];
+        let bufs_send = &[IoSlice::new(&buf1[..])][..];
+
+        let mut ancillary1_buffer = [0; 128];
+        let mut ancillary1 = SocketAncillary::new(&mut ancillary1_buffer[..]);
+        assert!(ancillary1.add_fds(&[s1.as_raw_fd()][..]));
+
+        let usize = or_panic!(s1.send_vectored_with_ancillary(&bufs_send, &mut ancillary1));
+        assert_eq!(usize, 8);
+
+        let mut buf2 = [0; 8];
+        let mut bufs_recv = &mut [IoSliceMut::new(&mut buf2[..])][..];
+
+        let mut ancillary2_buffer = [0; 128];
+        let mut ancillary2 = SocketAncillary::new(&mut ancillary2_buffer[..]);
+
+        let usize = or_panic!(s2.recv_vectored_with_ancillary(&mut bufs_recv, &mut ancillary2));
+        assert_eq!(usize, 8);
+        assert_eq!(buf1, buf2);
+        assert!(!ancillary2.truncated());
+
+        let mut ancillary_data_vec = Vec::from_iter(ancillary2.messages());
+        assert_eq!(ancillary_data_vec.len(), 1);
+        if let AncillaryData::ScmRights(scm_rights) = ancillary_data_vec.pop().unwrap().unwrap() {
+            let fd_vec = Vec::from_iter(scm_rights);
+            assert_eq!(fd_vec.len(), 1);
+            unsafe {
+                libc::close(fd_vec[0]);
+            }
+        } else {
+            unreachable!("must be ScmRights");
+        }
+    }
+
+    #[test]
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    fn test_send_vectored_with_ancillary_to_unix_datagram() {
+        fn getpid() -> libc::pid_t {
+            unsafe { libc::getpid() }
+        }
+
+        fn getuid() -> libc::uid_t {
+            unsafe { libc::getuid() }
+        }
+
+        fn getgid() -> libc::gid_t {
+            unsafe { libc::getgid() }
+        }
+
+        let dir = tmpdir();
+        let path1 = dir.path().join("sock1");
+        let path2 = dir.path().join("sock2");
+
+        let bsock1 = or_panic!(UnixDatagram::bind(&path1));
+        let bsock2 = or_panic!(UnixDatagram::bind(&path2));
+
+        or_panic!(bsock2.set_passcred(true));
+        assert!(or_panic!(bsock2.passcred()));
+
+        let buf1 = [1; 8];
+        let bufs_send = &[IoSlice::new(&buf1[..])][..];
+
+        let mut ancillary1_buffer = [0; 128];
+        let mut ancillary1 = SocketAncillary::new(&mut ancillary1_buffer[..]);
+        let mut cred1 = SocketCred::new();
+        cred1.set_pid(getpid());
+        cred1.set_uid(getuid());
+        cred1.set_gid(getgid());
+        assert!(ancillary1.add_creds(&[cred1.clone()][..]));
+
+        let usize =
+            or_panic!(bsock1.send_vectored_with_ancillary_to(&bufs_send, &mut ancillary1, &path2));
+        assert_eq!(usize, 8);
+
+        let mut buf2 = [0; 8];
+        let mut bufs_recv = &mut [IoSliceMut::new(&mut buf2[..])][..];
+
+        let mut ancillary2_buffer = [0; 128];
+        let mut ancillary2 = SocketAncillary::new(&mut ancillary2_buffer[..]);
+
+        let (usize, truncated, _addr) =
+            or_panic!(bsock2.recv_vectored_with_ancillary_from(&mut bufs_recv, &mut ancillary2));
+        assert_eq!(ancillary2.truncated(), false);
+        assert_eq!(usize, 8);
+        assert_eq!(truncated, false);
+        assert_eq!(buf1, buf2);
+
+        let mut ancillary_data_vec = Vec::from_iter(ancillary2.messages());
+        assert_eq!(ancillary_data_vec.len(), 1);
+        if let AncillaryData::ScmCredentials(scm_credentials) =
+            ancillary_data_vec.pop().unwrap().unwrap()
+        {
+            let cred_vec = Vec::from_iter(scm_credentials);
+            assert_eq!(cred_vec.len(), 1);
+            assert_eq!(cred1.get_pid(), cred_vec[0].get_pid());
+            assert_eq!(cred1.get_uid(), cred_vec[0].get_uid());
+            assert_eq!(cred1.get_gid(), cred_vec[0].get_gid());
+        } else {
+            unreachable!("must be ScmCredentials");
+        }
+    }
 }
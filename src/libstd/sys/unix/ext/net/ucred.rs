@@ -0,0 +1,54 @@
+//! Credentials of the process at the other end of a Unix domain socket.
+
+use libc::{gid_t, pid_t, uid_t};
+
+/// Credentials for a UNIX process for credentials passing.
+#[unstable(feature = "peer_credentials_unix_socket", issue = "0")]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct UCred {
+    /// The UID part of the peer credential. This is the effective UID of the process at the domain
+    /// socket's endpoint.
+    pub uid: uid_t,
+    /// The GID part of the peer credential. This is the effective GID of the process at the domain
+    /// socket's endpoint.
+    pub gid: gid_t,
+    /// The PID part of the peer credential. This field is optional because the PID part of the
+    /// peer credentials is not supported on every platform. On platforms where the mechanism to
+    /// discover the PID exists, this field will be populated to the PID of the process at the
+    /// domain socket's endpoint. Otherwise, it will be set to None.
+    pub pid: Option<pid_t>,
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+pub(super) use self::impl_linux::peer_cred;
+
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+pub(super) use self::impl_mac::peer_cred;
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+mod impl_linux {
+    use super::UCred;
+    use crate::io;
+    use crate::os::unix::net::UnixStream;
+    use crate::sys_common::net::getsockopt;
+
+    pub fn peer_cred(socket: &UnixStream) -> io::Result<UCred> {
+        let ucred: libc::ucred = getsockopt(&socket.0, libc::SOL_SOCKET, libc::SO_PEERCRED)?;
+        Ok(UCred { uid: ucred.uid, gid: ucred.gid, pid: Some(ucred.pid) })
+    }
+}
+
+#[cfg(any(target_os = "ios", target_os = "macos"))]
+mod impl_mac {
+    use super::UCred;
+    use crate::io;
+    use crate::os::unix::io::AsRawFd;
+    use crate::os::unix::net::UnixStream;
+    use crate::sys::cvt;
+
+    pub fn peer_cred(socket: &UnixStream) -> io::Result<UCred> {
+        let mut cred = UCred { uid: 1, gid: 1, pid: None };
+        cvt(unsafe { libc::getpeereid(socket.as_raw_fd(), &mut cred.uid, &mut cred.gid) })?;
+        Ok(cred)
+    }
+}
@@ -103,6 +103,61 @@ pub trait CommandExt {
     /// cross-platform `spawn` instead.
     #[stable(feature = "process_exec2", since = "1.9.0")]
     fn exec(&mut self) -> io::Error;
+
+    /// Sets the process group ID of the child process. This translates to a
+    /// `setpgid` call in the child process, or to `POSIX_SPAWN_SETPGROUP`
+    /// when the process is started with `posix_spawn`.
+    ///
+    /// A `pgroup` of `0` makes the child the leader of a new process group
+    /// whose ID is the child's PID. Failure to change the process group will
+    /// cause the spawn to fail.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(process_set_process_group)]
+    ///
+    /// use std::process::Command;
+    /// use std::os::unix::process::CommandExt;
+    ///
+    /// Command::new("sleep")
+    ///     .arg("10")
+    ///     .process_group(0)
+    ///     .spawn()
+    ///     .unwrap();
+    /// ```
+    #[unstable(feature = "process_set_process_group", issue = "0")]
+    fn process_group(&mut self, pgroup: i32) -> &mut process::Command;
+
+    /// Makes the child the leader of a new session by calling `setsid` in
+    /// the child process, or through `POSIX_SPAWN_SETSID` when the platform's
+    /// `posix_spawn` supports it. The child then has no controlling terminal.
+    ///
+    /// Setting both `setsid` and a [`process_group`] will cause the spawn to
+    /// fail, since a session leader can't change its process group.
+    ///
+    /// [`process_group`]: #tymethod.process_group
+    #[unstable(feature = "process_setsid", issue = "0")]
+    fn setsid(&mut self, setsid: bool) -> &mut process::Command;
+
+    /// Sets the signal mask of the child process.
+    ///
+    /// By default the child starts with an empty signal mask, regardless of
+    /// the mask of the thread that spawned it. The given signals are blocked
+    /// in the child instead. Invalid signal numbers will cause the spawn to
+    /// fail.
+    #[unstable(feature = "process_signal_control", issue = "0")]
+    fn signal_mask(&mut self, signals: &[i32]) -> &mut process::Command;
+
+    /// Resets the disposition of the given signals to `SIG_DFL` in the child
+    /// process.
+    ///
+    /// Ignored signals are inherited across `exec`, so this is useful when
+    /// the parent ignores signals the child should react to. `SIGPIPE`, which
+    /// the standard library ignores, is always reset. Signals that can't be
+    /// reset, such as `SIGKILL`, will cause the spawn to fail.
+    #[unstable(feature = "process_signal_control", issue = "0")]
+    fn default_signals(&mut self, signals: &[i32]) -> &mut process::Command;
 }
 
 #[stable(feature = "rust1", since = "1.0.0")]
@@ -127,6 +182,63 @@ impl CommandExt for process::Command {
     fn exec(&mut self) -> io::Error {
         self.as_inner_mut().exec(sys::process::Stdio::Inherit)
     }
+
+    fn process_group(&mut self, pgroup: i32) -> &mut process::Command {
+        self.as_inner_mut().pgroup(pgroup);
+        self
+    }
+
+    fn setsid(&mut self, setsid: bool) -> &mut process::Command {
+        self.as_inner_mut().setsid(setsid);
+        self
+    }
+
+    fn signal_mask(&mut self, signals: &[i32]) -> &mut process::Command {
+        self.as_inner_mut().signal_mask(signals);
+        self
+    }
+
+    fn default_signals(&mut self, signals: &[i32]) -> &mut process::Command {
+        self.as_inner_mut().default_signals(signals);
+        self
+    }
+}
+
+/// Unix-specific extensions to [`process::Child`].
+///
+/// [`process::Child`]: ../../../../std/process/struct.Child.html
+#[unstable(feature = "unix_send_signal", issue = "0")]
+pub trait ChildExt {
+    /// Sends a signal to the child process. This translates to a `kill`
+    /// call with the child's PID.
+    ///
+    /// Like [`Child::kill`], this returns an [`InvalidInput`] error if the
+    /// child has already been waited on, since its PID may have been reused.
+    ///
+    /// [`Child::kill`]: ../../../../std/process/struct.Child.html#method.kill
+    /// [`InvalidInput`]: ../../../../std/io/enum.ErrorKind.html#variant.InvalidInput
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(unix_send_signal)]
+    ///
+    /// use std::process::Command;
+    /// use std::os::unix::process::ChildExt;
+    ///
+    /// let mut child = Command::new("sleep").arg("10").spawn().unwrap();
+    /// child.send_signal(15).expect("couldn't send SIGTERM");
+    /// child.wait().unwrap();
+    /// ```
+    #[unstable(feature = "unix_send_signal", issue = "0")]
+    fn send_signal(&self, signal: i32) -> io::Result<()>;
+}
+
+#[unstable(feature = "unix_send_signal", issue = "0")]
+impl ChildExt for process::Child {
+    fn send_signal(&self, signal: i32) -> io::Result<()> {
+        self.as_inner().send_signal(signal)
+    }
 }
 
 /// Unix-specific extensions to [`process::ExitStatus`].
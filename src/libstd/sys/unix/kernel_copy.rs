@@ -0,0 +1,541 @@
+//! This module contains specializations that can offload `io::copy()` operations on file
+//! descriptor containing types (`File`, `TcpStream`, etc.) to more efficient syscalls than
+//! `read(2)` and `write(2)`.
+//!
+//! Specialization is only applied to wholly std-owned types so that user code can't observe
+//! that the `Read` and `Write` traits are not used.
+//!
+//! Since a copy operation involves a reader and writer side where each can consist of different
+//! types and also involve generic wrappers (e.g. `&File`) we need a specialization strategy
+//! that works for both sides at once. This is done by wrapping the reader and writer in a
+//! `Copier` and specializing `SpecCopy` on it when both sides implement the private
+//! `CopyRead` and `CopyWrite` traits, which expose the underlying file descriptor and what
+//! kind of object it refers to.
+//!
+//! Once both sides have been resolved the following syscalls are tried, in order:
+//!
+//! * `copy_file_range(2)` if both sides are regular files. This enables in-kernel copies
+//!   and, on some filesystems, reflinks.
+//! * `sendfile(2)` if the reader is a regular file or a block device. Since Linux 2.6.33
+//!   the writer can be any file descriptor, before that it had to be a socket.
+//! * `splice(2)` if either side is a pipe.
+//!
+//! Each of them may be unavailable (old kernels, seccomp filters) or refuse a particular
+//! combination of file descriptors. In those cases we fall back to the next candidate and
+//! finally to the generic read/write loop, continuing from wherever the previous attempt
+//! left off.
+
+use crate::fs::{File, Metadata};
+use crate::io::{self, generic_copy, Read, Write};
+use crate::net::TcpStream;
+use crate::os::unix::fs::FileTypeExt;
+use crate::os::unix::io::{AsRawFd, RawFd};
+use crate::os::unix::net::UnixStream;
+use crate::process::{ChildStderr, ChildStdin, ChildStdout};
+use crate::ptr;
+use crate::sync::atomic::{AtomicBool, Ordering};
+use crate::sys::cvt;
+
+pub(crate) fn copy_spec<R: Read + ?Sized, W: Write + ?Sized>(
+    read: &mut R,
+    write: &mut W,
+) -> io::Result<u64> {
+    let copier = Copier { read, write };
+    SpecCopy::copy(copier)
+}
+
+/// This type represents either the inferred `FileType` of a `RawFd` based on the source
+/// type from which it was extracted or the actual metadata.
+enum FdMeta {
+    Metadata(Metadata),
+    Socket,
+    Pipe,
+    /// We don't have any metadata, e.g. because the original type was `File` which can
+    /// represent anything, but the caller did not request the metadata or it could not
+    /// be obtained.
+    NoneObtained,
+}
+
+impl FdMeta {
+    fn maybe_fifo(&self) -> bool {
+        match self {
+            FdMeta::Metadata(meta) => meta.file_type().is_fifo(),
+            FdMeta::Socket => false,
+            FdMeta::Pipe => true,
+            FdMeta::NoneObtained => true,
+        }
+    }
+
+    fn potential_sendfile_source(&self) -> bool {
+        match self {
+            // procfs erroneously shows 0 length on non-empty readable files.
+            // and if a file is truly empty then a `read` syscall will determine that and skip the write syscall
+            // thus there would be no benefit from attempting sendfile
+            FdMeta::Metadata(meta)
+                if meta.file_type().is_file() && meta.len() > 0
+                    || meta.file_type().is_block_device() => true,
+            _ => false,
+        }
+    }
+
+    fn copy_file_range_candidate(&self, f: FdHandle) -> bool {
+        match self {
+            // copy_file_range will fail on empty procfs files. `read` can determine whether EOF has been reached
+            // without extra cost and skip the write, thus there is no benefit in attempting copy_file_range
+            FdMeta::Metadata(meta) if f == FdHandle::Input && meta.is_file() && meta.len() > 0 => {
+                true
+            }
+            FdMeta::Metadata(meta) if f == FdHandle::Output && meta.is_file() => true,
+            FdMeta::NoneObtained => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(PartialEq)]
+enum FdHandle {
+    Input,
+    Output,
+}
+
+struct CopyParams(FdMeta, Option<RawFd>);
+
+struct Copier<'a, 'b, R: Read + ?Sized, W: Write + ?Sized> {
+    read: &'a mut R,
+    write: &'b mut W,
+}
+
+trait SpecCopy {
+    fn copy(self) -> io::Result<u64>;
+}
+
+impl<R: Read + ?Sized, W: Write + ?Sized> SpecCopy for Copier<'_, '_, R, W> {
+    default fn copy(self) -> io::Result<u64> {
+        generic_copy(self.read, self.write)
+    }
+}
+
+impl<R: CopyRead, W: CopyWrite> SpecCopy for Copier<'_, '_, R, W> {
+    fn copy(self) -> io::Result<u64> {
+        let (reader, writer) = (self.read, self.write);
+        let r_cfg = reader.properties();
+        let w_cfg = writer.properties();
+
+        let mut written = 0u64;
+        if let (CopyParams(input_meta, Some(readfd)), CopyParams(output_meta, Some(writefd))) =
+            (r_cfg, w_cfg)
+        {
+            if input_meta.copy_file_range_candidate(FdHandle::Input)
+                && output_meta.copy_file_range_candidate(FdHandle::Output)
+            {
+                match copy_regular_files(readfd, writefd) {
+                    CopyResult::Ended(result) => return result,
+                    CopyResult::Fallback(bytes) => written += bytes,
+                }
+            }
+
+            // On modern kernels sendfile can copy from any mmapable type (some but not all
+            // regular files and block devices) to any writable file descriptor. On older
+            // kernels the writer side can only be a socket. So we just try and fall back
+            // if needed.
+            if input_meta.potential_sendfile_source() {
+                match sendfile_splice(SpliceMode::Sendfile, readfd, writefd) {
+                    CopyResult::Ended(result) => return result.map(|bytes| bytes + written),
+                    CopyResult::Fallback(bytes) => written += bytes,
+                }
+            }
+
+            if input_meta.maybe_fifo() || output_meta.maybe_fifo() {
+                match sendfile_splice(SpliceMode::Splice, readfd, writefd) {
+                    CopyResult::Ended(result) => return result.map(|bytes| bytes + written),
+                    CopyResult::Fallback(bytes) => written += bytes,
+                }
+            }
+        }
+
+        generic_copy(reader, writer).map(|bytes| bytes + written)
+    }
+}
+
+/// Types whose file descriptor `copy_spec` may read from directly.
+///
+/// Only implement this for types which don't buffer internally, otherwise
+/// data held in the buffer would be skipped.
+trait CopyRead: Read {
+    /// Extracts the file descriptor and hints/metadata, delegating through wrappers if necessary.
+    fn properties(&self) -> CopyParams;
+}
+
+/// Types whose file descriptor `copy_spec` may write to directly.
+///
+/// Only implement this for types which don't buffer internally, otherwise
+/// the order of the written data could be changed.
+trait CopyWrite: Write {
+    /// Extracts the file descriptor and hints/metadata, delegating through wrappers if necessary.
+    fn properties(&self) -> CopyParams;
+}
+
+impl CopyRead for File {
+    fn properties(&self) -> CopyParams {
+        CopyParams(fd_to_meta(self), Some(self.as_raw_fd()))
+    }
+}
+
+impl CopyRead for &File {
+    fn properties(&self) -> CopyParams {
+        CopyParams(fd_to_meta(*self), Some(self.as_raw_fd()))
+    }
+}
+
+impl CopyWrite for File {
+    fn properties(&self) -> CopyParams {
+        CopyParams(fd_to_meta(self), Some(self.as_raw_fd()))
+    }
+}
+
+impl CopyWrite for &File {
+    fn properties(&self) -> CopyParams {
+        CopyParams(fd_to_meta(*self), Some(self.as_raw_fd()))
+    }
+}
+
+impl CopyRead for TcpStream {
+    fn properties(&self) -> CopyParams {
+        // avoid the stat syscall since we can be fairly sure it's a socket
+        CopyParams(FdMeta::Socket, Some(self.as_raw_fd()))
+    }
+}
+
+impl CopyRead for &TcpStream {
+    fn properties(&self) -> CopyParams {
+        // avoid the stat syscall since we can be fairly sure it's a socket
+        CopyParams(FdMeta::Socket, Some(self.as_raw_fd()))
+    }
+}
+
+impl CopyWrite for TcpStream {
+    fn properties(&self) -> CopyParams {
+        // avoid the stat syscall since we can be fairly sure it's a socket
+        CopyParams(FdMeta::Socket, Some(self.as_raw_fd()))
+    }
+}
+
+impl CopyWrite for &TcpStream {
+    fn properties(&self) -> CopyParams {
+        // avoid the stat syscall since we can be fairly sure it's a socket
+        CopyParams(FdMeta::Socket, Some(self.as_raw_fd()))
+    }
+}
+
+impl CopyRead for UnixStream {
+    fn properties(&self) -> CopyParams {
+        CopyParams(FdMeta::Socket, Some(self.as_raw_fd()))
+    }
+}
+
+impl CopyRead for &UnixStream {
+    fn properties(&self) -> CopyParams {
+        CopyParams(FdMeta::Socket, Some(self.as_raw_fd()))
+    }
+}
+
+impl CopyWrite for UnixStream {
+    fn properties(&self) -> CopyParams {
+        CopyParams(FdMeta::Socket, Some(self.as_raw_fd()))
+    }
+}
+
+impl CopyWrite for &UnixStream {
+    fn properties(&self) -> CopyParams {
+        CopyParams(FdMeta::Socket, Some(self.as_raw_fd()))
+    }
+}
+
+impl CopyWrite for ChildStdin {
+    fn properties(&self) -> CopyParams {
+        CopyParams(FdMeta::Pipe, Some(self.as_raw_fd()))
+    }
+}
+
+impl CopyRead for ChildStdout {
+    fn properties(&self) -> CopyParams {
+        CopyParams(FdMeta::Pipe, Some(self.as_raw_fd()))
+    }
+}
+
+impl CopyRead for ChildStderr {
+    fn properties(&self) -> CopyParams {
+        CopyParams(FdMeta::Pipe, Some(self.as_raw_fd()))
+    }
+}
+
+fn fd_to_meta(file: &File) -> FdMeta {
+    match file.metadata() {
+        Ok(meta) => FdMeta::Metadata(meta),
+        Err(_) => FdMeta::NoneObtained,
+    }
+}
+
+enum CopyResult {
+    /// The copy finished, either successfully or with an error that the
+    /// generic fallback would hit as well.
+    Ended(io::Result<u64>),
+    /// The syscall is unavailable or does not support this pair of file
+    /// descriptors; the given number of bytes has already been copied.
+    Fallback(u64),
+}
+
+/// Linux-specific implementation that will attempt to use copy_file_range for copy offloading.
+/// As the name says, it only works on regular files.
+///
+/// Callers must handle fallback to a generic copy loop.
+/// `Fallback` may indicate non-zero number of bytes already written
+/// if one of the files' cursor would exceed u64::MAX (`EOVERFLOW`).
+fn copy_regular_files(reader: RawFd, writer: RawFd) -> CopyResult {
+    // Kernel prior to 4.5 don't have copy_file_range
+    // We store the availability in a global to avoid unnecessary syscalls
+    static HAS_COPY_FILE_RANGE: AtomicBool = AtomicBool::new(true);
+
+    syscall! {
+        fn copy_file_range(
+            fd_in: libc::c_int,
+            off_in: *mut libc::loff_t,
+            fd_out: libc::c_int,
+            off_out: *mut libc::loff_t,
+            len: libc::size_t,
+            flags: libc::c_uint
+        ) -> libc::ssize_t
+    }
+
+    if !HAS_COPY_FILE_RANGE.load(Ordering::Relaxed) {
+        return CopyResult::Fallback(0);
+    }
+
+    let mut written = 0u64;
+    loop {
+        // The kernel caps a single call at a bit under 2 GiB anyway, copy in
+        // 1 GiB chunks so the length also fits `size_t` on 32-bit targets.
+        let bytes_to_copy = 0x4000_0000usize;
+        let copy_result = unsafe {
+            // We actually don't have to adjust the offsets,
+            // because copy_file_range adjusts the file offset automatically
+            cvt(copy_file_range(
+                reader,
+                ptr::null_mut(),
+                writer,
+                ptr::null_mut(),
+                bytes_to_copy,
+                0,
+            ))
+        };
+
+        match copy_result {
+            Ok(0) if written == 0 => {
+                // fallback to work around several kernel bugs where copy_file_range will fail to
+                // copy any bytes and return 0 instead of an error if
+                // - reading virtual files from the proc filesystem which appear to have 0 size
+                //   but are not empty. noted in coreutils to affect kernels at least up to 5.6.19.
+                // - copying from an overlay filesystem in docker. reported to occur on fedora 32.
+                return CopyResult::Fallback(0);
+            }
+            Ok(0) => return CopyResult::Ended(Ok(written)), // reached EOF
+            Ok(ret) => written += ret as u64,
+            Err(ref err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => {
+                return match err.raw_os_error() {
+                    // when file offset + max_length > u64::MAX
+                    Some(libc::EOVERFLOW) => CopyResult::Fallback(written),
+                    Some(libc::ENOSYS) | Some(libc::EPERM) => {
+                        // Try fallback io::copy if either:
+                        // - Kernel version is < 4.5 (ENOSYS)
+                        // - copy_file_range is disallowed, for example by seccomp (EPERM)
+                        assert_eq!(written, 0);
+                        HAS_COPY_FILE_RANGE.store(false, Ordering::Relaxed);
+                        CopyResult::Fallback(0)
+                    }
+                    Some(libc::EXDEV) | Some(libc::EINVAL) | Some(libc::EOPNOTSUPP)
+                        | Some(libc::EBADF) if written == 0 =>
+                    {
+                        // Try fallback io::copy if either:
+                        // - Files are mounted on different fs on kernels < 5.3 (EXDEV)
+                        // - copy_file_range cannot be used with pipes or device nodes (EINVAL)
+                        // - the filesystem does not support it (EOPNOTSUPP)
+                        // - the writer was opened with O_APPEND (EBADF)
+                        CopyResult::Fallback(0)
+                    }
+                    _ => CopyResult::Ended(Err(err)),
+                };
+            }
+        }
+    }
+}
+
+#[derive(PartialEq)]
+enum SpliceMode {
+    Sendfile,
+    Splice,
+}
+
+/// Performs splice or sendfile between file descriptors.
+/// Does _not_ fall back to a generic copy loop.
+fn sendfile_splice(mode: SpliceMode, reader: RawFd, writer: RawFd) -> CopyResult {
+    static HAS_SENDFILE: AtomicBool = AtomicBool::new(true);
+    static HAS_SPLICE: AtomicBool = AtomicBool::new(true);
+
+    syscall! {
+        fn splice(
+            srcfd: libc::c_int,
+            src_offset: *const i64,
+            dstfd: libc::c_int,
+            dst_offset: *const i64,
+            len: libc::size_t,
+            flags: libc::c_int
+        ) -> libc::ssize_t
+    }
+
+    match mode {
+        SpliceMode::Sendfile if !HAS_SENDFILE.load(Ordering::Relaxed) => {
+            return CopyResult::Fallback(0);
+        }
+        SpliceMode::Splice if !HAS_SPLICE.load(Ordering::Relaxed) => {
+            return CopyResult::Fallback(0);
+        }
+        _ => (),
+    }
+
+    let mut written = 0u64;
+    loop {
+        // Both syscalls transfer at most 0x7ffff000 bytes per call.
+        let chunk_size = 0x7fff_f000usize;
+
+        let result = match mode {
+            SpliceMode::Sendfile => {
+                cvt(unsafe { libc::sendfile(writer, reader, ptr::null_mut(), chunk_size) })
+            }
+            SpliceMode::Splice => {
+                cvt(unsafe { splice(reader, ptr::null_mut(), writer, ptr::null_mut(), chunk_size, 0) })
+            }
+        };
+
+        match result {
+            Ok(0) => break, // EOF
+            Ok(ret) => written += ret as u64,
+            Err(ref err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => {
+                return match err.raw_os_error() {
+                    Some(libc::ENOSYS) | Some(libc::EPERM) => {
+                        // syscall not supported (ENOSYS)
+                        // syscall is disallowed, e.g. by seccomp (EPERM)
+                        match mode {
+                            SpliceMode::Sendfile => HAS_SENDFILE.store(false, Ordering::Relaxed),
+                            SpliceMode::Splice => HAS_SPLICE.store(false, Ordering::Relaxed),
+                        }
+                        assert_eq!(written, 0);
+                        CopyResult::Fallback(0)
+                    }
+                    Some(libc::EINVAL) => {
+                        // splice/sendfile do not support this particular file descriptor (EINVAL)
+                        assert_eq!(written, 0);
+                        CopyResult::Fallback(0)
+                    }
+                    Some(libc::EOVERFLOW) => CopyResult::Fallback(written),
+                    _ => CopyResult::Ended(Err(err)),
+                };
+            }
+        }
+    }
+    CopyResult::Ended(Ok(written))
+}
+
+#[cfg(all(test, not(target_os = "emscripten")))]
+mod tests {
+    use crate::fs::{self, File, OpenOptions};
+    use crate::io::{self, Read, Write};
+    use crate::net::{TcpListener, TcpStream};
+    use crate::sys_common::io::test::tmpdir;
+    use crate::thread;
+
+    #[test]
+    fn copy_specialization() {
+        let tmp = tmpdir();
+        let source_path = tmp.join("copy-spec.source");
+        let sink_path = tmp.join("copy-spec.sink");
+
+        let content = (0..16 * 1024u32).map(|i| i as u8).collect::<Vec<u8>>();
+        fs::write(&source_path, &content).unwrap();
+
+        let mut source = File::open(&source_path).unwrap();
+        let mut sink = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .open(&sink_path)
+            .unwrap();
+
+        let copied = io::copy(&mut source, &mut sink).unwrap();
+        assert_eq!(copied, content.len() as u64);
+        assert_eq!(fs::read(&sink_path).unwrap(), content);
+    }
+
+    #[test]
+    fn copy_specialization_resumes_from_offset() {
+        let tmp = tmpdir();
+        let source_path = tmp.join("copy-spec-offset.source");
+        let sink_path = tmp.join("copy-spec-offset.sink");
+
+        fs::write(&source_path, b"0123456789").unwrap();
+
+        let mut source = File::open(&source_path).unwrap();
+        let mut skipped = [0u8; 4];
+        source.read_exact(&mut skipped).unwrap();
+
+        let mut sink = File::create(&sink_path).unwrap();
+        sink.write_all(b"ab").unwrap();
+
+        let copied = io::copy(&mut source, &mut sink).unwrap();
+        assert_eq!(copied, 6);
+        assert_eq!(fs::read(&sink_path).unwrap(), b"ab456789");
+    }
+
+    #[test]
+    fn copy_specialization_append_falls_back() {
+        let tmp = tmpdir();
+        let source_path = tmp.join("copy-spec-append.source");
+        let sink_path = tmp.join("copy-spec-append.sink");
+
+        fs::write(&source_path, b"world").unwrap();
+        fs::write(&sink_path, b"hello ").unwrap();
+
+        let mut source = File::open(&source_path).unwrap();
+        let mut sink = OpenOptions::new().append(true).open(&sink_path).unwrap();
+
+        let copied = io::copy(&mut source, &mut sink).unwrap();
+        assert_eq!(copied, 5);
+        assert_eq!(fs::read(&sink_path).unwrap(), b"hello world");
+    }
+
+    #[test]
+    fn copy_file_to_socket() {
+        let tmp = tmpdir();
+        let source_path = tmp.join("copy-spec-socket.source");
+
+        let content = (0..64 * 1024u32).map(|i| i as u8).collect::<Vec<u8>>();
+        fs::write(&source_path, &content).unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let receiver = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut received = Vec::new();
+            stream.read_to_end(&mut received).unwrap();
+            received
+        });
+
+        let mut source = File::open(&source_path).unwrap();
+        let mut sink = TcpStream::connect(addr).unwrap();
+        let copied = io::copy(&mut source, &mut sink).unwrap();
+        drop(sink);
+
+        assert_eq!(copied, content.len() as u64);
+        assert_eq!(receiver.join().unwrap(), content);
+    }
+}
@@ -36,6 +36,8 @@ pub mod fd;
 pub mod fs;
 pub mod memchr;
 pub mod io;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub mod kernel_copy;
 pub mod mutex;
 #[cfg(not(target_os = "l4re"))]
 pub mod net;
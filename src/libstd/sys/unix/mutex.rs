@@ -1,5 +1,6 @@
 use crate::cell::UnsafeCell;
-use crate::mem::MaybeUninit;
+use crate::mem::{self, MaybeUninit};
+use crate::time::Duration;
 
 pub struct Mutex { inner: UnsafeCell<libc::pthread_mutex_t> }
 
@@ -65,6 +66,20 @@ impl Mutex {
         libc::pthread_mutex_trylock(self.inner.get()) == 0
     }
     #[inline]
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub unsafe fn try_lock_for(&self, dur: Duration) -> bool {
+        let timeout = realtime_deadline(dur);
+        let r = libc::pthread_mutex_timedlock(self.inner.get(), &timeout);
+        debug_assert!(r == 0 || r == libc::ETIMEDOUT);
+        r == 0
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    pub unsafe fn try_lock_for(&self, dur: Duration) -> bool {
+        // `pthread_mutex_timedlock` is missing on macOS, and not reliably
+        // available on the other unixes we support.
+        crate::sys_common::mutex::try_lock_polling(dur, || self.try_lock())
+    }
+    #[inline]
     #[cfg(not(target_os = "dragonfly"))]
     pub unsafe fn destroy(&self) {
         let r = libc::pthread_mutex_destroy(self.inner.get());
@@ -82,6 +97,32 @@ impl Mutex {
     }
 }
 
+/// Converts a timeout into the absolute `CLOCK_REALTIME` deadline expected by
+/// the `pthread_*_timed*lock` functions, saturating on overflow.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn realtime_deadline(dur: Duration) -> libc::timespec {
+    let mut now: libc::timespec = unsafe { mem::zeroed() };
+    let r = unsafe { libc::clock_gettime(libc::CLOCK_REALTIME, &mut now) };
+    assert_eq!(r, 0);
+
+    // Nanosecond calculations can't overflow because both values are below 1e9.
+    let nsec = dur.subsec_nanos() + now.tv_nsec as u32;
+
+    let sec = if dur.as_secs() > libc::time_t::max_value() as u64 {
+        None
+    } else {
+        Some(dur.as_secs() as libc::time_t)
+    };
+    let sec = sec
+        .and_then(|s| s.checked_add((nsec / 1_000_000_000) as libc::time_t))
+        .and_then(|s| s.checked_add(now.tv_sec));
+
+    match sec {
+        Some(s) => libc::timespec { tv_sec: s, tv_nsec: (nsec % 1_000_000_000) as _ },
+        None => libc::timespec { tv_sec: libc::time_t::max_value(), tv_nsec: 1_000_000_000 - 1 },
+    }
+}
+
 pub struct ReentrantMutex { inner: UnsafeCell<libc::pthread_mutex_t> }
 
 unsafe impl Send for ReentrantMutex {}
@@ -10,7 +10,7 @@ use crate::sys::pipe::{self, AnonPipe};
 use crate::sys_common::process::{CommandEnv, DefaultEnvKey};
 use crate::collections::BTreeMap;
 
-use libc::{c_int, gid_t, uid_t, c_char, EXIT_SUCCESS, EXIT_FAILURE};
+use libc::{c_int, gid_t, pid_t, uid_t, c_char, EXIT_SUCCESS, EXIT_FAILURE};
 
 ////////////////////////////////////////////////////////////////////////////////
 // Command
@@ -42,6 +42,13 @@ pub struct Command {
     cwd: Option<CString>,
     uid: Option<uid_t>,
     gid: Option<gid_t>,
+    pgroup: Option<pid_t>,
+    setsid: bool,
+    // Signals blocked in the child and signals whose disposition is reset to
+    // `SIG_DFL` in addition to `SIGPIPE`. Both are applied through
+    // `posix_spawnattr_t` when `posix_spawn` is used.
+    blocked_signals: Vec<c_int>,
+    default_signals: Vec<c_int>,
     saw_nul: bool,
     closures: Vec<Box<dyn FnMut() -> io::Result<()> + Send + Sync>>,
     stdin: Option<Stdio>,
@@ -96,6 +103,10 @@ impl Command {
             cwd: None,
             uid: None,
             gid: None,
+            pgroup: None,
+            setsid: false,
+            blocked_signals: Vec::new(),
+            default_signals: Vec::new(),
             saw_nul,
             closures: Vec::new(),
             stdin: None,
@@ -125,6 +136,18 @@ impl Command {
     pub fn gid(&mut self, id: gid_t) {
         self.gid = Some(id);
     }
+    pub fn pgroup(&mut self, pgroup: pid_t) {
+        self.pgroup = Some(pgroup);
+    }
+    pub fn setsid(&mut self, setsid: bool) {
+        self.setsid = setsid;
+    }
+    pub fn signal_mask(&mut self, signals: &[c_int]) {
+        self.blocked_signals = signals.to_vec();
+    }
+    pub fn default_signals(&mut self, signals: &[c_int]) {
+        self.default_signals = signals.to_vec();
+    }
 
     pub fn saw_nul(&self) -> bool {
         self.saw_nul
@@ -145,6 +168,22 @@ impl Command {
     pub fn get_gid(&self) -> Option<gid_t> {
         self.gid
     }
+    #[allow(dead_code)]
+    pub fn get_pgroup(&self) -> Option<pid_t> {
+        self.pgroup
+    }
+    #[allow(dead_code)]
+    pub fn get_setsid(&self) -> bool {
+        self.setsid
+    }
+    #[allow(dead_code)]
+    pub fn get_blocked_signals(&self) -> &[c_int] {
+        &self.blocked_signals
+    }
+    #[allow(dead_code)]
+    pub fn get_default_signals(&self) -> &[c_int] {
+        &self.default_signals
+    }
 
     pub fn get_closures(&mut self) -> &mut Vec<Box<dyn FnMut() -> io::Result<()> + Send + Sync>> {
         &mut self.closures
@@ -410,7 +449,8 @@ mod tests {
     use crate::ffi::OsStr;
     use crate::mem;
     use crate::ptr;
-    use crate::sys::cvt;
+    use crate::sys::{self, cvt};
+    use crate::sys::process::Process;
 
     macro_rules! t {
         ($e:expr) => {
@@ -499,4 +539,94 @@ mod tests {
             t!(cat.wait());
         }
     }
+
+    #[test]
+    fn test_process_group_and_send_signal() {
+        // A `cat` waiting on its stdin stays alive until it is signalled.
+        let mut cmd = Command::new(OsStr::new("cat"));
+        cmd.pgroup(0);
+        cmd.stdin(Stdio::MakePipe);
+        cmd.stdout(Stdio::Null);
+
+        let (mut cat, mut pipes) = t!(cmd.spawn(Stdio::Null, true));
+        let _stdin_write = pipes.stdin.take().unwrap();
+
+        let pid = cat.id() as libc::pid_t;
+        assert_eq!(unsafe { libc::getpgid(pid) }, pid);
+
+        t!(cat.send_signal(libc::SIGTERM));
+        let status = t!(cat.wait());
+        assert_eq!(status.signal(), Some(libc::SIGTERM));
+
+        // Once reaped, the PID may be reused and can no longer be signalled.
+        assert!(cat.send_signal(libc::SIGTERM).is_err());
+    }
+
+    // The tests below run each command twice: once as is, which uses
+    // `posix_spawn` where it is available, and once with a no-op `pre_exec`
+    // closure, which forces the fork/exec path.
+    fn spawn_cat(mut cmd: Command, fork_exec: bool) -> (Process, AnonPipe) {
+        if fork_exec {
+            unsafe { cmd.pre_exec(Box::new(|| Ok(()))) };
+        }
+        cmd.stdin(Stdio::MakePipe);
+        cmd.stdout(Stdio::Null);
+
+        let (cat, mut pipes) = t!(cmd.spawn(Stdio::Null, true));
+        (cat, pipes.stdin.take().unwrap())
+    }
+
+    #[test]
+    fn test_setsid() {
+        for &fork_exec in &[false, true] {
+            let mut cmd = Command::new(OsStr::new("cat"));
+            cmd.setsid(true);
+            let (mut cat, stdin_write) = spawn_cat(cmd, fork_exec);
+
+            let pid = cat.id() as libc::pid_t;
+            assert_eq!(unsafe { libc::getsid(pid) }, pid);
+            assert_ne!(unsafe { libc::getsid(0) }, pid);
+
+            drop(stdin_write);
+            assert!(t!(cat.wait()).success());
+        }
+    }
+
+    #[test]
+    #[cfg_attr(target_os = "macos", ignore)]
+    #[cfg_attr(target_arch = "arm", ignore)]
+    #[cfg_attr(target_arch = "aarch64", ignore)]
+    fn test_signal_mask() {
+        for &fork_exec in &[false, true] {
+            let mut cmd = Command::new(OsStr::new("cat"));
+            cmd.signal_mask(&[libc::SIGUSR2]);
+            let (mut cat, _stdin_write) = spawn_cat(cmd, fork_exec);
+
+            // SIGUSR2 stays pending in the child, so the SIGTERM sent after
+            // it is the one that terminates `cat`.
+            t!(cat.send_signal(libc::SIGUSR2));
+            t!(cat.send_signal(libc::SIGTERM));
+            assert_eq!(t!(cat.wait()).signal(), Some(libc::SIGTERM));
+        }
+    }
+
+    #[test]
+    #[cfg_attr(target_os = "macos", ignore)]
+    #[cfg_attr(target_arch = "arm", ignore)]
+    #[cfg_attr(target_arch = "aarch64", ignore)]
+    fn test_default_signals() {
+        for &fork_exec in &[false, true] {
+            // Ignored signals are inherited, so ignore SIGUSR2 while spawning
+            // and check that the child still dies from it.
+            let mut cmd = Command::new(OsStr::new("cat"));
+            cmd.default_signals(&[libc::SIGUSR2]);
+            let old = unsafe { sys::signal(libc::SIGUSR2, libc::SIG_IGN) };
+            assert!(old != libc::SIG_ERR);
+            let (mut cat, _stdin_write) = spawn_cat(cmd, fork_exec);
+            unsafe { sys::signal(libc::SIGUSR2, old) };
+
+            t!(cat.send_signal(libc::SIGUSR2));
+            assert_eq!(t!(cat.wait()).signal(), Some(libc::SIGUSR2));
+        }
+    }
 }
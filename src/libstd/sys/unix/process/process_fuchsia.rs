@@ -21,6 +21,13 @@ impl Command {
                                       "nul byte found in provided data"));
         }
 
+        if self.get_pgroup().is_some() || self.get_setsid() ||
+            !self.get_blocked_signals().is_empty() || !self.get_default_signals().is_empty() {
+            return Err(io::Error::new(io::ErrorKind::Other,
+                                      "process groups, sessions and signals are not \
+                                       supported on Fuchsia"));
+        }
+
         let (ours, theirs) = self.setup_io(default, needs_stdin)?;
 
         let process_handle = unsafe { self.do_exec(theirs, envp.as_ref())? };
@@ -117,6 +124,10 @@ impl Process {
         Ok(())
     }
 
+    pub fn send_signal(&self, _signal: i32) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::Other, "signals are not supported on Fuchsia"))
+    }
+
     pub fn wait(&mut self) -> io::Result<ExitStatus> {
         use crate::default::Default;
         use crate::sys::process::zircon::*;
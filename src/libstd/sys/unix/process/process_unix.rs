@@ -202,7 +202,7 @@ impl Command {
         // emscripten has no signal support.
         #[cfg(not(any(target_os = "emscripten")))]
         {
-            use crate::mem::MaybeUninit;
+            use crate::mem::{self, MaybeUninit};
             // Reset signal handling so the child process starts in a
             // standardized state. libstd ignores SIGPIPE, and signal-handling
             // libraries often set a mask. Child processes inherit ignored
@@ -210,14 +210,29 @@ impl Command {
             // UNIX programs do not reset these things on their own, so we
             // need to clean things up now to avoid confusing the program
             // we're about to run.
+            //
+            // Signals explicitly requested through `CommandExt::signal_mask`
+            // stay blocked, and those passed to `CommandExt::default_signals`
+            // are reset to their default disposition along with SIGPIPE.
             let mut set = MaybeUninit::<libc::sigset_t>::uninit();
             if cfg!(target_os = "android") {
                 // Implementing sigemptyset allow us to support older Android
                 // versions. See the comment about Android and sig* functions in
                 // process_common.rs
                 set.as_mut_ptr().write_bytes(0u8, 1);
+                for &signal in self.get_blocked_signals() {
+                    if signal <= 0 || signal as usize > mem::size_of::<libc::sigset_t>() * 8 {
+                        return Err(io::Error::from_raw_os_error(libc::EINVAL));
+                    }
+                    let raw = set.as_mut_ptr() as *mut u8;
+                    let bit = (signal - 1) as usize;
+                    *raw.add(bit / 8) |= 1 << (bit % 8);
+                }
             } else {
                 cvt(libc::sigemptyset(set.as_mut_ptr()))?;
+                for &signal in self.get_blocked_signals() {
+                    cvt(libc::sigaddset(set.as_mut_ptr(), signal))?;
+                }
             }
             cvt(libc::pthread_sigmask(libc::SIG_SETMASK, set.as_ptr(),
                                          ptr::null_mut()))?;
@@ -225,6 +240,19 @@ impl Command {
             if ret == libc::SIG_ERR {
                 return Err(io::Error::last_os_error())
             }
+            for &signal in self.get_default_signals() {
+                let ret = sys::signal(signal, libc::SIG_DFL);
+                if ret == libc::SIG_ERR {
+                    return Err(io::Error::last_os_error())
+                }
+            }
+        }
+
+        if self.get_setsid() {
+            cvt(libc::setsid())?;
+        }
+        if let Some(pgroup) = self.get_pgroup() {
+            cvt(libc::setpgid(0, pgroup))?;
         }
 
         for callback in self.get_closures().iter_mut() {
@@ -274,6 +302,19 @@ impl Command {
         use crate::mem::MaybeUninit;
         use crate::sys;
 
+        // `POSIX_SPAWN_SETSID` is a non-standard flag that only newer libcs
+        // know about, so it is not exposed by the `libc` crate everywhere.
+        #[cfg(all(target_os = "linux", target_env = "gnu"))]
+        const POSIX_SPAWN_SETSID: c_int = 0x80;
+        #[cfg(target_os = "macos")]
+        const POSIX_SPAWN_SETSID: c_int = 0x400;
+        #[cfg(target_os = "freebsd")]
+        const POSIX_SPAWN_SETSID: c_int = 0;
+
+        if self.get_setsid() && POSIX_SPAWN_SETSID == 0 {
+            return Ok(None)
+        }
+
         if self.get_gid().is_some() ||
             self.get_uid().is_some() ||
             self.env_saw_path() ||
@@ -288,6 +329,10 @@ impl Command {
                 if version < (2, 24) {
                     return Ok(None)
                 }
+                // POSIX_SPAWN_SETSID was added in glibc 2.26.
+                if self.get_setsid() && version < (2, 26) {
+                    return Ok(None)
+                }
             } else {
                 return Ok(None)
             }
@@ -361,14 +406,28 @@ impl Command {
 
             let mut set = MaybeUninit::<libc::sigset_t>::uninit();
             cvt(libc::sigemptyset(set.as_mut_ptr()))?;
+            for &signal in self.get_blocked_signals() {
+                cvt(libc::sigaddset(set.as_mut_ptr(), signal))?;
+            }
             cvt(libc::posix_spawnattr_setsigmask(attrs.0.as_mut_ptr(),
                                                  set.as_ptr()))?;
+            cvt(libc::sigemptyset(set.as_mut_ptr()))?;
             cvt(libc::sigaddset(set.as_mut_ptr(), libc::SIGPIPE))?;
+            for &signal in self.get_default_signals() {
+                cvt(libc::sigaddset(set.as_mut_ptr(), signal))?;
+            }
             cvt(libc::posix_spawnattr_setsigdefault(attrs.0.as_mut_ptr(),
                                                     set.as_ptr()))?;
 
-            let flags = libc::POSIX_SPAWN_SETSIGDEF |
+            let mut flags = libc::POSIX_SPAWN_SETSIGDEF |
                 libc::POSIX_SPAWN_SETSIGMASK;
+            if let Some(pgroup) = self.get_pgroup() {
+                flags |= libc::POSIX_SPAWN_SETPGROUP;
+                cvt(libc::posix_spawnattr_setpgroup(attrs.0.as_mut_ptr(), pgroup))?;
+            }
+            if self.get_setsid() {
+                flags |= POSIX_SPAWN_SETSID;
+            }
             cvt(libc::posix_spawnattr_setflags(attrs.0.as_mut_ptr(), flags as _))?;
 
             // Make sure we synchronize access to the global `environ` resource
@@ -419,6 +478,16 @@ impl Process {
         }
     }
 
+    pub fn send_signal(&self, signal: i32) -> io::Result<()> {
+        // See `kill` for why an exited process can't be signalled.
+        if self.status.is_some() {
+            Err(Error::new(ErrorKind::InvalidInput,
+                           "invalid argument: can't send a signal to an exited process"))
+        } else {
+            cvt(unsafe { libc::kill(self.pid, signal) }).map(|_| ())
+        }
+    }
+
     pub fn wait(&mut self) -> io::Result<ExitStatus> {
         use crate::sys::cvt_r;
         if let Some(status) = self.status {
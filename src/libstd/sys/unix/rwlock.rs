@@ -1,5 +1,15 @@
 use crate::cell::UnsafeCell;
 use crate::sync::atomic::{AtomicUsize, Ordering};
+use crate::time::Duration;
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+extern {
+    // Not exposed by the `libc` crate yet.
+    fn pthread_rwlock_timedrdlock(lock: *mut libc::pthread_rwlock_t,
+                                  abstime: *const libc::timespec) -> libc::c_int;
+    fn pthread_rwlock_timedwrlock(lock: *mut libc::pthread_rwlock_t,
+                                  abstime: *const libc::timespec) -> libc::c_int;
+}
 
 pub struct RWLock {
     inner: UnsafeCell<libc::pthread_rwlock_t>,
@@ -54,16 +64,35 @@ impl RWLock {
     #[inline]
     pub unsafe fn try_read(&self) -> bool {
         let r = libc::pthread_rwlock_tryrdlock(self.inner.get());
-        if r == 0 {
-            if *self.write_locked.get() {
-                self.raw_unlock();
-                false
-            } else {
-                self.num_readers.fetch_add(1, Ordering::Relaxed);
-                true
-            }
-        } else {
+        r == 0 && self.finish_try_read()
+    }
+    #[inline]
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub unsafe fn try_read_for(&self, dur: Duration) -> bool {
+        let timeout = super::mutex::realtime_deadline(dur);
+        let r = pthread_rwlock_timedrdlock(self.inner.get(), &timeout);
+        if r == libc::EAGAIN {
+            panic!("rwlock maximum reader count exceeded");
+        }
+        // A detected deadlock is reported as a timeout rather than a panic,
+        // as the caller is prepared for the acquisition to fail.
+        debug_assert!(r == 0 || r == libc::ETIMEDOUT || r == libc::EDEADLK);
+        r == 0 && self.finish_try_read()
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    pub unsafe fn try_read_for(&self, dur: Duration) -> bool {
+        crate::sys_common::mutex::try_lock_polling(dur, || self.try_read())
+    }
+    /// Called with a freshly acquired read lock; gives it up again if the
+    /// lock turns out to be write locked by the current thread.
+    #[inline]
+    unsafe fn finish_try_read(&self) -> bool {
+        if *self.write_locked.get() {
+            self.raw_unlock();
             false
+        } else {
+            self.num_readers.fetch_add(1, Ordering::Relaxed);
+            true
         }
     }
     #[inline]
@@ -85,16 +114,31 @@ impl RWLock {
     #[inline]
     pub unsafe fn try_write(&self) -> bool {
         let r = libc::pthread_rwlock_trywrlock(self.inner.get());
-        if r == 0 {
-            if *self.write_locked.get() || self.num_readers.load(Ordering::Relaxed) != 0 {
-                self.raw_unlock();
-                false
-            } else {
-                *self.write_locked.get() = true;
-                true
-            }
-        } else {
+        r == 0 && self.finish_try_write()
+    }
+    #[inline]
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub unsafe fn try_write_for(&self, dur: Duration) -> bool {
+        let timeout = super::mutex::realtime_deadline(dur);
+        let r = pthread_rwlock_timedwrlock(self.inner.get(), &timeout);
+        // See `try_read_for` for why EDEADLK isn't a panic here.
+        debug_assert!(r == 0 || r == libc::ETIMEDOUT || r == libc::EDEADLK);
+        r == 0 && self.finish_try_write()
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    pub unsafe fn try_write_for(&self, dur: Duration) -> bool {
+        crate::sys_common::mutex::try_lock_polling(dur, || self.try_write())
+    }
+    /// Called with a freshly acquired write lock; gives it up again if the
+    /// current thread already holds the lock.
+    #[inline]
+    unsafe fn finish_try_write(&self) -> bool {
+        if *self.write_locked.get() || self.num_readers.load(Ordering::Relaxed) != 0 {
+            self.raw_unlock();
             false
+        } else {
+            *self.write_locked.get() = true;
+            true
         }
     }
     #[inline]
@@ -2,6 +2,7 @@ use crate::cmp;
 use crate::ffi::CStr;
 use crate::io;
 use crate::mem;
+use crate::num::NonZeroUsize;
 use crate::ptr;
 use crate::sys::os;
 use crate::time::Duration;
@@ -185,6 +186,109 @@ impl Drop for Thread {
     }
 }
 
+#[cfg(target_os = "linux")]
+pub fn available_parallelism() -> io::Result<NonZeroUsize> {
+    let quota = cgroups::quota().max(1);
+    let mut set: libc::cpu_set_t = unsafe { mem::zeroed() };
+    unsafe {
+        if libc::sched_getaffinity(0, mem::size_of::<libc::cpu_set_t>(), &mut set) == 0 {
+            let count = (0..libc::CPU_SETSIZE as usize)
+                .filter(|&cpu| libc::CPU_ISSET(cpu, &set))
+                .count();
+            if let Some(count) = NonZeroUsize::new(cmp::min(count, quota)) {
+                return Ok(count);
+            }
+        }
+    }
+    // The affinity mask could not be read, fall back to sysconf but
+    // still honour the cgroup quota.
+    match unsafe { libc::sysconf(libc::_SC_NPROCESSORS_ONLN) } {
+        -1 => Err(io::Error::last_os_error()),
+        0 => Err(io::Error::new(io::ErrorKind::NotFound,
+                                "the number of hardware threads is not known \
+                                 for the target platform")),
+        cpus => Ok(unsafe {
+            NonZeroUsize::new_unchecked(cmp::min(cpus as usize, quota))
+        }),
+    }
+}
+
+#[cfg(any(
+    target_os = "android",
+    target_os = "emscripten",
+    target_os = "fuchsia",
+    target_os = "ios",
+    target_os = "macos",
+    target_os = "solaris",
+))]
+pub fn available_parallelism() -> io::Result<NonZeroUsize> {
+    match unsafe { libc::sysconf(libc::_SC_NPROCESSORS_ONLN) } {
+        -1 => Err(io::Error::last_os_error()),
+        0 => Err(io::Error::new(io::ErrorKind::NotFound,
+                                "the number of hardware threads is not known \
+                                 for the target platform")),
+        cpus => Ok(unsafe { NonZeroUsize::new_unchecked(cpus as usize) }),
+    }
+}
+
+#[cfg(any(
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+pub fn available_parallelism() -> io::Result<NonZeroUsize> {
+    let mut cpus = match unsafe { libc::sysconf(libc::_SC_NPROCESSORS_ONLN) } {
+        n if n > 0 => n as libc::c_uint,
+        _ => 0,
+    };
+
+    if cpus < 1 {
+        let mut cpus_size = mem::size_of_val(&cpus);
+        let mut mib = [libc::CTL_HW, libc::HW_NCPU, 0, 0];
+        let res = unsafe {
+            libc::sysctl(
+                mib.as_mut_ptr(),
+                2,
+                &mut cpus as *mut _ as *mut _,
+                &mut cpus_size as *mut _ as *mut _,
+                ptr::null_mut(),
+                0,
+            )
+        };
+
+        // Handle errors if any.
+        if res == -1 {
+            return Err(io::Error::last_os_error());
+        } else if cpus == 0 {
+            return Err(io::Error::new(io::ErrorKind::NotFound,
+                                      "the number of hardware threads is not known \
+                                       for the target platform"));
+        }
+    }
+    Ok(unsafe { NonZeroUsize::new_unchecked(cpus as usize) })
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "emscripten",
+    target_os = "fuchsia",
+    target_os = "ios",
+    target_os = "macos",
+    target_os = "solaris",
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "netbsd",
+    target_os = "openbsd",
+)))]
+pub fn available_parallelism() -> io::Result<NonZeroUsize> {
+    // FIXME: implement on haiku, l4re and the other remaining targets
+    Err(io::Error::new(io::ErrorKind::NotFound,
+                       "the number of hardware threads is not known \
+                        for the target platform"))
+}
+
 #[cfg(all(not(all(target_os = "linux", not(target_env = "musl"))),
           not(target_os = "freebsd"),
           not(target_os = "macos"),
@@ -419,3 +523,118 @@ fn min_stack_size(_: *const libc::pthread_attr_t) -> usize {
 fn min_stack_size(_: *const libc::pthread_attr_t) -> usize {
     2048 // just a guess
 }
+
+#[cfg(target_os = "linux")]
+mod cgroups {
+    //! Reads the CPU bandwidth quota of the cgroup the process belongs to.
+    //!
+    //! Both the unified (v2) hierarchy and the v1 `cpu` controller are
+    //! supported. Any failure to read or parse the cgroup files is treated
+    //! as "no quota" rather than an error, since cgroups are an optional
+    //! limit on top of the affinity mask.
+
+    use crate::fs;
+    use crate::path::{Path, PathBuf};
+    use crate::str::from_utf8;
+    use crate::usize;
+
+    /// Returns the number of CPUs the process may use according to its
+    /// cgroup quota, or `usize::MAX` if there is no quota.
+    pub(super) fn quota() -> usize {
+        let cgroups = match fs::read("/proc/self/cgroup") {
+            Ok(cgroups) => cgroups,
+            Err(_) => return usize::MAX,
+        };
+        let cgroups = match from_utf8(&cgroups) {
+            Ok(cgroups) => cgroups,
+            Err(_) => return usize::MAX,
+        };
+
+        let mut quota = usize::MAX;
+        for line in cgroups.lines() {
+            // Each line is `hierarchy-ID:controller-list:cgroup-path`.
+            let mut fields = line.splitn(3, ':');
+            let (controllers, path) = match (fields.next(), fields.next(), fields.next()) {
+                (Some(_id), Some(controllers), Some(path)) => {
+                    (controllers, path.trim_start_matches('/'))
+                }
+                _ => continue,
+            };
+
+            let limit = if controllers.is_empty() {
+                quota_v2(path)
+            } else if controllers.split(',').any(|c| c == "cpu") {
+                quota_v1(path)
+            } else {
+                None
+            };
+            if let Some(limit) = limit {
+                quota = quota.min(limit);
+            }
+        }
+
+        quota
+    }
+
+    /// Walks from the process' cgroup up to the root of the unified
+    /// hierarchy, taking the tightest `cpu.max` limit along the way.
+    fn quota_v2(group_path: &str) -> Option<usize> {
+        let root = Path::new("/sys/fs/cgroup");
+        let mut path = root.join(group_path);
+        let mut quota = usize::MAX;
+
+        while path.starts_with(root) {
+            path.push("cpu.max");
+            if let Ok(limit) = fs::read_to_string(&path) {
+                let mut fields = limit.split_whitespace();
+                let max = fields.next();
+                let period = fields.next();
+                if let (Some(max), Some(period)) = (max, period) {
+                    // `max` means no limit at this level.
+                    if max != "max" {
+                        if let Some(q) = divide(max, period) {
+                            quota = quota.min(q);
+                        }
+                    }
+                }
+            }
+            path.pop();
+
+            if !path.pop() {
+                break;
+            }
+        }
+
+        if quota == usize::MAX { None } else { Some(quota) }
+    }
+
+    /// Reads `cpu.cfs_quota_us` and `cpu.cfs_period_us` for a v1 `cpu`
+    /// controller. The controller may be mounted on its own or together
+    /// with `cpuacct`.
+    fn quota_v1(group_path: &str) -> Option<usize> {
+        for mount in &["/sys/fs/cgroup/cpu", "/sys/fs/cgroup/cpu,cpuacct"] {
+            let dir: PathBuf = Path::new(mount).join(group_path);
+            let quota = match fs::read_to_string(dir.join("cpu.cfs_quota_us")) {
+                Ok(quota) => quota,
+                Err(_) => continue,
+            };
+            let period = fs::read_to_string(dir.join("cpu.cfs_period_us")).ok()?;
+            // A quota of -1 means the group is unrestricted.
+            if quota.trim() == "-1" {
+                return None;
+            }
+            return divide(&quota, &period);
+        }
+        None
+    }
+
+    fn divide(quota: &str, period: &str) -> Option<usize> {
+        let quota: u64 = quota.trim().parse().ok()?;
+        let period: u64 = period.trim().parse().ok()?;
+        if quota == 0 || period == 0 {
+            return None;
+        }
+        // Round down, but never below one CPU.
+        Some(((quota / period) as usize).max(1))
+    }
+}
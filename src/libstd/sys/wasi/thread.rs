@@ -1,6 +1,7 @@
 use crate::cmp;
 use crate::ffi::CStr;
 use crate::io;
+use crate::num::NonZeroUsize;
 use crate::sys::cvt;
 use crate::sys::{unsupported, Void};
 use crate::time::Duration;
@@ -49,6 +50,10 @@ impl Thread {
     }
 }
 
+pub fn available_parallelism() -> io::Result<NonZeroUsize> {
+    unsupported()
+}
+
 pub mod guard {
     pub type Guard = !;
     pub unsafe fn current() -> Option<Guard> { None }
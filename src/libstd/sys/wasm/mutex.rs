@@ -1,4 +1,5 @@
 use crate::cell::UnsafeCell;
+use crate::time::Duration;
 
 pub struct Mutex {
     locked: UnsafeCell<bool>,
@@ -39,6 +40,12 @@ impl Mutex {
         }
     }
 
+    #[inline]
+    pub unsafe fn try_lock_for(&self, _dur: Duration) -> bool {
+        // Without other threads nobody could release the lock while we wait.
+        self.try_lock()
+    }
+
     #[inline]
     pub unsafe fn destroy(&self) {
     }
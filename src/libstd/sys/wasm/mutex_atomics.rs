@@ -1,8 +1,10 @@
 use crate::arch::wasm32;
 use crate::cell::UnsafeCell;
+use crate::cmp;
 use crate::mem;
 use crate::sync::atomic::{AtomicUsize, AtomicU32, Ordering::SeqCst};
 use crate::sys::thread;
+use crate::time::{Duration, Instant};
 
 pub struct Mutex {
     locked: AtomicUsize,
@@ -48,6 +50,22 @@ impl Mutex {
         self.locked.compare_exchange(0, 1, SeqCst, SeqCst).is_ok()
     }
 
+    pub unsafe fn try_lock_for(&self, dur: Duration) -> bool {
+        let start = Instant::now();
+        while !self.try_lock() {
+            let remaining = match dur.checked_sub(start.elapsed()) {
+                Some(remaining) => remaining,
+                None => return false,
+            };
+            let nanos = cmp::min(i64::max_value() as u128, remaining.as_nanos());
+            // Same as in `lock`, except that we may also time out (2), in
+            // which case the loop gives the lock one last try.
+            let val = wasm32::i32_atomic_wait(self.ptr(), 1, nanos as i64);
+            debug_assert!(val == 0 || val == 1 || val == 2);
+        }
+        true
+    }
+
     #[inline]
     pub unsafe fn destroy(&self) {
         // nothing to do
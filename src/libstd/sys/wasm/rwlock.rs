@@ -1,4 +1,5 @@
 use crate::cell::UnsafeCell;
+use crate::time::Duration;
 
 pub struct RWLock {
     mode: UnsafeCell<isize>,
@@ -35,6 +36,12 @@ impl RWLock {
         }
     }
 
+    #[inline]
+    pub unsafe fn try_read_for(&self, _dur: Duration) -> bool {
+        // Without other threads nobody could release the lock while we wait.
+        self.try_read()
+    }
+
     #[inline]
     pub unsafe fn write(&self) {
         let mode = self.mode.get();
@@ -56,6 +63,11 @@ impl RWLock {
         }
     }
 
+    #[inline]
+    pub unsafe fn try_write_for(&self, _dur: Duration) -> bool {
+        self.try_write()
+    }
+
     #[inline]
     pub unsafe fn read_unlock(&self) {
         *self.mode.get() -= 1;
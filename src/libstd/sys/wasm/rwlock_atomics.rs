@@ -1,6 +1,7 @@
 use crate::cell::UnsafeCell;
 use crate::sys::mutex::Mutex;
 use crate::sys::condvar::Condvar;
+use crate::time::{Duration, Instant};
 
 pub struct RWLock {
     lock: Mutex,
@@ -51,6 +52,11 @@ impl RWLock {
         return ok
     }
 
+    #[inline]
+    pub unsafe fn try_read_for(&self, dur: Duration) -> bool {
+        self.acquire_for(dur, State::inc_readers)
+    }
+
     #[inline]
     pub unsafe fn write(&self) {
         self.lock.lock();
@@ -68,6 +74,27 @@ impl RWLock {
         return ok
     }
 
+    #[inline]
+    pub unsafe fn try_write_for(&self, dur: Duration) -> bool {
+        self.acquire_for(dur, State::inc_writers)
+    }
+
+    unsafe fn acquire_for(&self, dur: Duration, acquire: fn(&mut State) -> bool) -> bool {
+        let start = Instant::now();
+        self.lock.lock();
+        let mut ok = acquire(&mut *self.state.get());
+        while !ok {
+            let remaining = match dur.checked_sub(start.elapsed()) {
+                Some(remaining) => remaining,
+                None => break,
+            };
+            self.cond.wait_timeout(&self.lock, remaining);
+            ok = acquire(&mut *self.state.get());
+        }
+        self.lock.unlock();
+        ok
+    }
+
     #[inline]
     pub unsafe fn read_unlock(&self) {
         self.lock.lock();
@@ -24,6 +24,8 @@ use crate::mem::{self, MaybeUninit};
 use crate::sync::atomic::{AtomicUsize, Ordering};
 use crate::sys::c;
 use crate::sys::compat;
+use crate::sys_common::mutex::try_lock_polling;
+use crate::time::Duration;
 
 pub struct Mutex {
     lock: AtomicUsize,
@@ -85,6 +87,10 @@ impl Mutex {
             }
         }
     }
+    pub unsafe fn try_lock_for(&self, dur: Duration) -> bool {
+        // Neither SRWLock nor CriticalSection support timed acquisition.
+        try_lock_polling(dur, || self.try_lock())
+    }
     pub unsafe fn unlock(&self) {
         *self.held.get() = false;
         match kind() {
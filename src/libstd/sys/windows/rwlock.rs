@@ -1,5 +1,7 @@
 use crate::cell::UnsafeCell;
 use crate::sys::c;
+use crate::sys_common::mutex::try_lock_polling;
+use crate::time::Duration;
 
 pub struct RWLock { inner: UnsafeCell<c::SRWLOCK> }
 
@@ -19,6 +21,11 @@ impl RWLock {
         c::TryAcquireSRWLockShared(self.inner.get()) != 0
     }
     #[inline]
+    pub unsafe fn try_read_for(&self, dur: Duration) -> bool {
+        // SRWLock has no timed acquisition.
+        try_lock_polling(dur, || self.try_read())
+    }
+    #[inline]
     pub unsafe fn write(&self) {
         c::AcquireSRWLockExclusive(self.inner.get())
     }
@@ -27,6 +34,10 @@ impl RWLock {
         c::TryAcquireSRWLockExclusive(self.inner.get()) != 0
     }
     #[inline]
+    pub unsafe fn try_write_for(&self, dur: Duration) -> bool {
+        try_lock_polling(dur, || self.try_write())
+    }
+    #[inline]
     pub unsafe fn read_unlock(&self) {
         c::ReleaseSRWLockShared(self.inner.get())
     }
@@ -1,4 +1,11 @@
+use crate::cell::UnsafeCell;
+use crate::cmp;
+use crate::collections::VecDeque;
+use crate::sync::atomic;
 use crate::sys::mutex as imp;
+use crate::sys_common::condvar::Condvar;
+use crate::thread;
+use crate::time::{Duration, Instant};
 
 /// An OS-based mutual exclusion lock.
 ///
@@ -51,6 +58,14 @@ impl Mutex {
     #[inline]
     pub unsafe fn try_lock(&self) -> bool { self.0.try_lock() }
 
+    /// Attempts to lock the mutex, blocking the current thread for at most
+    /// `dur`, returning whether it was successfully acquired or not.
+    ///
+    /// Behavior is undefined if the mutex has been moved between this and any
+    /// previous function call.
+    #[inline]
+    pub unsafe fn try_lock_for(&self, dur: Duration) -> bool { self.0.try_lock_for(dur) }
+
     /// Unlocks the mutex.
     ///
     /// Behavior is undefined if the current thread does not actually hold the
@@ -82,3 +97,171 @@ impl Drop for MutexGuard<'_> {
         unsafe { self.0.unlock(); }
     }
 }
+
+/// Repeatedly calls `try_lock` until it succeeds or `dur` has elapsed.
+///
+/// This is the fallback for platforms without a native timed lock
+/// acquisition. It first spins, then yields and finally sleeps in short
+/// intervals, so a lock which is released quickly is still picked up quickly.
+#[allow(dead_code)] // not used on all platforms
+pub fn try_lock_polling<F: FnMut() -> bool>(dur: Duration, mut try_lock: F) -> bool {
+    if try_lock() {
+        return true;
+    }
+    let start = Instant::now();
+    let mut attempt = 0u32;
+    loop {
+        if attempt < 6 {
+            for _ in 0..(1 << attempt) {
+                atomic::spin_loop_hint();
+            }
+        } else if attempt < 16 {
+            thread::yield_now();
+        } else {
+            let remaining = match dur.checked_sub(start.elapsed()) {
+                Some(remaining) => remaining,
+                None => return try_lock(),
+            };
+            thread::sleep(cmp::min(remaining, Duration::from_millis(1)));
+        }
+        attempt = attempt.saturating_add(1);
+
+        if try_lock() {
+            return true;
+        }
+        if start.elapsed() >= dur {
+            return false;
+        }
+    }
+}
+
+/// Hands out the lock of a fair mutex in first-come, first-served order.
+///
+/// The OS mutexes make no ordering guarantees, so fair mutexes first wait for
+/// their turn in this queue and only then acquire the OS mutex. The turn is
+/// given back with `release` after the OS mutex has been unlocked.
+///
+/// Like `Mutex`, this must not be moved once it has been initialized.
+pub struct FairQueue {
+    lock: Mutex,
+    cvar: Condvar,
+    state: UnsafeCell<FairState>,
+}
+
+struct FairState {
+    // Whether some thread currently has its turn.
+    held: bool,
+    next_ticket: u64,
+    // Tickets of the waiting threads, in arrival order.
+    waiting: VecDeque<u64>,
+}
+
+unsafe impl Sync for FairQueue {}
+
+impl FairQueue {
+    /// Creates a new, empty queue. It must be initialized with `init`
+    /// before use.
+    pub fn new() -> FairQueue {
+        FairQueue {
+            lock: Mutex::new(),
+            cvar: Condvar::new(),
+            state: UnsafeCell::new(FairState {
+                held: false,
+                next_ticket: 0,
+                waiting: VecDeque::new(),
+            }),
+        }
+    }
+
+    /// Prepares the queue for use.
+    ///
+    /// Behavior is undefined if the queue is moved after this call.
+    pub unsafe fn init(&mut self) {
+        self.lock.init();
+        self.cvar.init();
+    }
+
+    /// Blocks the current thread until it is its turn.
+    pub unsafe fn acquire(&self) {
+        self.acquire_until(None);
+    }
+
+    /// Takes the turn if no other thread has it or is waiting for it.
+    pub unsafe fn try_acquire(&self) -> bool {
+        let _guard = self.lock.lock();
+        let state = &mut *self.state.get();
+        if state.held || !state.waiting.is_empty() {
+            false
+        } else {
+            state.held = true;
+            true
+        }
+    }
+
+    /// Blocks the current thread for at most `dur` until it is its turn,
+    /// returning whether it got it.
+    pub unsafe fn acquire_for(&self, dur: Duration) -> bool {
+        // An unrepresentable deadline is as good as no deadline at all.
+        self.acquire_until(Instant::now().checked_add(dur))
+    }
+
+    unsafe fn acquire_until(&self, deadline: Option<Instant>) -> bool {
+        let _guard = self.lock.lock();
+        let ticket = {
+            let state = &mut *self.state.get();
+            let ticket = state.next_ticket;
+            state.next_ticket += 1;
+            state.waiting.push_back(ticket);
+            ticket
+        };
+        loop {
+            {
+                let state = &mut *self.state.get();
+                if !state.held && state.waiting.front() == Some(&ticket) {
+                    state.waiting.pop_front();
+                    state.held = true;
+                    return true;
+                }
+            }
+            match deadline {
+                None => self.cvar.wait(&self.lock),
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        let state = &mut *self.state.get();
+                        state.waiting.retain(|&t| t != ticket);
+                        // We may have been at the front of the queue.
+                        self.cvar.notify_all();
+                        return false;
+                    }
+                    self.cvar.wait_timeout(&self.lock, deadline - now);
+                }
+            }
+        }
+    }
+
+    /// Gives up the turn of the current thread.
+    pub unsafe fn release(&self) {
+        let _guard = self.lock.lock();
+        (*self.state.get()).held = false;
+        self.cvar.notify_all();
+    }
+
+    /// Returns the number of threads waiting for their turn.
+    #[cfg(test)]
+    pub fn waiting(&self) -> usize {
+        unsafe {
+            let _guard = self.lock.lock();
+            (*self.state.get()).waiting.len()
+        }
+    }
+
+    /// Deallocates all resources associated with this queue.
+    ///
+    /// Behavior is undefined if there are current or will be future users of
+    /// this queue.
+    pub unsafe fn destroy(&self) {
+        self.cvar.destroy();
+        self.lock.destroy();
+    }
+}
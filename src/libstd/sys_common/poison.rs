@@ -3,7 +3,7 @@ use crate::fmt;
 use crate::sync::atomic::{AtomicBool, Ordering};
 use crate::thread;
 
-pub struct Flag { failed: AtomicBool }
+pub struct Flag { failed: AtomicBool, enabled: bool }
 
 // Note that the Ordering uses to access the `failed` field of `Flag` below is
 // always `Relaxed`, and that's because this isn't actually protecting any data,
@@ -18,7 +18,13 @@ pub struct Flag { failed: AtomicBool }
 
 impl Flag {
     pub const fn new() -> Flag {
-        Flag { failed: AtomicBool::new(false) }
+        Flag { failed: AtomicBool::new(false), enabled: true }
+    }
+
+    /// Creates a flag which is never set, for locks that opted out of
+    /// poisoning.
+    pub const fn disabled() -> Flag {
+        Flag { failed: AtomicBool::new(false), enabled: false }
     }
 
     #[inline]
@@ -33,7 +39,7 @@ impl Flag {
 
     #[inline]
     pub fn done(&self, guard: &Guard) {
-        if !guard.panicking && thread::panicking() {
+        if self.enabled && !guard.panicking && thread::panicking() {
             self.failed.store(true, Ordering::Relaxed);
         }
     }
@@ -1,4 +1,5 @@
 use crate::sys::rwlock as imp;
+use crate::time::Duration;
 
 /// An OS-based reader-writer lock.
 ///
@@ -32,6 +33,14 @@ impl RWLock {
     #[inline]
     pub unsafe fn try_read(&self) -> bool { self.0.try_read() }
 
+    /// Attempts to acquire shared access to this lock, blocking the current
+    /// thread for at most `dur`, returning whether it succeeded or not.
+    ///
+    /// Behavior is undefined if the rwlock has been moved between this and any
+    /// previous method call.
+    #[inline]
+    pub unsafe fn try_read_for(&self, dur: Duration) -> bool { self.0.try_read_for(dur) }
+
     /// Acquires write access to the underlying lock, blocking the current thread
     /// to do so.
     ///
@@ -50,6 +59,14 @@ impl RWLock {
     #[inline]
     pub unsafe fn try_write(&self) -> bool { self.0.try_write() }
 
+    /// Attempts to acquire exclusive access to this lock, blocking the current
+    /// thread for at most `dur`, returning whether it succeeded or not.
+    ///
+    /// Behavior is undefined if the rwlock has been moved between this and any
+    /// previous method call.
+    #[inline]
+    pub unsafe fn try_write_for(&self, dur: Duration) -> bool { self.0.try_write_for(dur) }
+
     /// Unlocks previously acquired shared access to this lock.
     ///
     /// Behavior is undefined if the current thread does not have shared access.
@@ -162,7 +162,7 @@ use crate::ffi::{CStr, CString};
 use crate::fmt;
 use crate::io;
 use crate::mem;
-use crate::num::NonZeroU64;
+use crate::num::{NonZeroU64, NonZeroUsize};
 use crate::panic;
 use crate::panicking;
 use crate::str;
@@ -203,6 +203,15 @@ pub use self::local::{LocalKey, AccessError};
 #[unstable(feature = "libstd_thread_internals", issue = "0")]
 #[doc(hidden)] pub use self::local::os::Key as __OsLocalKeyInner;
 
+////////////////////////////////////////////////////////////////////////////////
+// Scoped threads
+////////////////////////////////////////////////////////////////////////////////
+
+mod scoped;
+
+#[unstable(feature = "scoped_threads", issue = "0")]
+pub use self::scoped::{scope, Scope, ScopedJoinHandle};
+
 ////////////////////////////////////////////////////////////////////////////////
 // Builder
 ////////////////////////////////////////////////////////////////////////////////
@@ -447,6 +456,19 @@ impl Builder {
     #[unstable(feature = "thread_spawn_unchecked", issue = "55132")]
     pub unsafe fn spawn_unchecked<'a, F, T>(self, f: F) -> io::Result<JoinHandle<T>> where
         F: FnOnce() -> T, F: Send + 'a, T: Send + 'a
+    {
+        Ok(JoinHandle(self.spawn_unchecked_(f, None)?))
+    }
+
+    // Shared implementation of `spawn_unchecked` and `spawn_scoped`. If `scope_data` is given,
+    // the scope is told about the thread, and its packet reports back to the scope once the
+    // thread's result has been dropped.
+    unsafe fn spawn_unchecked_<'a, F, T>(
+        self,
+        f: F,
+        scope_data: Option<Arc<scoped::ScopeData>>,
+    ) -> io::Result<JoinInner<T>> where
+        F: FnOnce() -> T, F: Send + 'a, T: Send + 'a
     {
         let Builder { name, stack_size } = self;
 
@@ -455,8 +477,13 @@ impl Builder {
         let my_thread = Thread::new(name);
         let their_thread = my_thread.clone();
 
-        let my_packet : Arc<UnsafeCell<Option<Result<T>>>>
-            = Arc::new(UnsafeCell::new(None));
+        if let Some(scope_data) = &scope_data {
+            scope_data.increment_num_running_threads();
+        }
+        let my_packet: Arc<Packet<T>> = Arc::new(Packet {
+            scope: scope_data,
+            result: UnsafeCell::new(None),
+        });
         let their_packet = my_packet.clone();
 
         let main = move || {
@@ -471,10 +498,10 @@ impl Builder {
             }));
             #[cfg(not(feature = "backtrace"))]
             let try_result = panic::catch_unwind(panic::AssertUnwindSafe(f));
-            *their_packet.get() = Some(try_result);
+            *their_packet.result.get() = Some(try_result);
         };
 
-        Ok(JoinHandle(JoinInner {
+        Ok(JoinInner {
             // `imp::Thread::new` takes a closure with a `'static` lifetime, since it's passed
             // through FFI or otherwise used with low-level threading primitives that have no
             // notion of or way to enforce lifetimes.
@@ -493,8 +520,8 @@ impl Builder {
                 )),
             )?),
             thread: my_thread,
-            packet: Packet(my_packet),
-        }))
+            packet: my_packet,
+        })
     }
 }
 
@@ -1011,6 +1038,42 @@ pub fn park_timeout(dur: Duration) {
     }
 }
 
+/// Returns an estimate of the default amount of parallelism a program should use.
+///
+/// Parallelism is a resource. A given machine provides a certain capacity for
+/// parallelism, i.e., a bound on the number of computations it can perform
+/// simultaneously. This number often corresponds to the amount of CPUs a
+/// computer has, but it may diverge in various cases.
+///
+/// This function will, but is not limited to, return errors in the following
+/// cases:
+///
+/// - If the number of hardware threads is not known for the target platform.
+/// - The process lacks permissions to view the number of hardware threads
+///   available.
+///
+/// # Platform-specific behavior
+///
+/// On Linux the result respects the CPU affinity mask of the calling thread
+/// as reported by `sched_getaffinity`, and is further limited by cgroup
+/// (v1 and v2) CPU quotas when those are set. The value may therefore be
+/// lower than the number of CPUs installed in the machine, and it may change
+/// over the lifetime of the process.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(available_parallelism)]
+/// use std::thread;
+///
+/// let count = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+/// assert!(count >= 1);
+/// ```
+#[unstable(feature = "available_parallelism", issue = "0")]
+pub fn available_parallelism() -> io::Result<NonZeroUsize> {
+    imp::available_parallelism()
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // ThreadId
 ////////////////////////////////////////////////////////////////////////////////
@@ -1299,34 +1362,66 @@ impl fmt::Debug for Thread {
 pub type Result<T> = crate::result::Result<T, Box<dyn Any + Send + 'static>>;
 
 // This packet is used to communicate the return value between the child thread
-// and the parent thread. Memory is shared through the `Arc` within and there's
-// no need for a mutex here because synchronization happens with `join()` (the
-// parent thread never reads this packet until the child has exited).
+// and the parent thread. Memory is shared through an `Arc` and there's no need
+// for a mutex here because synchronization happens with `join()` (the parent
+// thread never reads this packet until the child has exited).
 //
-// This packet itself is then stored into a `JoinInner` which in turns is placed
-// in `JoinHandle` and `JoinGuard`. Due to the usage of `UnsafeCell` we need to
-// manually worry about impls like Send and Sync. The type `T` should
+// An `Arc` to this packet is stored into a `JoinInner` which in turns is placed
+// in `JoinHandle` and `ScopedJoinHandle`. Due to the usage of `UnsafeCell` we
+// need to manually worry about impls like Send and Sync. The type `T` should
 // already always be Send (otherwise the thread could not have been created) and
 // this type is inherently Sync because no methods take &self. Regardless,
 // however, we add inheriting impls for Send/Sync to this type to ensure it's
 // Send/Sync and that future modifications will still appropriately classify it.
-struct Packet<T>(Arc<UnsafeCell<Option<Result<T>>>>);
+//
+// For threads spawned in a scope, the packet also keeps the scope informed: the
+// scope only returns once the packets of all its threads have been dropped, which
+// guarantees that no borrowed data is still in use by the threads' results.
+struct Packet<T> {
+    scope: Option<Arc<scoped::ScopeData>>,
+    result: UnsafeCell<Option<Result<T>>>,
+}
 
 unsafe impl<T: Send> Send for Packet<T> {}
 unsafe impl<T: Sync> Sync for Packet<T> {}
 
+impl<T> Drop for Packet<T> {
+    fn drop(&mut self) {
+        if let Some(scope) = &self.scope {
+            // If this packet was for a scoped thread that panicked, and nobody
+            // consumed the panic payload by joining it, the scope will panic.
+            let unhandled_panic = match unsafe { &*self.result.get() } {
+                Some(Err(_)) => true,
+                _ => false,
+            };
+            // Drop the result before telling the scope we're done, since it may
+            // borrow from the scope's environment. If that panics we're outside of
+            // the thread's `catch_unwind`, so there is nothing left to do but abort.
+            let result = &self.result;
+            if panic::catch_unwind(panic::AssertUnwindSafe(|| unsafe {
+                *result.get() = None;
+            })).is_err() {
+                crate::sys_common::util::abort(
+                    format_args!("thread result panicked on drop"),
+                );
+            }
+            scope.decrement_num_running_threads(unhandled_panic);
+        }
+    }
+}
+
 /// Inner representation for JoinHandle
 struct JoinInner<T> {
     native: Option<imp::Thread>,
     thread: Thread,
-    packet: Packet<T>,
+    packet: Arc<Packet<T>>,
 }
 
 impl<T> JoinInner<T> {
     fn join(&mut self) -> Result<T> {
         self.native.take().unwrap().join();
         unsafe {
-            (*self.packet.0.get()).take().unwrap()
+            (*self.packet.result.get()).take().unwrap()
         }
     }
 }
@@ -1500,6 +1595,13 @@ mod tests {
     // !!! These tests are dangerous. If something is buggy, they will hang, !!!
     // !!! instead of exiting cleanly. This might wedge the buildbots.       !!!
 
+    #[test]
+    fn test_available_parallelism() {
+        // Every supported test host can report at least one hardware thread.
+        let n = thread::available_parallelism().unwrap();
+        assert!(n.get() >= 1);
+    }
+
     #[test]
     fn test_unnamed_thread() {
         thread::spawn(move|| {
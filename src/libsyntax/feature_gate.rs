@@ -577,6 +577,15 @@ declare_features! (
     // Allows the use of `#[cfg(doctest)]`, set when rustdoc is collecting doctests
     (active, cfg_doctest, "1.37.0", Some(62210), None),
 
+    // Allows `if`, `match` and short-circuiting `&&`/`||` in constant contexts.
+    (active, const_if_match, "1.38.0", Some(49146), None),
+
+    // Allows `loop` and `while` in constant contexts.
+    (active, const_loop, "1.38.0", Some(52000), None),
+
+    // Allows `Box::new`, `Vec::with_capacity` and the `alloc` functions in constant contexts.
+    (active, const_heap, "1.38.0", None, None),
+
     // -------------------------------------------------------------------------
     // feature-group-end: actual feature gates
     // -------------------------------------------------------------------------
@@ -1393,6 +1402,12 @@ pub const BUILTIN_ATTRIBUTES: &[BuiltinAttribute] = &[
                                                 "internal implementation detail",
                                                 cfg_fn!(rustc_attrs))),
 
+    (sym::rustc_const_heap, Whitelisted, template!(NameValueStr: "name"),
+                                                Gated(Stability::Unstable,
+                                                sym::rustc_attrs,
+                                                "internal implementation detail",
+                                                cfg_fn!(rustc_attrs))),
+
     (sym::rustc_dummy, Normal, template!(Word /* doesn't matter*/), Gated(Stability::Unstable,
                                          sym::rustc_attrs,
                                          "used by the test suite",
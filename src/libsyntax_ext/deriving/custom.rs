@@ -1,5 +1,4 @@
-use crate::proc_macro_impl::EXEC_STRATEGY;
-use crate::proc_macro_server;
+use crate::proc_macro_impl::ProcMacroClient;
 
 use errors::FatalError;
 use rustc_data_structures::sync::Lrc;
@@ -29,7 +28,7 @@ impl<'a> Visitor<'a> for MarkAttrs<'a> {
 }
 
 pub struct ProcMacroDerive {
-    pub client: proc_macro::bridge::client::Client<
+    pub client: ProcMacroClient<
         fn(proc_macro::TokenStream) -> proc_macro::TokenStream,
     >,
     pub attrs: Vec<ast::Name>,
@@ -71,19 +70,9 @@ impl MultiItemModifier for ProcMacroDerive {
         let token = token::Interpolated(Lrc::new(token::NtItem(item)));
         let input = tokenstream::TokenTree::token(token, DUMMY_SP).into();
 
-        let server = proc_macro_server::Rustc::new(ecx);
-        let stream = match self.client.run(&EXEC_STRATEGY, server, input) {
+        let stream = match self.client.run(ecx, input) {
             Ok(stream) => stream,
-            Err(e) => {
-                let msg = "proc-macro derive panicked";
-                let mut err = ecx.struct_span_fatal(span, msg);
-                if let Some(s) = e.as_str() {
-                    err.help(&format!("message: {}", s));
-                }
-
-                err.emit();
-                FatalError.raise();
-            }
+            Err(e) => e.report(ecx, span, "proc-macro derive"),
         };
 
         let error_count_before = ecx.parse_sess.span_diagnostic.err_count();
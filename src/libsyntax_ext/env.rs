@@ -22,8 +22,10 @@ pub fn expand_option_env<'cx>(cx: &'cx mut ExtCtxt<'_>,
     };
 
     let sp = sp.apply_mark(cx.current_expansion.mark);
-    let e = match env::var(&*var.as_str()) {
-        Err(..) => {
+    let value = env::var(&var).ok().map(|value| Symbol::intern(&value));
+    cx.parse_sess.env_depinfo.borrow_mut().insert((Symbol::intern(&var), value));
+    let e = match value {
+        None => {
             let lt = cx.lifetime(sp, Ident::with_empty_ctxt(kw::StaticLifetime));
             cx.expr_path(cx.path_all(sp,
                                      true,
@@ -35,10 +37,10 @@ pub fn expand_option_env<'cx>(cx: &'cx mut ExtCtxt<'_>,
                                                      ast::Mutability::Immutable))],
                                      vec![]))
         }
-        Ok(s) => {
+        Some(value) => {
             cx.expr_call_global(sp,
                                 cx.std_path(&[sym::option, sym::Option, sym::Some]),
-                                vec![cx.expr_str(sp, Symbol::intern(&s))])
+                                vec![cx.expr_str(sp, value)])
         }
     };
     MacEager::expr(e)
@@ -76,12 +78,14 @@ pub fn expand_env<'cx>(cx: &'cx mut ExtCtxt<'_>,
         return DummyResult::expr(sp);
     }
 
-    let e = match env::var(&*var.as_str()) {
-        Err(_) => {
+    let value = env::var(&*var.as_str()).ok().map(|value| Symbol::intern(&value));
+    cx.parse_sess.env_depinfo.borrow_mut().insert((var, value));
+    let e = match value {
+        None => {
             cx.span_err(sp, &msg.as_str());
             return DummyResult::expr(sp);
         }
-        Ok(s) => cx.expr_str(sp, Symbol::intern(&s)),
+        Some(value) => cx.expr_str(sp, value),
     };
     MacEager::expr(e)
 }
@@ -1,15 +1,101 @@
 use crate::proc_macro_server;
 
 use errors::FatalError;
+use proc_macro::bridge::PanicMessage;
+use proc_macro::bridge::process::{HostError, ProcessServer};
+use rustc_data_structures::sync::Lrc;
 use syntax::source_map::Span;
 use syntax::ext::base::{self, *};
 use syntax::tokenstream::TokenStream;
 
+use std::path::PathBuf;
+
 pub const EXEC_STRATEGY: proc_macro::bridge::server::SameThread =
     proc_macro::bridge::server::SameThread;
 
+/// The client of a proc macro, either loaded into the compiler, or running
+/// in a separate host process (with `-Z out-of-process-proc-macros`).
+pub enum ProcMacroClient<F> {
+    Local(proc_macro::bridge::client::Client<F>),
+    Remote(RemoteProcMacro),
+}
+
+/// A proc macro loaded into a host process, i.e. the proc macro at `index`
+/// in the declarations `symbol` of `dylib`.
+pub struct RemoteProcMacro {
+    pub host: Lrc<ProcessServer>,
+    pub dylib: PathBuf,
+    pub symbol: String,
+    pub index: u32,
+}
+
+pub(crate) enum ExpandError {
+    Panic(PanicMessage),
+    Host(HostError),
+}
+
+impl ExpandError {
+    /// Aborts compilation after a proc macro failed, `what` being its kind.
+    pub(crate) fn report(self, ecx: &ExtCtxt<'_>, span: Span, what: &str) -> ! {
+        let mut err;
+        match self {
+            ExpandError::Panic(e) => {
+                err = ecx.struct_span_fatal(span, &format!("{} panicked", what));
+                if let Some(s) = e.as_str() {
+                    err.help(&format!("message: {}", s));
+                }
+            }
+            ExpandError::Host(e) => {
+                err = ecx.struct_span_fatal(span, &format!("{} failed to run", what));
+                err.note(&e.to_string());
+            }
+        }
+
+        err.emit();
+        FatalError.raise();
+    }
+}
+
+impl ProcMacroClient<fn(proc_macro::TokenStream) -> proc_macro::TokenStream> {
+    pub(crate) fn run(&self, ecx: &mut ExtCtxt<'_>, input: TokenStream)
+                      -> Result<TokenStream, ExpandError> {
+        let server = proc_macro_server::Rustc::new(ecx);
+        match self {
+            ProcMacroClient::Local(client) => {
+                client.run(&EXEC_STRATEGY, server, input).map_err(ExpandError::Panic)
+            }
+            ProcMacroClient::Remote(remote) => {
+                remote.host
+                    .expand1(&remote.dylib, &remote.symbol, remote.index, server, input)
+                    .map_err(ExpandError::Host)?
+                    .map_err(ExpandError::Panic)
+            }
+        }
+    }
+}
+
+impl ProcMacroClient<
+    fn(proc_macro::TokenStream, proc_macro::TokenStream) -> proc_macro::TokenStream,
+> {
+    pub(crate) fn run(&self, ecx: &mut ExtCtxt<'_>, input: TokenStream, input2: TokenStream)
+                      -> Result<TokenStream, ExpandError> {
+        let server = proc_macro_server::Rustc::new(ecx);
+        match self {
+            ProcMacroClient::Local(client) => {
+                client.run(&EXEC_STRATEGY, server, input, input2).map_err(ExpandError::Panic)
+            }
+            ProcMacroClient::Remote(remote) => {
+                remote.host
+                    .expand2(&remote.dylib, &remote.symbol, remote.index, server, input, input2)
+                    .map_err(ExpandError::Host)?
+                    .map_err(ExpandError::Panic)
+            }
+        }
+    }
+}
+
 pub struct AttrProcMacro {
-    pub client: proc_macro::bridge::client::Client<
+    pub client: ProcMacroClient<
         fn(proc_macro::TokenStream, proc_macro::TokenStream) -> proc_macro::TokenStream,
     >,
 }
@@ -21,25 +107,15 @@ impl base::AttrProcMacro for AttrProcMacro {
                    annotation: TokenStream,
                    annotated: TokenStream)
                    -> TokenStream {
-        let server = proc_macro_server::Rustc::new(ecx);
-        match self.client.run(&EXEC_STRATEGY, server, annotation, annotated) {
+        match self.client.run(ecx, annotation, annotated) {
             Ok(stream) => stream,
-            Err(e) => {
-                let msg = "custom attribute panicked";
-                let mut err = ecx.struct_span_fatal(span, msg);
-                if let Some(s) = e.as_str() {
-                    err.help(&format!("message: {}", s));
-                }
-
-                err.emit();
-                FatalError.raise();
-            }
+            Err(e) => e.report(ecx, span, "custom attribute"),
         }
     }
 }
 
 pub struct BangProcMacro {
-    pub client: proc_macro::bridge::client::Client<
+    pub client: ProcMacroClient<
         fn(proc_macro::TokenStream) -> proc_macro::TokenStream,
     >,
 }
@@ -50,19 +126,9 @@ impl base::ProcMacro for BangProcMacro {
                    span: Span,
                    input: TokenStream)
                    -> TokenStream {
-        let server = proc_macro_server::Rustc::new(ecx);
-        match self.client.run(&EXEC_STRATEGY, server, input) {
+        match self.client.run(ecx, input) {
             Ok(stream) => stream,
-            Err(e) => {
-                let msg = "proc macro panicked";
-                let mut err = ecx.struct_span_fatal(span, msg);
-                if let Some(s) = e.as_str() {
-                    err.help(&format!("message: {}", s));
-                }
-
-                err.emit();
-                FatalError.raise();
-            }
+            Err(e) => e.report(ecx, span, "proc macro"),
         }
     }
 }
@@ -10,6 +10,7 @@ use std::ascii;
 use std::ops::Bound;
 use syntax::ast;
 use syntax::ext::base::ExtCtxt;
+use syntax::mut_visit::MutVisitor;
 use syntax::parse::lexer::comments;
 use syntax::parse::{self, token, ParseSess};
 use syntax::tokenstream::{self, DelimSpan, IsJoint::*, TokenStream, TreeAndJoint};
@@ -282,6 +283,8 @@ impl ToInternal<errors::Level> for Level {
     }
 }
 
+pub struct FreeFunctions;
+
 #[derive(Clone)]
 pub struct TokenStreamIter {
     cursor: tokenstream::Cursor,
@@ -355,14 +358,16 @@ pub struct Literal {
     span: Span,
 }
 
-pub(crate) struct Rustc<'a> {
-    sess: &'a ParseSess,
+pub(crate) struct Rustc<'a, 'b> {
+    sess: &'b ParseSess,
+    ecx: &'a mut ExtCtxt<'b>,
     def_site: Span,
     call_site: Span,
+    mixed_site: Span,
 }
 
-impl<'a> Rustc<'a> {
-    pub fn new(cx: &'a ExtCtxt<'_>) -> Self {
+impl<'a, 'b> Rustc<'a, 'b> {
+    pub fn new(cx: &'a mut ExtCtxt<'b>) -> Self {
         // No way to determine def location for a proc macro right now, so use call location.
         let location = cx.current_expansion.mark.expn_info().unwrap().call_site;
         let to_span = |transparency| {
@@ -371,10 +376,15 @@ impl<'a> Rustc<'a> {
                     .apply_mark_with_transparency(cx.current_expansion.mark, transparency),
             )
         };
+        let def_site = to_span(Transparency::Opaque);
+        let call_site = to_span(Transparency::Transparent);
+        let mixed_site = to_span(Transparency::SemiTransparent);
         Rustc {
             sess: cx.parse_sess,
-            def_site: to_span(Transparency::Opaque),
-            call_site: to_span(Transparency::Transparent),
+            ecx: cx,
+            def_site,
+            call_site,
+            mixed_site,
         }
     }
 
@@ -386,7 +396,8 @@ impl<'a> Rustc<'a> {
     }
 }
 
-impl server::Types for Rustc<'_> {
+impl server::Types for Rustc<'_, '_> {
+    type FreeFunctions = FreeFunctions;
     type TokenStream = TokenStream;
     type TokenStreamBuilder = tokenstream::TokenStreamBuilder;
     type TokenStreamIter = TokenStreamIter;
@@ -400,7 +411,17 @@ impl server::Types for Rustc<'_> {
     type Span = Span;
 }
 
-impl server::TokenStream for Rustc<'_> {
+impl server::FreeFunctions for Rustc<'_, '_> {
+    fn track_env_var(&mut self, var: &str, value: Option<&str>) {
+        self.sess.env_depinfo.borrow_mut().insert((Symbol::intern(var), value.map(Symbol::intern)));
+    }
+
+    fn track_path(&mut self, path: &str) {
+        self.sess.file_depinfo.borrow_mut().insert(Symbol::intern(path));
+    }
+}
+
+impl server::TokenStream for Rustc<'_, '_> {
     fn new(&mut self) -> Self::TokenStream {
         TokenStream::empty()
     }
@@ -418,6 +439,43 @@ impl server::TokenStream for Rustc<'_> {
     fn to_string(&mut self, stream: &Self::TokenStream) -> String {
         stream.to_string()
     }
+    fn expand_expr(&mut self, stream: &Self::TokenStream) -> Result<Self::TokenStream, ()> {
+        // Parse the expression from our tokenstream.
+        let mut parser = parse::stream_to_parser(
+            self.sess,
+            stream.clone(),
+            Some("proc_macro expand expr"),
+        );
+        let expr = parser.parse_expr()
+            .and_then(|expr| parser.expect(&token::Eof).map(|_| expr));
+        let mut expr = expr.map_err(|mut err| err.emit())?;
+
+        // Perform eager expansion on the expression, like `concat!` does for its arguments.
+        expr.span = expr.span.apply_mark(self.ecx.current_expansion.mark);
+        self.ecx.expander().visit_expr(&mut expr);
+
+        // NOTE: For now, limit `expand_expr` to exclusively expand to literals.
+        // This may be relaxed in the future. We don't convert arbitrary
+        // expressions back to tokens, as that can't be done faithfully yet.
+        match expr.node {
+            ast::ExprKind::Lit(ref lit) => {
+                Ok(tokenstream::TokenTree::token(token::Literal(lit.token), lit.span).into())
+            }
+            ast::ExprKind::Unary(ast::UnOp::Neg, ref inner) => match inner.node {
+                ast::ExprKind::Lit(ref lit) => match lit.token.kind {
+                    token::Integer | token::Float => Ok(TokenStream::new(vec![
+                        // FIXME: the span of the `-` token is lost by the parser.
+                        tokenstream::TokenTree::token(token::BinOp(token::Minus), inner.span)
+                            .into(),
+                        tokenstream::TokenTree::token(token::Literal(lit.token), lit.span).into(),
+                    ])),
+                    _ => Err(()),
+                },
+                _ => Err(()),
+            },
+            _ => Err(()),
+        }
+    }
     fn from_token_tree(
         &mut self,
         tree: TokenTree<Self::Group, Self::Punct, Self::Ident, Self::Literal>,
@@ -432,7 +490,7 @@ impl server::TokenStream for Rustc<'_> {
     }
 }
 
-impl server::TokenStreamBuilder for Rustc<'_> {
+impl server::TokenStreamBuilder for Rustc<'_, '_> {
     fn new(&mut self) -> Self::TokenStreamBuilder {
         tokenstream::TokenStreamBuilder::new()
     }
@@ -444,7 +502,7 @@ impl server::TokenStreamBuilder for Rustc<'_> {
     }
 }
 
-impl server::TokenStreamIter for Rustc<'_> {
+impl server::TokenStreamIter for Rustc<'_, '_> {
     fn next(
         &mut self,
         iter: &mut Self::TokenStreamIter,
@@ -471,7 +529,7 @@ impl server::TokenStreamIter for Rustc<'_> {
     }
 }
 
-impl server::Group for Rustc<'_> {
+impl server::Group for Rustc<'_, '_> {
     fn new(&mut self, delimiter: Delimiter, stream: Self::TokenStream) -> Self::Group {
         Group {
             delimiter,
@@ -499,7 +557,7 @@ impl server::Group for Rustc<'_> {
     }
 }
 
-impl server::Punct for Rustc<'_> {
+impl server::Punct for Rustc<'_, '_> {
     fn new(&mut self, ch: char, spacing: Spacing) -> Self::Punct {
         Punct::new(ch, spacing == Spacing::Joint, server::Span::call_site(self))
     }
@@ -521,7 +579,7 @@ impl server::Punct for Rustc<'_> {
     }
 }
 
-impl server::Ident for Rustc<'_> {
+impl server::Ident for Rustc<'_, '_> {
     fn new(&mut self, string: &str, span: Self::Span, is_raw: bool) -> Self::Ident {
         Ident::new(Symbol::intern(string), is_raw, span)
     }
@@ -533,11 +591,56 @@ impl server::Ident for Rustc<'_> {
     }
 }
 
-impl server::Literal for Rustc<'_> {
+impl server::Literal for Rustc<'_, '_> {
     // FIXME(eddyb) `Literal` should not expose internal `Debug` impls.
     fn debug(&mut self, literal: &Self::Literal) -> String {
         format!("{:?}", literal)
     }
+    fn from_str(&mut self, s: &str) -> Result<Self::Literal, ()> {
+        let name = FileName::proc_macro_source_code(s);
+        let mut parser = parse::new_parser_from_source_str(self.sess, name, s.to_owned());
+
+        let first_span = parser.token.span.data();
+        let minus_present = parser.eat(&token::BinOp(token::Minus));
+
+        let lit_span = parser.token.span.data();
+        let mut lit = match parser.token.kind {
+            token::Literal(lit) => lit,
+            _ => return Err(()),
+        };
+
+        // Check no comment or whitespace surrounding the (possibly negative)
+        // literal, or more tokens after it.
+        if (lit_span.hi.0 - first_span.lo.0) as usize != s.len() {
+            return Err(());
+        }
+
+        if minus_present {
+            // If minus is present, check no comment or whitespace in between it
+            // and the literal token.
+            if first_span.hi.0 != lit_span.lo.0 {
+                return Err(());
+            }
+
+            // Check literal is a kind we allow to be negated in a proc macro token.
+            match lit.kind {
+                token::Bool
+                | token::Byte
+                | token::Char
+                | token::Str
+                | token::StrRaw(_)
+                | token::ByteStr
+                | token::ByteStrRaw(_)
+                | token::Err => return Err(()),
+                token::Integer | token::Float => {}
+            }
+
+            // Synthesize a new symbol that includes the minus sign.
+            lit.symbol = Symbol::intern(&s[..1 + lit.symbol.as_str().len()]);
+        }
+
+        Ok(Literal { lit, span: self.call_site })
+    }
     fn integer(&mut self, n: &str) -> Self::Literal {
         self.lit(token::Integer, Symbol::intern(n), None)
     }
@@ -618,7 +721,7 @@ impl server::Literal for Rustc<'_> {
     }
 }
 
-impl server::SourceFile for Rustc<'_> {
+impl server::SourceFile for Rustc<'_, '_> {
     fn eq(&mut self, file1: &Self::SourceFile, file2: &Self::SourceFile) -> bool {
         Lrc::ptr_eq(file1, file2)
     }
@@ -636,7 +739,7 @@ impl server::SourceFile for Rustc<'_> {
     }
 }
 
-impl server::MultiSpan for Rustc<'_> {
+impl server::MultiSpan for Rustc<'_, '_> {
     fn new(&mut self) -> Self::MultiSpan {
         vec![]
     }
@@ -645,7 +748,7 @@ impl server::MultiSpan for Rustc<'_> {
     }
 }
 
-impl server::Diagnostic for Rustc<'_> {
+impl server::Diagnostic for Rustc<'_, '_> {
     fn new(&mut self, level: Level, msg: &str, spans: Self::MultiSpan) -> Self::Diagnostic {
         let mut diag = Diagnostic::new(level.to_internal(), msg);
         diag.set_span(MultiSpan::from_spans(spans));
@@ -665,7 +768,7 @@ impl server::Diagnostic for Rustc<'_> {
     }
 }
 
-impl server::Span for Rustc<'_> {
+impl server::Span for Rustc<'_, '_> {
     fn debug(&mut self, span: Self::Span) -> String {
         format!("{:?} bytes({}..{})", span.ctxt(), span.lo().0, span.hi().0)
     }
@@ -675,6 +778,9 @@ impl server::Span for Rustc<'_> {
     fn call_site(&mut self) -> Self::Span {
         self.call_site
     }
+    fn mixed_site(&mut self) -> Self::Span {
+        self.mixed_site
+    }
     fn source_file(&mut self, span: Self::Span) -> Self::SourceFile {
         self.sess.source_map().lookup_char_pos(span.lo()).file
     }
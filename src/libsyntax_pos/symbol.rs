@@ -146,6 +146,7 @@ symbols! {
         associated_type_bounds,
         associated_type_defaults,
         associated_types,
+        assume_init,
         async_await,
         async_closure,
         attr,
@@ -198,8 +199,11 @@ symbols! {
         const_fn,
         const_fn_union,
         const_generics,
+        const_heap,
+        const_if_match,
         const_indexing,
         const_let,
+        const_loop,
         const_panic,
         const_raw_ptr_deref,
         const_raw_ptr_to_usize_cast,
@@ -409,6 +413,9 @@ symbols! {
         match_beginning_vert,
         match_default_bindings,
         may_dangle,
+        maybe_uninit,
+        MaybeUninit,
+        mem,
         member_constraints,
         message,
         meta,
@@ -560,6 +567,7 @@ symbols! {
         rustc_attrs,
         rustc_builtin_macro,
         rustc_clean,
+        rustc_const_heap,
         rustc_const_unstable,
         rustc_conversion_suggestion,
         rustc_copy_clone_marker,
@@ -691,6 +699,8 @@ symbols! {
         underscore_imports,
         underscore_lifetimes,
         uniform_paths,
+        uninit,
+        uninitialized,
         universal_impl_trait,
         unmarked_api,
         unreachable_code,
@@ -722,6 +732,7 @@ symbols! {
         windows,
         windows_subsystem,
         Yield,
+        zeroed,
     }
 }
 
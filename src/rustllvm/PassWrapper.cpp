@@ -496,6 +496,7 @@ static TargetMachine::CodeGenFileType fromRust(LLVMRustFileType Type) {
 extern "C" LLVMRustResult
 LLVMRustWriteOutputFile(LLVMTargetMachineRef Target, LLVMPassManagerRef PMR,
                         LLVMModuleRef M, const char *Path,
+                        const char *SplitDwarfFile, const char *DwoPath,
                         LLVMRustFileType RustFileType) {
   llvm::legacy::PassManager *PM = unwrap<llvm::legacy::PassManager>(PMR);
   auto FileType = fromRust(RustFileType);
@@ -511,12 +512,35 @@ LLVMRustWriteOutputFile(LLVMTargetMachineRef Target, LLVMPassManagerRef PMR,
   }
 
 #if LLVM_VERSION_GE(7, 0)
+  // The name of the DWARF object recorded in the skeleton units. When no
+  // separate `.dwo` stream is given the DWARF object is emitted into
+  // excluded sections of the object file itself.
+  if (SplitDwarfFile)
+    unwrap(Target)->Options.MCOptions.SplitDwarfFile = SplitDwarfFile;
+
+  // Like `OS`, the DWARF object streams must outlive `PM` below.
+  std::unique_ptr<raw_fd_ostream> DOS;
+  std::unique_ptr<buffer_ostream> DBOS;
+  if (DwoPath) {
+    DOS = llvm::make_unique<raw_fd_ostream>(DwoPath, EC, sys::fs::F_None);
+    if (EC) {
+      LLVMRustSetLastError(EC.message().c_str());
+      return LLVMRustResult::Failure;
+    }
+    DBOS = llvm::make_unique<buffer_ostream>(*DOS);
+  }
+
   buffer_ostream BOS(OS);
-  unwrap(Target)->addPassesToEmitFile(*PM, BOS, nullptr, FileType, false);
+  unwrap(Target)->addPassesToEmitFile(*PM, BOS, DBOS.get(), FileType, false);
+  PM->run(*unwrap(M));
 #else
+  if (SplitDwarfFile || DwoPath) {
+    LLVMRustSetLastError("split DWARF requires LLVM 7 or later");
+    return LLVMRustResult::Failure;
+  }
   unwrap(Target)->addPassesToEmitFile(*PM, OS, FileType, false);
-#endif
   PM->run(*unwrap(M));
+#endif
 
   // Apparently `addPassesToEmitFile` adds a pointer to our on-the-stack output
   // stream (OS), so the only real safe place to delete this is here? Don't we
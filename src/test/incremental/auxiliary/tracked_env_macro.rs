@@ -0,0 +1,15 @@
+// force-host
+// no-prefer-dynamic
+
+#![crate_type = "proc-macro"]
+#![feature(proc_macro_tracked_env)]
+
+extern crate proc_macro;
+
+use proc_macro::{tracked_env, TokenStream};
+
+#[proc_macro]
+pub fn tracked_value(_: TokenStream) -> TokenStream {
+    let value = tracked_env::var("INCR_TRACKED_ENV").unwrap_or_default();
+    format!("{:?}", value).parse().unwrap()
+}
@@ -0,0 +1,24 @@
+// Test that changing an environment variable read by a proc macro through
+// `proc_macro::tracked_env` invalidates the cache, while keeping its value
+// doesn't.
+
+// aux-build:tracked_env_macro.rs
+// revisions:rpass1 rpass2 rpass3
+// compile-flags: -Z query-dep-graph
+
+//[rpass1] rustc-env:INCR_TRACKED_ENV=one
+//[rpass2] rustc-env:INCR_TRACKED_ENV=two
+//[rpass3] rustc-env:INCR_TRACKED_ENV=two
+
+#![feature(rustc_attrs)]
+
+#![rustc_partition_codegened(module="tracked_env_var", cfg="rpass2")]
+#![rustc_partition_reused(module="tracked_env_var", cfg="rpass3")]
+
+#[macro_use]
+extern crate tracked_env_macro;
+
+pub fn main() {
+    let value: &str = tracked_value!();
+    assert!(value == "one" || value == "two");
+}
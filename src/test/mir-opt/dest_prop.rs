@@ -0,0 +1,82 @@
+// compile-flags: -C overflow-checks=off
+
+pub struct Big {
+    a: [u64; 8],
+    b: u32,
+}
+
+fn observe(_: &Big) {}
+
+// The local `big` is built in place of the return value.
+fn make(b: u32) -> Big {
+    let big = Big { a: [0; 8], b };
+    big
+}
+
+// Borrowed locals are left alone, `observe` could hold onto the reference.
+fn borrowed(b: u32) -> Big {
+    let big = Big { a: [0; 8], b };
+    observe(&big);
+    big
+}
+
+// The result of the addition is never read.
+fn dead(b: u32) {
+    let _ = b + 1;
+}
+
+fn main() {
+    make(0);
+    borrowed(1);
+    dead(2);
+}
+
+// END RUST SOURCE
+// START rustc.make.DestinationPropagation.before.mir
+// bb0: {
+//     ...
+//     (_2.0: [u64; 8]) = move _3;
+//     (_2.1: u32) = move _4;
+//     ...
+//     _0 = move _2;
+//     StorageDead(_2);
+//     return;
+// }
+// END rustc.make.DestinationPropagation.before.mir
+// START rustc.make.DestinationPropagation.after.mir
+// bb0: {
+//     ...
+//     (_0.0: [u64; 8]) = move _3;
+//     (_0.1: u32) = move _4;
+//     ...
+//     nop;
+//     nop;
+//     return;
+// }
+// END rustc.make.DestinationPropagation.after.mir
+// START rustc.borrowed.DestinationPropagation.after.mir
+// bb1: {
+//     ...
+//     _0 = move _2;
+//     StorageDead(_2);
+//     return;
+// }
+// END rustc.borrowed.DestinationPropagation.after.mir
+// START rustc.dead.DestinationPropagation.before.mir
+// bb0: {
+//     ...
+//     _3 = _1;
+//     _2 = Add(move _3, const 1u32);
+//     StorageDead(_3);
+//     ...
+// }
+// END rustc.dead.DestinationPropagation.before.mir
+// START rustc.dead.DestinationPropagation.after.mir
+// bb0: {
+//     ...
+//     _3 = _1;
+//     nop;
+//     StorageDead(_3);
+//     ...
+// }
+// END rustc.dead.DestinationPropagation.after.mir
@@ -0,0 +1,11 @@
+#[no_mangle]
+#[inline(never)]
+pub extern "C" fn hey_i_get_compiled() -> u32 {
+    42
+}
+
+#[allow(dead_code)]
+fn main() {
+    assert_eq!(hey_i_get_compiled(), 42);
+    println!("Hello, world!");
+}
@@ -0,0 +1,7 @@
+pub fn foo() -> u32 {
+    1
+}
+
+pub fn bar() -> u32 {
+    foo() + 1
+}
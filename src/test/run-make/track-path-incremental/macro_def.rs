@@ -0,0 +1,14 @@
+#![feature(proc_macro_tracked_path)]
+#![crate_type = "proc-macro"]
+
+extern crate proc_macro;
+use proc_macro::*;
+use std::{env, fs};
+
+#[proc_macro]
+pub fn tracked_contents(_: TokenStream) -> TokenStream {
+    let path = env::var("TRACKED_FILE").unwrap();
+    tracked_path::path(&path);
+    let contents = fs::read_to_string(&path).unwrap();
+    format!("{:?}", contents.trim()).parse().unwrap()
+}
@@ -0,0 +1,6 @@
+#[macro_use]
+extern crate macro_def;
+
+fn main() {
+    println!("{}", tracked_contents!());
+}
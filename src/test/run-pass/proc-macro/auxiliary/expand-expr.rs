@@ -0,0 +1,31 @@
+// force-host
+// no-prefer-dynamic
+
+#![feature(proc_macro_expand)]
+#![crate_type = "proc-macro"]
+
+extern crate proc_macro;
+use proc_macro::*;
+
+fn expand(expr: &str) -> String {
+    let stream: TokenStream = expr.parse().unwrap();
+    stream.expand_expr().unwrap().to_string()
+}
+
+#[proc_macro]
+pub fn check(_: TokenStream) -> TokenStream {
+    assert_eq!(expand("\"a\""), "\"a\"");
+    assert_eq!(expand("-1"), "-1".parse::<TokenStream>().unwrap().to_string());
+    assert_eq!(expand("concat!(\"a\", 1, 'b')"), "\"a1b\"");
+    assert_eq!(expand("stringify!(x + y)"), "\"x + y\"");
+    assert_eq!(expand("concat!(stringify!(a), \"b\")"), "\"ab\"");
+
+    // Only expressions expanding to literals are supported.
+    let stream: TokenStream = "vec![1, 2]".parse().unwrap();
+    assert!(stream.expand_expr().is_err());
+    let stream: TokenStream = "x".parse().unwrap();
+    assert!(stream.expand_expr().is_err());
+
+    let stream: TokenStream = "concat!(\"hello\", \" world\")".parse().unwrap();
+    stream.expand_expr().unwrap()
+}
@@ -0,0 +1,20 @@
+// force-host
+// no-prefer-dynamic
+
+#![feature(proc_macro_literal_from_str)]
+#![crate_type = "proc-macro"]
+
+extern crate proc_macro;
+use proc_macro::*;
+
+#[proc_macro]
+pub fn check(_: TokenStream) -> TokenStream {
+    for ok in &["1", "-1", "1u8", "-2.5f32", "'a'", "\"a\\nb\"", "r#\"raw\"#", "b'x'", "b\"x\""] {
+        let lit: Literal = ok.parse().unwrap();
+        assert_eq!(lit.to_string(), *ok);
+    }
+    for err in &["", " 1", "1 ", "1 2", "/* */ 1", "- 1", "-'a'", "-\"a\"", "a", "(1)"] {
+        assert!(err.parse::<Literal>().is_err(), "`{}` parsed as a literal", err);
+    }
+    TokenStream::new()
+}
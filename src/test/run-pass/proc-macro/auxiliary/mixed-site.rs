@@ -0,0 +1,23 @@
+// force-host
+// no-prefer-dynamic
+
+#![feature(proc_macro_mixed_site)]
+#![crate_type = "proc-macro"]
+
+extern crate proc_macro;
+use proc_macro::*;
+
+#[proc_macro]
+pub fn add_one(input: TokenStream) -> TokenStream {
+    let ident = |name| TokenTree::from(Ident::new(name, Span::mixed_site()));
+    let punct = |ch| TokenTree::from(Punct::new(ch, Spacing::Alone));
+    // Expands to `{ let one = 1; (<input>) + one * ONE }`: the local `one` is hidden
+    // from `input`, like in `macro_rules`, while the item `ONE` is looked up at the
+    // call site.
+    let body: Vec<TokenTree> = vec![
+        ident("let"), ident("one"), punct('='), Literal::i32_unsuffixed(1).into(), punct(';'),
+        Group::new(Delimiter::Parenthesis, input).into(),
+        punct('+'), ident("one"), punct('*'), ident("ONE"),
+    ];
+    Group::new(Delimiter::Brace, body.into_iter().collect()).into()
+}
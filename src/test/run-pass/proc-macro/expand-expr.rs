@@ -0,0 +1,7 @@
+// aux-build:expand-expr.rs
+
+extern crate expand_expr;
+
+fn main() {
+    assert_eq!(expand_expr::check!(), "hello world");
+}
@@ -0,0 +1,7 @@
+// aux-build:literal-from-str.rs
+
+extern crate literal_from_str;
+
+literal_from_str::check!();
+
+fn main() {}
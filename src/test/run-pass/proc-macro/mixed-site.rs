@@ -0,0 +1,12 @@
+// aux-build:mixed-site.rs
+
+#![feature(proc_macro_hygiene)]
+
+extern crate mixed_site;
+
+const ONE: i32 = 1;
+
+fn main() {
+    let one = 10;
+    assert_eq!(mixed_site::add_one!(one), 11);
+}
@@ -72,6 +72,12 @@ enum NicheFilledEnumWithAbsentVariant {
     C,
 }
 
+enum NicheFilledMultipleDataful {
+    A(bool, [u8; 4]),
+    B([u8; 4]),
+    C,
+}
+
 pub fn main() {
     assert_eq!(size_of::<u8>(), 1 as usize);
     assert_eq!(size_of::<u32>(), 4 as usize);
@@ -109,6 +115,10 @@ pub fn main() {
                size_of::<EnumWithMaybeUninhabitedVariant<()>>());
     assert_eq!(size_of::<NicheFilledEnumWithAbsentVariant>(), size_of::<&'static ()>());
 
+    // `B` is placed after the niche in `A`, so no separate tag is needed.
+    assert_eq!(size_of::<NicheFilledMultipleDataful>(), 5);
+    assert_eq!(size_of::<Option<NicheFilledMultipleDataful>>(), 5);
+
     assert_eq!(size_of::<Option<Option<(bool, &())>>>(), size_of::<(bool, &())>());
     assert_eq!(size_of::<Option<Option<(&(), bool)>>>(), size_of::<(bool, &())>());
 }
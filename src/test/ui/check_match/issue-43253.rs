@@ -2,6 +2,7 @@
 
 #![feature(exclusive_range_pattern)]
 #![warn(unreachable_patterns)]
+#![allow(overlapping_patterns)]
 
 fn main() {
     // These cases should generate no warning.
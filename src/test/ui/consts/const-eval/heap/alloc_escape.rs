@@ -0,0 +1,12 @@
+// Test that heap allocations escaping into the final value of a constant, other
+// than behind a shared reference, are rejected.
+
+#![feature(const_heap)]
+
+const BOX: Box<i32> = Box::new(42);
+//~^ ERROR heap allocations in the final value of a constant must be behind a shared reference
+
+const VEC: Vec<u32> = Vec::with_capacity(3);
+//~^ ERROR heap allocations in the final value of a constant must be behind a shared reference
+
+fn main() {}
@@ -0,0 +1,25 @@
+#![feature(core_intrinsics)]
+use std::intrinsics;
+
+const RAW: *const i32 = unsafe { intrinsics::const_allocate(4, 4) as *const i32 };
+//~^ ERROR heap allocations in the final value of a constant must be behind a shared reference
+
+static BAD_ALIGN: () = unsafe {
+    intrinsics::const_allocate(4, 3);
+    //~^ ERROR could not evaluate static initializer
+};
+
+static DOUBLE_FREE: () = unsafe {
+    let ptr = intrinsics::const_allocate(4, 4);
+    intrinsics::const_deallocate(ptr, 4, 4);
+    intrinsics::const_deallocate(ptr, 4, 4);
+    //~^ ERROR could not evaluate static initializer
+};
+
+static WRONG_SIZE: () = unsafe {
+    let ptr = intrinsics::const_allocate(4, 4);
+    intrinsics::const_deallocate(ptr, 8, 4);
+    //~^ ERROR could not evaluate static initializer
+};
+
+fn main() {}
@@ -0,0 +1,15 @@
+// run-pass
+// compile-flags: -Zunleash-the-miri-inside-of-you
+#![feature(core_intrinsics)]
+#![feature(const_raw_ptr_deref)]
+use std::intrinsics;
+
+const FOO: &i32 = unsafe {
+    let ptr = intrinsics::const_allocate(4, 4) as *mut i32;
+    *ptr = 42; //~ WARN skipping const checks
+    &*ptr
+};
+
+fn main() {
+    assert_eq!(*FOO, 42);
+}
@@ -0,0 +1,13 @@
+// run-pass
+#![feature(core_intrinsics)]
+use std::intrinsics;
+
+const FOO: i32 = unsafe {
+    let ptr = intrinsics::const_allocate(4, 4);
+    intrinsics::const_deallocate(ptr, 4, 4);
+    3
+};
+
+fn main() {
+    assert_eq!(FOO, 3);
+}
@@ -0,0 +1,11 @@
+// Test that heap allocations not freed before the end of evaluation are
+// rejected if they would escape into the final value of a constant.
+
+#![feature(const_heap)]
+
+use std::alloc::{alloc, Layout};
+
+const RAW: *mut u8 = unsafe { alloc(Layout::from_size_align_unchecked(4, 4)) };
+//~^ ERROR heap allocations in the final value of a constant must be behind a shared reference
+
+fn main() {}
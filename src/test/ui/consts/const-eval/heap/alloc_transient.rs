@@ -0,0 +1,22 @@
+// run-pass
+
+// Test that the `alloc` functions can be evaluated in a constant, as long as
+// the memory is deallocated again before evaluation finishes.
+
+#![feature(const_heap)]
+
+use std::alloc::{alloc, alloc_zeroed, dealloc, realloc, Layout};
+
+const FOO: i32 = unsafe {
+    let layout = Layout::from_size_align_unchecked(4, 4);
+    let ptr = alloc(layout);
+    dealloc(ptr, layout);
+    let ptr = alloc_zeroed(layout);
+    let ptr = realloc(ptr, layout, 8);
+    dealloc(ptr, Layout::from_size_align_unchecked(8, 4));
+    3
+};
+
+fn main() {
+    assert_eq!(FOO, 3);
+}
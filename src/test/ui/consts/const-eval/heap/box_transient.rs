@@ -0,0 +1,15 @@
+// run-pass
+
+// Test that `Box::new` can be evaluated in a constant, as long as the box is
+// freed again before evaluation finishes.
+
+#![feature(const_heap)]
+
+const FOO: i32 = {
+    let b = Box::new(42);
+    *b
+};
+
+fn main() {
+    assert_eq!(FOO, 42);
+}
@@ -0,0 +1,17 @@
+// run-pass
+
+// Test that `Vec::with_capacity` can be evaluated in a constant, as long as the
+// vector is dropped again before evaluation finishes.
+
+#![feature(const_heap)]
+
+const FOO: i32 = {
+    let _v: Vec<u32> = Vec::with_capacity(3);
+    let _empty: Vec<u32> = Vec::with_capacity(0);
+    let _zst: Vec<()> = Vec::with_capacity(3);
+    42
+};
+
+fn main() {
+    assert_eq!(FOO, 42);
+}
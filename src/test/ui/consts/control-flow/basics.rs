@@ -0,0 +1,79 @@
+// Test that `if`, `match`, `while`, `loop` and short-circuiting operators
+// evaluate correctly in constants and `const fn`.
+
+// run-pass
+
+#![feature(const_if_match, const_loop)]
+
+const fn abs(x: i32) -> i32 {
+    if x < 0 { -x } else { x }
+}
+
+const fn checked_div(a: u32, b: u32) -> Option<u32> {
+    match b {
+        0 => None,
+        _ => Some(a / b),
+    }
+}
+
+const fn unwrap_or(opt: Option<u32>, default: u32) -> u32 {
+    match opt {
+        Some(x) => x,
+        None => default,
+    }
+}
+
+const fn gcd(mut a: u32, mut b: u32) -> u32 {
+    while b != 0 {
+        let t = b;
+        b = a % b;
+        a = t;
+    }
+    a
+}
+
+const fn first_bit_set(x: u64) -> u32 {
+    let mut i = 0;
+    loop {
+        if i == 64 || x & (1 << i) != 0 {
+            break;
+        }
+        i += 1;
+    }
+    i
+}
+
+const fn table() -> [u8; 8] {
+    let mut out = [0; 8];
+    let mut i = 0;
+    while i < 8 {
+        out[i] = (i * i) as u8;
+        i += 1;
+    }
+    out
+}
+
+const fn in_bounds(s: &[u8; 4], i: usize) -> bool {
+    // The right-hand side would index out of bounds if evaluated eagerly.
+    i < 4 && s[i] != 0
+}
+
+const ABS: i32 = abs(-7);
+const DIV: Option<u32> = checked_div(10, 0);
+const OR: u32 = unwrap_or(checked_div(10, 2), 0);
+const GCD: u32 = gcd(1071, 462);
+const BIT: u32 = first_bit_set(0b1000);
+const NONE: u32 = first_bit_set(0);
+const SQUARES: [u8; 8] = table();
+const SHORT: bool = in_bounds(&[1, 2, 3, 4], 7);
+
+fn main() {
+    assert_eq!(ABS, 7);
+    assert_eq!(DIV, None);
+    assert_eq!(OR, 5);
+    assert_eq!(GCD, 21);
+    assert_eq!(BIT, 3);
+    assert_eq!(NONE, 64);
+    assert_eq!(SQUARES, [0, 1, 4, 9, 16, 25, 36, 49]);
+    assert!(!SHORT);
+}
@@ -0,0 +1,18 @@
+// Test that a value moved on only one path is still dropped on the others.
+
+#![feature(const_if_match)]
+
+struct D;
+
+impl Drop for D {
+    fn drop(&mut self) {}
+}
+
+const fn keep_if(b: bool, d: D) -> Option<D> {
+//~^ ERROR destructors cannot be evaluated at compile-time
+    if b { Some(d) } else { None }
+}
+
+fn main() {
+    let _ = keep_if(true, D);
+}
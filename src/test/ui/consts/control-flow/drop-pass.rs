@@ -0,0 +1,45 @@
+// Test that values moved out on every path are not considered dropped, and
+// that unwind paths are not const-checked, with branches and loops allowed.
+
+// check-pass
+
+#![feature(const_if_match, const_loop)]
+
+struct D;
+
+impl Drop for D {
+    fn drop(&mut self) {}
+}
+
+const fn wrap(d: D) -> Option<D> {
+    Some(d)
+}
+
+const fn id(d: D) -> D {
+    d
+}
+
+// `a` is live across the call to `id`, so it is dropped on its unwind path.
+const fn pair(a: D, b: D) -> (D, D) {
+    let b = id(b);
+    (a, b)
+}
+
+const fn wrap_either(b: bool, d: D) -> Option<D> {
+    if b { Some(d) } else { wrap(d) }
+}
+
+const fn wrap_last(n: u32, d: D) -> Option<D> {
+    let mut i = 0;
+    while i < n {
+        i += 1;
+    }
+    Some(d)
+}
+
+fn main() {
+    let _ = wrap(D);
+    let _ = pair(D, D);
+    let _ = wrap_either(true, D);
+    let _ = wrap_last(3, D);
+}
@@ -0,0 +1,27 @@
+// Test that a loop which never repeats its state is stopped by the step limit,
+// which is off unless `-Z const-eval-step-limit` is passed.
+
+// compile-flags: -Z const-eval-step-limit=100
+
+#![feature(const_if_match, const_loop)]
+
+fn main() {
+    let _ = [(); {
+        let mut n: usize = 0;
+        while n < 1000 { n += 1; }
+        //~^ ERROR evaluation of constant value failed
+        0
+    }];
+
+    // The limit applies to each constant separately.
+    let _ = [(); {
+        let mut n: usize = 0;
+        while n < 10 { n += 1; }
+        0
+    }];
+    let _ = [(); {
+        let mut n: usize = 0;
+        while n < 10 { n += 1; }
+        0
+    }];
+}
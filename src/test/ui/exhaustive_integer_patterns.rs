@@ -2,6 +2,7 @@
 #![feature(exclusive_range_pattern)]
 
 #![deny(unreachable_patterns)]
+#![allow(overlapping_patterns)]
 
 use std::{char, u8, u16, u32, u64, u128, i8, i16, i32, i64, i128};
 
@@ -0,0 +1,10 @@
+// Test that `Box::new` in constants requires `#![feature(const_heap)]`.
+
+const X: i32 = {
+    let b = Box::new(1);
+    //~^ ERROR heap allocations in constants are unstable
+    //~| ERROR destructors cannot be evaluated at compile-time
+    *b
+};
+
+fn main() {}
@@ -0,0 +1,8 @@
+// Test that branching in constants requires `#![feature(const_if_match)]`.
+
+#![feature(const_loop)]
+
+const X: i32 = if true { 1 } else { 2 };
+//~^ ERROR `if`, `match`, `&&` and `||` are unstable in constants
+
+fn main() {}
@@ -0,0 +1,12 @@
+// Test that looping in constants requires `#![feature(const_loop)]`.
+
+#![feature(const_if_match)]
+
+const X: u32 = {
+    let mut n = 0;
+    loop { n += 1; if n == 10 { break; } }
+    //~^ ERROR `loop` and `while` are unstable in constants
+    n
+};
+
+fn main() {}
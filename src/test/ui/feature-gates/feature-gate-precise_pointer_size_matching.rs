@@ -1,4 +1,5 @@
 #![feature(exclusive_range_pattern)]
+#![allow(overlapping_patterns)]
 
 use std::usize::MAX;
 
@@ -0,0 +1,69 @@
+// Test that enums whose niche is shared with other data-carrying variants
+// round-trip every variant correctly, both at runtime and in constants.
+
+// run-pass
+
+#![allow(dead_code)]
+
+use std::mem::size_of;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Packet {
+    Data(bool, [u8; 4]),
+    Raw([u8; 4]),
+    Empty,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Message {
+    A(u32, bool),
+    B(u32),
+    C,
+}
+
+const PACKETS: [Packet; 4] = [
+    Packet::Data(true, [1, 2, 3, 4]),
+    Packet::Raw([0xff, 0xfe, 0xfd, 0xfc]),
+    Packet::Empty,
+    Packet::Data(false, [0; 4]),
+];
+
+#[inline(never)]
+fn opaque<T>(x: T) -> T {
+    x
+}
+
+#[inline(never)]
+fn describe(p: Packet) -> u32 {
+    match p {
+        Packet::Data(flag, bytes) => flag as u32 + bytes.iter().map(|&b| b as u32).sum::<u32>(),
+        Packet::Raw(bytes) => 1000 + bytes[0] as u32,
+        Packet::Empty => 2000,
+    }
+}
+
+fn main() {
+    assert_eq!(size_of::<Packet>(), 5);
+    assert_eq!(size_of::<Option<Packet>>(), 5);
+
+    assert_eq!(describe(PACKETS[0]), 11);
+    assert_eq!(describe(PACKETS[1]), 1255);
+    assert_eq!(describe(PACKETS[2]), 2000);
+    assert_eq!(describe(PACKETS[3]), 0);
+
+    let mut p = Packet::Empty;
+    for (i, &expected) in PACKETS.iter().enumerate() {
+        p = if i % 2 == 0 { expected } else { opaque(expected) };
+        assert_eq!(p, expected);
+    }
+    assert_eq!(Some(p), Some(Packet::Data(false, [0; 4])));
+
+    let messages = [Message::A(7, true), Message::B(u32::max_value()), Message::C];
+    for m in messages.iter() {
+        match *m {
+            Message::A(x, b) => assert!(x == 7 && b),
+            Message::B(x) => assert_eq!(x, u32::max_value()),
+            Message::C => {}
+        }
+    }
+}
@@ -0,0 +1,5 @@
+#![crate_type = "lib"]
+
+extern {
+    pub fn extern_fn(x: u8);
+}
@@ -0,0 +1,102 @@
+// check-pass
+// aux-build:external_extern_fn.rs
+#![crate_type = "lib"]
+#![allow(dead_code)]
+
+extern crate external_extern_fn;
+
+extern {
+    fn clash(x: u8);
+    fn no_clash(x: u8);
+}
+
+fn redeclared_different_signature() {
+    extern {
+        fn clash(x: u64); //~ WARN `clash` redeclared with a different signature
+    }
+}
+
+fn redeclared_same_signature() {
+    extern {
+        fn no_clash(x: u8);
+    }
+}
+
+fn clash_with_upstream() {
+    extern {
+        fn extern_fn(x: u64); //~ WARN `extern_fn` redeclared with a different signature
+    }
+}
+
+fn link_name_clash() {
+    extern {
+        fn extern_link_name(x: u32);
+    }
+    extern {
+        #[link_name = "extern_link_name"]
+        fn some_new_name(x: i16); //~ WARN `extern_link_name` redeclared with a different signature
+    }
+}
+
+mod a {
+    extern {
+        static CLASHING_STATIC: u32;
+    }
+}
+
+mod b {
+    extern {
+        static CLASHING_STATIC: u64; //~ WARN `CLASHING_STATIC` redeclared with a different type
+    }
+}
+
+mod ffi_equivalent {
+    use std::ptr::NonNull;
+
+    #[repr(transparent)]
+    pub struct Wrapper(usize);
+
+    #[repr(C)]
+    pub struct Point { x: i32, y: i32 }
+
+    mod a {
+        extern {
+            fn transparent(x: usize);
+            fn nullable(x: *const u8);
+            fn reference(x: &u8);
+            fn repr_c(p: super::Point);
+        }
+    }
+
+    mod b {
+        #[repr(C)]
+        pub struct OtherPoint { x: i32, y: i32 }
+
+        extern {
+            fn transparent(x: super::Wrapper);
+            fn nullable(x: Option<super::NonNull<u8>>);
+            fn reference(x: *const u8);
+            fn repr_c(p: OtherPoint);
+        }
+    }
+}
+
+mod mismatched_structs {
+    #[repr(C)]
+    pub struct Narrow { x: i32 }
+
+    #[repr(C)]
+    pub struct Wide { x: i64 }
+
+    mod a {
+        extern {
+            fn take_struct(s: super::Narrow);
+        }
+    }
+
+    mod b {
+        extern {
+            fn take_struct(s: super::Wide); //~ WARN redeclared with a different signature
+        }
+    }
+}
@@ -0,0 +1,48 @@
+#![deny(improper_ctypes_definitions)]
+#![allow(dead_code, unused_variables)]
+
+#[repr(C)]
+pub struct Callbacks {
+    pub on_event: extern "C" fn(u32),
+    pub on_name: extern "C" fn(&str), //~ ERROR: uses type `str`
+    pub rust_callback: fn(String),
+    pub maybe: Option<extern "C" fn() -> char>, //~ ERROR: uses type `char`
+}
+
+pub struct NotReprC {
+    pub on_name: extern "C" fn(&str),
+}
+
+#[repr(C)]
+pub struct Generic<T> {
+    pub f: extern "C" fn(T),
+}
+
+pub extern "C" fn str_type(p: &str) {} //~ ERROR: uses type `str`
+
+pub extern "C" fn string_return() -> String { //~ ERROR: uses type `std::string::String`
+    String::new()
+}
+
+pub extern "C" fn tuple_type(p: (i32, i32)) {} //~ ERROR: uses type `(i32, i32)`
+
+pub extern "C" fn nested_fn_ptr(f: extern "C" fn(char)) {} //~ ERROR: uses type `char`
+
+pub extern "C" fn good(x: u32, p: *const u8, f: Option<extern "C" fn(u32)>) -> i32 {
+    0
+}
+
+pub extern "C" fn generic<T>(x: T) {}
+
+pub fn rust_abi(p: &str, s: String) {}
+
+#[repr(C)]
+pub struct Handler {
+    pub id: u32,
+}
+
+impl Handler {
+    pub extern "C" fn slice_method(&self, p: &[u8]) {} //~ ERROR: uses type `[u8]`
+}
+
+fn main() {}
@@ -0,0 +1,52 @@
+#![feature(exclusive_range_pattern)]
+#![deny(overlapping_patterns)]
+#![deny(unreachable_patterns)]
+
+fn main() {
+    match 0u8 {
+        0 ..= 10 => {}
+        10 ..= 20 => {} //~ ERROR multiple patterns covering the same range
+        _ => {}
+    }
+
+    match 0u8 {
+        0 .. 10 => {}
+        5 ..= 20 => {} //~ ERROR multiple patterns covering the same range
+        _ => {}
+    }
+
+    match 0i8 {
+        0 ..= 10 => {}
+        -10 ..= 0 => {} //~ ERROR multiple patterns covering the same range
+        _ => {}
+    }
+
+    match 'a' {
+        'a' ..= 'm' => {}
+        'm' ..= 'z' => {} //~ ERROR multiple patterns covering the same range
+        _ => {}
+    }
+
+    // Neither adjacent nor nested ranges overlap on an endpoint.
+    match 0u8 {
+        0 ..= 10 => {}
+        11 ..= 20 => {}
+        0 ..= 30 => {}
+        25 ..= 30 => {} //~ ERROR unreachable pattern
+        _ => {}
+    }
+
+    // Single values are not reported.
+    match 0u8 {
+        20 => {}
+        10 ..= 20 => {}
+        _ => {}
+    }
+
+    // Guarded arms may intentionally overlap with later ones.
+    match 0u8 {
+        0 ..= 10 if true => {}
+        10 ..= 20 => {}
+        _ => {}
+    }
+}
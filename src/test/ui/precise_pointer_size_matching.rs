@@ -9,6 +9,7 @@
 #![feature(exclusive_range_pattern)]
 
 #![deny(unreachable_patterns)]
+#![allow(overlapping_patterns)]
 
 use std::{usize, isize};
 
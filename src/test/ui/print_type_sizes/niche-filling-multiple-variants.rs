@@ -0,0 +1,32 @@
+// compile-flags: -Z print-type-sizes
+// build-pass (FIXME(62277): could be check-pass?)
+// ignore-pass
+// ^-- needed because `--pass check` does not emit the output needed.
+//     FIXME: consider using an attribute instead of side-effects.
+
+// This file illustrates niche-filling enums where more than one variant
+// carries data: the other variants' fields are placed around the niche
+// of the largest variant, and the layout is used when it is no larger
+// than the tagged one.
+
+#![feature(start)]
+#![allow(dead_code)]
+
+pub enum Message {
+    A(u32, bool),
+    B(u32),
+    C,
+}
+
+pub enum Packet {
+    Data(bool, [u8; 4]),
+    Raw([u8; 4]),
+    Empty,
+}
+
+#[start]
+fn start(_: isize, _: *const *const u8) -> isize {
+    let _x: Message = Message::C;
+    let _y: Packet = Packet::Empty;
+    0
+}
@@ -0,0 +1,17 @@
+// force-host
+// no-prefer-dynamic
+
+#![crate_type = "proc-macro"]
+
+extern crate proc_macro;
+use proc_macro::TokenStream;
+use std::io::{self, Write};
+
+#[proc_macro]
+pub fn print_to_stdout(input: TokenStream) -> TokenStream {
+    println!("printed with println!");
+    let mut stdout = io::stdout();
+    stdout.write_all(b"written to io::stdout()\n").unwrap();
+    stdout.flush().unwrap();
+    input
+}
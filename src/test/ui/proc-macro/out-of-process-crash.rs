@@ -0,0 +1,11 @@
+// Test that a proc macro taking down its host process is reported as an error.
+
+// aux-build:abort-macro.rs
+// compile-flags: -Z out-of-process-proc-macros
+// normalize-stderr-test "host exited with .*" -> "host exited with $$STATUS"
+
+extern crate abort_macro;
+
+abort_macro::abort!(); //~ ERROR proc macro failed to run
+
+fn main() {}
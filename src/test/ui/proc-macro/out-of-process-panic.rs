@@ -0,0 +1,11 @@
+// aux-build:test-macros.rs
+// compile-flags: -Z out-of-process-proc-macros
+
+#[macro_use]
+extern crate test_macros;
+
+#[derive(Panic)]
+//~^ ERROR: proc-macro derive panicked
+struct Foo;
+
+fn main() {}
@@ -0,0 +1,15 @@
+// check-pass
+// aux-build:print-macro.rs
+// compile-flags: -Z out-of-process-proc-macros
+
+// Output of proc macros must not get mixed up with the messages between the
+// compiler and the proc macro host, which uses the host's stdout.
+
+#[macro_use]
+extern crate print_macro;
+
+print_to_stdout!(fn foo() {});
+
+fn main() {
+    foo();
+}
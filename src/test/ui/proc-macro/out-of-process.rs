@@ -0,0 +1,19 @@
+// check-pass
+// aux-build:test-macros.rs
+// compile-flags: -Z out-of-process-proc-macros
+
+#[macro_use]
+extern crate test_macros;
+
+#[derive(Identity, Empty)]
+#[identity_helper]
+struct Foo;
+
+#[identity_attr]
+fn bar() -> Foo {
+    identity!(Foo)
+}
+
+fn main() {
+    recollect!(bar());
+}
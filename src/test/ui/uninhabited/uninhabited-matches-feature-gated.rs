@@ -1,4 +1,4 @@
-#![allow(deprecated)]
+#![allow(deprecated, invalid_value)]
 
 enum Void {}
 